@@ -1,18 +1,37 @@
-use crate::shapes::{DrawShape, ToolType};
-use egui::{Color32, ColorImage, Pos2, Rect, Stroke, TextureHandle};
+use crate::effects;
+use crate::render;
+use crate::session::Session;
+use crate::shapes::{
+    self, ArrowStyle, DrawShape, Layer, LayerImage, NumberStyle, PaintContext, SpotlightHole, ToolType,
+    DEFAULT_BADGE_RADIUS, DEFAULT_MAGNIFICATION, DEFAULT_SMOOTHING, DEFAULT_SPOTLIGHT_OPACITY,
+};
+use crate::stamps::{self, Stamp};
+use crate::style::{DashPattern, LineCap, LineJoin, ShapeStyle};
+use crate::svg;
+use crate::transform::ImageOp;
+use egui::epaint::text::Fonts;
+use egui::{Color32, ColorImage, Pos2, Rect, TextureHandle, TextureId};
+use std::collections::HashMap;
+use std::sync::Arc;
 
 /// 캔버스 상태
 pub struct Canvas {
-    /// 배경 이미지 (캡처된 화면)
-    pub background_image: Option<ColorImage>,
+    /// 배경 이미지 (캡처된 화면, 실행 취소 상태와 공유)
+    pub background_image: Option<Arc<ColorImage>>,
     pub background_texture: Option<TextureHandle>,
 
-    /// 그려진 도형들
-    pub shapes: Vec<DrawShape>,
+    /// 배경 텍스처에 반영된 효과(스포트라이트)의 해시
+    background_key: u64,
+
+    /// 이미지 레이어 ID별 텍스처
+    layer_textures: HashMap<u64, TextureHandle>,
+
+    /// 그려진 도형들 (아래 레이어부터 위 레이어 순서)
+    pub layers: Vec<Layer>,
 
     /// 실행 취소/다시 실행 스택
-    pub undo_stack: Vec<Vec<DrawShape>>,
-    pub redo_stack: Vec<Vec<DrawShape>>,
+    pub undo_stack: Vec<CanvasState>,
+    pub redo_stack: Vec<CanvasState>,
 
     /// 현재 그리기 중인 도형
     pub current_shape: Option<DrawShape>,
@@ -24,77 +43,375 @@ pub struct Canvas {
     pub current_color: Color32,
     pub current_stroke_width: f32,
 
-    /// 번호 카운터
+    /// 채우기 색 (None이면 채우지 않음), 불투명도
+    pub current_fill: Option<Color32>,
+    pub current_opacity: f32,
+
+    /// 점선, 선 끝, 꺾임 모양, 사각형 모서리 둥글기
+    pub current_dash: DashPattern,
+    pub current_line_cap: LineCap,
+    pub current_line_join: LineJoin,
+    pub current_corner_radius: f32,
+
+    /// 곡선 부드럽게 하기 강도
+    pub current_smoothing: f32,
+
+    /// 화살표 모양
+    pub current_arrow_style: ArrowStyle,
+
+    /// 스포트라이트 구멍 모양, 어둡기, 흑백 여부
+    pub spotlight_ellipse: bool,
+    pub spotlight_opacity: f32,
+    pub spotlight_desaturate: bool,
+
+    /// 드래그로 그리는 중인 스포트라이트 구멍 (도형 인덱스, 구멍 인덱스)
+    active_spotlight_hole: Option<(usize, usize)>,
+
+    /// 돋보기 확대 배율과 원형 여부
+    pub magnifier_zoom: f32,
+    pub magnifier_circular: bool,
+
+    /// 선택된 스탬프와 이미 만든 스탬프 이미지 (같은 스탬프는 텍스처 공유)
+    pub current_stamp: Stamp,
+    stamp_images: Vec<(Stamp, LayerImage)>,
+
+    /// 스포이트로 추출한 색과 평균 영역 크기 (N×N 픽셀)
+    pub sampled_color: Option<Color32>,
+    pub sample_size: usize,
+
+    /// 화면 표시 배율 (핸들 크기를 화면 픽셀 기준으로 유지하기 위해 사용)
+    pub view_scale: f32,
+
+    /// 곡선 그리기 중 마지막 기준점 이후 생략된 원본 점들
+    curve_skipped: Vec<Pos2>,
+
+    /// 번호 카운터 (다음 배지에 붙을 번호)
     pub number_counter: u32,
 
+    /// 번호 배지 시작 값, 표기 방식, 크기
+    pub number_start: u32,
+    pub number_style: NumberStyle,
+    pub number_radius: f32,
+
     /// 텍스트 입력 상태
     pub text_input: String,
     pub text_input_pos: Option<Pos2>,
 
+    /// 텍스트를 다시 편집 중인 기존 도형 인덱스
+    pub editing_shape_index: Option<usize>,
+
     /// 드래그 시작 위치
     pub drag_start: Option<Pos2>,
 
     /// 선택된 도형 인덱스
     pub selected_shape_index: Option<usize>,
+
+    /// 드래그 중인 선택 도형의 핸들 인덱스
+    pub active_handle: Option<usize>,
+
+    /// 선택 도형 이동 중 마지막 마우스 위치
+    move_last_pos: Option<Pos2>,
+
+    /// 현재 핸들/이동 편집에 대해 실행 취소 상태를 저장했는지 여부
+    edit_state_saved: bool,
+
+    /// 레이어나 배경이 바뀔 때마다 늘어나는 번호 (저장/자동 저장 여부 판단용)
+    revision: u64,
+
+    /// 자르기 도구로 고른 영역 (이미지 좌표, None이면 이미지 전체)
+    pub crop_rect: Option<Rect>,
+    crop_drag: Option<CropDrag>,
+}
+
+/// 실행 취소/다시 실행 단위
+///
+/// 배경 이미지는 자르기/회전 등으로 바뀔 때만 새로 만들어지므로 상태끼리 공유합니다.
+#[derive(Clone)]
+pub struct CanvasState {
+    pub layers: Vec<Layer>,
+    pub background: Option<Arc<ColorImage>>,
 }
 
+/// 자르기 영역 드래그 방식
+#[derive(Debug, Clone, Copy)]
+enum CropDrag {
+    /// 모서리/변 핸들 (`crop_handles` 인덱스)
+    Handle(usize),
+    /// 영역 전체 이동 (마지막 마우스 위치)
+    Move(Pos2),
+    /// 새 영역 지정 (시작 위치)
+    New(Pos2),
+}
+
+/// 핸들을 잡을 수 있는 반경 (화면 픽셀)
+pub const HANDLE_RADIUS: f32 = 6.0;
+
+/// 자르기 핸들 위치 (모서리 4개, 변 가운데 4개)
+const CROP_HANDLE_SIDES: [[i8; 2]; 8] = [[-1, -1], [1, -1], [1, 1], [-1, 1], [0, -1], [1, 0], [0, 1], [-1, 0]];
+
+/// 자르기 영역의 핸들 위치
+pub fn crop_handles(rect: Rect) -> [Pos2; 8] {
+    CROP_HANDLE_SIDES.map(|[hx, hy]| {
+        let x = match hx {
+            -1 => rect.min.x,
+            1 => rect.max.x,
+            _ => rect.center().x,
+        };
+        let y = match hy {
+            -1 => rect.min.y,
+            1 => rect.max.y,
+            _ => rect.center().y,
+        };
+        Pos2::new(x, y)
+    })
+}
+
+/// 자동 레이어 이름에 보여줄 텍스트 길이 (글자 수)
+const LAYER_NAME_PREVIEW: usize = 20;
+
 impl Canvas {
     pub fn new() -> Self {
         Self {
             background_image: None,
             background_texture: None,
-            shapes: Vec::new(),
+            background_key: 0,
+            layer_textures: HashMap::new(),
+            layers: Vec::new(),
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
             current_shape: None,
             current_tool: ToolType::Select,
             current_color: Color32::RED,
             current_stroke_width: 2.0,
+            current_fill: None,
+            current_opacity: 1.0,
+            current_dash: DashPattern::Solid,
+            current_line_cap: LineCap::Butt,
+            current_line_join: LineJoin::Miter,
+            current_corner_radius: 0.0,
+            current_smoothing: DEFAULT_SMOOTHING,
+            current_arrow_style: ArrowStyle::Filled,
+            curve_skipped: Vec::new(),
+            spotlight_ellipse: false,
+            spotlight_opacity: DEFAULT_SPOTLIGHT_OPACITY,
+            spotlight_desaturate: false,
+            active_spotlight_hole: None,
+            magnifier_zoom: DEFAULT_MAGNIFICATION,
+            magnifier_circular: false,
+            current_stamp: Stamp::Check,
+            stamp_images: Vec::new(),
+            sampled_color: None,
+            sample_size: 1,
+            view_scale: 1.0,
             number_counter: 1,
+            number_start: 1,
+            number_style: NumberStyle::Numeric,
+            number_radius: DEFAULT_BADGE_RADIUS,
             text_input: String::new(),
             text_input_pos: None,
+            editing_shape_index: None,
             drag_start: None,
             selected_shape_index: None,
+            active_handle: None,
+            move_last_pos: None,
+            edit_state_saved: false,
+            revision: 0,
+            crop_rect: None,
+            crop_drag: None,
         }
     }
 
     /// 배경 이미지 설정
     pub fn set_background(&mut self, image: ColorImage) {
-        self.background_image = Some(image);
+        self.background_image = Some(Arc::new(image));
         self.background_texture = None; // 텍스처는 다음 프레임에서 생성
+        // 새 캡처로 바뀐 내용도 저장하지 않은 변경 사항
+        self.mark_modified();
     }
 
     /// 텍스처 업데이트
+    ///
+    /// 스포트라이트는 배경 효과이므로 바뀔 때마다 효과를 적용한 배경으로 텍스처를 다시 만듭니다.
     pub fn update_texture(&mut self, ctx: &egui::Context) {
         if let Some(ref image) = self.background_image {
-            if self.background_texture.is_none() {
-                self.background_texture = Some(ctx.load_texture(
-                    "background",
-                    image.clone(),
-                    Default::default(),
-                ));
+            let key = effects::effects_key(self.visible_shapes());
+            if self.background_texture.is_none() || key != self.background_key {
+                let mut image = ColorImage::clone(image);
+                effects::apply_effects(&mut image, self.visible_shapes());
+                // 돋보기로 확대할 때 픽셀이 흐려지지 않도록 확대는 최근접 필터 사용
+                let options = egui::TextureOptions {
+                    magnification: egui::TextureFilter::Nearest,
+                    ..Default::default()
+                };
+                self.background_texture = Some(ctx.load_texture("background", image, options));
+                self.background_key = key;
             }
         }
+
+        // 이미지 레이어 텍스처 (실행 취소 스택에만 남은 레이어는 필요할 때 다시 올림)
+        let layers: Vec<&LayerImage> = self
+            .layers
+            .iter()
+            .filter_map(|layer| match &layer.shape {
+                DrawShape::Image { image, .. } => Some(image),
+                _ => None,
+            })
+            .collect();
+        self.layer_textures.retain(|id, _| layers.iter().any(|layer| layer.id == *id));
+        for layer in layers {
+            self.layer_textures.entry(layer.id).or_insert_with(|| {
+                ctx.load_texture(format!("layer-{}", layer.id), (*layer.pixels).clone(), Default::default())
+            });
+        }
+    }
+
+    /// 도형을 그릴 때 사용할 이미지 레이어 텍스처 ID
+    pub fn layer_texture_ids(&self) -> HashMap<u64, TextureId> {
+        self.layer_textures.iter().map(|(id, texture)| (*id, texture.id())).collect()
+    }
+
+    /// 이미지를 새 레이어로 삽입 (배경 중앙, 배경의 절반 크기 이내로 축소)
+    pub fn insert_image(&mut self, image: ColorImage) {
+        let Some(ref background) = self.background_image else {
+            return;
+        };
+        let bounds = Rect::from_min_size(Pos2::ZERO, background.size.map(|v| v as f32).into());
+        let size = egui::vec2(image.width() as f32, image.height() as f32);
+        let fit = (bounds.width() * 0.5 / size.x).min(bounds.height() * 0.5 / size.y).min(1.0);
+
+        self.add_shape(DrawShape::Image {
+            rect: Rect::from_center_size(bounds.center(), size * fit),
+            image: LayerImage::new(image),
+            style: ShapeStyle::outline(self.current_color, 0.0),
+        });
+        self.selected_shape_index = Some(self.layers.len() - 1);
+    }
+
+    /// 스탬프 이미지 (처음 사용할 때 한 번만 렌더링)
+    fn stamp_image(&mut self, stamp: Stamp) -> LayerImage {
+        if let Some((_, image)) = self.stamp_images.iter().find(|(s, _)| *s == stamp) {
+            return image.clone();
+        }
+        let image = LayerImage::new(stamp.render());
+        self.stamp_images.push((stamp, image.clone()));
+        image
+    }
+
+    /// 도구 모음 설정으로 만든 새 도형의 스타일
+    ///
+    /// 채워진 사각형/타원은 외곽선 없이 채우기 색(없으면 현재 색)으로 칠합니다.
+    pub fn current_style(&self) -> ShapeStyle {
+        let style = ShapeStyle {
+            stroke_color: self.current_color,
+            stroke_width: self.current_stroke_width,
+            fill_color: self.current_fill,
+            opacity: self.current_opacity,
+            dash: self.current_dash,
+            line_cap: self.current_line_cap,
+            line_join: self.current_line_join,
+            corner_radius: self.current_corner_radius,
+        };
+        match self.current_tool {
+            ToolType::FilledRect | ToolType::FilledEllipse => ShapeStyle {
+                stroke_width: 0.0,
+                fill_color: Some(self.current_fill.unwrap_or(self.current_color)),
+                ..style
+            },
+            _ => style,
+        }
+    }
+
+    /// 번호 배지/말풍선 스타일 (현재 색으로 채우고 외곽선 없음)
+    fn badge_style(&self) -> ShapeStyle {
+        ShapeStyle {
+            stroke_width: 0.0,
+            fill_color: Some(self.current_color),
+            ..self.current_style()
+        }
+    }
+
+    /// 배경 이미지에서 `pos` 픽셀을 중심으로 한 size×size 영역의 평균 색
+    ///
+    /// 이미지 밖으로 나간 부분은 제외하고, 커서가 이미지 밖이면 None을 반환합니다.
+    pub fn sample_color(&self, pos: Pos2, size: usize) -> Option<Color32> {
+        let image = self.background_image.as_ref()?;
+        let [width, height] = image.size;
+        if pos.x < 0.0 || pos.y < 0.0 || pos.x >= width as f32 || pos.y >= height as f32 {
+            return None;
+        }
+
+        let (cx, cy) = (pos.x as usize, pos.y as usize);
+        let half = size.max(1) / 2;
+        let xs = cx.saturating_sub(half)..(cx + half + 1).min(width);
+        let ys = cy.saturating_sub(half)..(cy + half + 1).min(height);
+
+        let mut sum = [0u32; 4];
+        let mut count = 0;
+        for y in ys {
+            for x in xs.clone() {
+                for (total, value) in sum.iter_mut().zip(image.pixels[y * width + x].to_array()) {
+                    *total += value as u32;
+                }
+                count += 1;
+            }
+        }
+        let [r, g, b, a] = sum.map(|total| ((total + count / 2) / count) as u8);
+        Some(Color32::from_rgba_premultiplied(r, g, b, a))
+    }
+
+    /// 모든 레이어의 도형
+    pub fn shapes(&self) -> impl Iterator<Item = &DrawShape> {
+        self.layers.iter().map(|layer| &layer.shape)
+    }
+
+    /// 숨기지 않은 레이어의 도형 (화면 표시와 내보내기에 사용)
+    pub fn visible_shapes(&self) -> impl Iterator<Item = &DrawShape> {
+        self.layers.iter().filter(|layer| layer.visible).map(|layer| &layer.shape)
     }
 
     /// 도형 추가 (undo 스택에 현재 상태 저장)
     pub fn add_shape(&mut self, shape: DrawShape) {
         self.save_state();
-        self.shapes.push(shape);
+        let kind = std::mem::discriminant(&shape);
+        let ordinal = self
+            .layers
+            .iter()
+            .filter(|layer| std::mem::discriminant(&layer.shape) == kind)
+            .map(|layer| layer.ordinal + 1)
+            .max()
+            .unwrap_or(1);
+        self.layers.push(Layer { ordinal, ..Layer::new(shape) });
         self.redo_stack.clear();
     }
 
     /// 마우스 프레스 핸들러
     pub fn on_mouse_press(&mut self, pos: Pos2) {
         self.drag_start = Some(pos);
+        self.edit_state_saved = false;
+
+        // 텍스트 입력을 기다리던 말풍선이나 편집 중인 텍스트는 취소
+        if matches!(self.current_shape, Some(DrawShape::Callout { .. })) {
+            self.current_shape = None;
+            self.text_input_pos = None;
+        }
+        if self.editing_shape_index.take().is_some() {
+            self.text_input_pos = None;
+        }
 
         match self.current_tool {
             ToolType::Select => {
-                // 도형 선택
+                // 선택된 도형의 핸들을 먼저 확인
+                if let Some(handle) = self.handle_at(pos) {
+                    self.active_handle = Some(handle);
+                    return;
+                }
+
+                // 도형 선택 (선택한 도형은 드래그로 이동, 숨기거나 잠근 레이어는 제외)
                 self.selected_shape_index = None;
-                for (i, shape) in self.shapes.iter().enumerate().rev() {
-                    if shape.contains_point(pos) {
+                for (i, layer) in self.layers.iter().enumerate().rev() {
+                    if layer.is_editable() && layer.shape.contains_point(pos) {
                         self.selected_shape_index = Some(i);
+                        self.move_last_pos = Some(pos);
                         break;
                     }
                 }
@@ -102,8 +419,7 @@ impl Canvas {
             ToolType::Rectangle | ToolType::FilledRect => {
                 self.current_shape = Some(DrawShape::Rectangle {
                     rect: Rect::from_two_pos(pos, pos),
-                    stroke: Stroke::new(self.current_stroke_width, self.current_color),
-                    filled: self.current_tool == ToolType::FilledRect,
+                    style: self.current_style(),
                 });
             }
             ToolType::Ellipse | ToolType::FilledEllipse => {
@@ -111,39 +427,124 @@ impl Canvas {
                     center: pos,
                     radius_x: 0.0,
                     radius_y: 0.0,
-                    stroke: Stroke::new(self.current_stroke_width, self.current_color),
-                    filled: self.current_tool == ToolType::FilledEllipse,
+                    style: self.current_style(),
                 });
             }
             ToolType::Arrow => {
+                let kind = self.current_arrow_style;
                 self.current_shape = Some(DrawShape::Arrow {
                     start: pos,
                     end: pos,
-                    stroke: Stroke::new(self.current_stroke_width, self.current_color),
+                    control: (kind == ArrowStyle::Curved).then_some(pos),
+                    kind,
+                    style: self.current_style(),
                 });
             }
             ToolType::Line => {
                 self.current_shape = Some(DrawShape::Line {
                     points: vec![pos],
-                    stroke: Stroke::new(self.current_stroke_width, self.current_color),
+                    style: self.current_style(),
                 });
             }
             ToolType::Curve => {
+                self.curve_skipped.clear();
                 self.current_shape = Some(DrawShape::Curve {
                     points: vec![pos],
-                    stroke: Stroke::new(self.current_stroke_width, self.current_color),
+                    smoothing: self.current_smoothing,
+                    style: self.current_style(),
                 });
             }
             ToolType::Text => {
                 self.text_input_pos = Some(pos);
                 self.text_input.clear();
             }
+            ToolType::Callout => {
+                // 누른 곳이 꼬리 위치, 놓은 곳이 말풍선 위치
+                self.current_shape = Some(DrawShape::Callout {
+                    rect: Rect::from_min_size(pos, egui::Vec2::ZERO),
+                    target: pos,
+                    text: String::new(),
+                    font_size: 16.0,
+                    style: self.badge_style(),
+                });
+            }
+            ToolType::Spotlight => {
+                let hole = SpotlightHole {
+                    rect: Rect::from_two_pos(pos, pos),
+                    ellipse: self.spotlight_ellipse,
+                };
+                // 스포트라이트는 하나의 도형에 구멍을 계속 추가
+                let existing = self
+                    .layers
+                    .iter()
+                    .rposition(|layer| layer.is_editable() && matches!(layer.shape, DrawShape::Spotlight { .. }));
+                let index = match existing {
+                    Some(index) => {
+                        self.save_state();
+                        self.redo_stack.clear();
+                        index
+                    }
+                    None => {
+                        self.add_shape(DrawShape::Spotlight {
+                            holes: Vec::new(),
+                            opacity: self.spotlight_opacity,
+                            desaturate: self.spotlight_desaturate,
+                            style: ShapeStyle {
+                                stroke_width: 0.0,
+                                ..self.current_style()
+                            },
+                        });
+                        self.layers.len() - 1
+                    }
+                };
+                // 도구 모음의 어둡게/흑백 설정은 구멍을 추가할 때마다 스포트라이트 전체에 적용
+                if let DrawShape::Spotlight { holes, opacity, desaturate, .. } = &mut self.layers[index].shape {
+                    *opacity = self.spotlight_opacity;
+                    *desaturate = self.spotlight_desaturate;
+                    holes.push(hole);
+                    self.active_spotlight_hole = Some((index, holes.len() - 1));
+                }
+            }
+            ToolType::Magnifier => {
+                self.current_shape = Some(DrawShape::Magnifier {
+                    source: Rect::from_two_pos(pos, pos),
+                    inset: Rect::from_two_pos(pos, pos),
+                    circular: self.magnifier_circular,
+                    style: self.current_style(),
+                });
+            }
+            ToolType::Stamp => {
+                let image = self.stamp_image(self.current_stamp);
+                self.add_shape(DrawShape::Image {
+                    rect: Rect::from_center_size(pos, egui::vec2(stamps::STAMP_SIZE, stamps::STAMP_SIZE)),
+                    image,
+                    style: ShapeStyle::outline(self.current_color, 0.0),
+                });
+            }
+            ToolType::Eyedropper => {
+                self.sampled_color = self.sample_color(pos, self.sample_size).or(self.sampled_color);
+            }
+            ToolType::Crop => {
+                let Some(bounds) = self.image_bounds() else {
+                    return;
+                };
+                let rect = self.crop_rect.unwrap_or(bounds);
+                let handle = crop_handles(rect)
+                    .iter()
+                    .position(|handle| handle.distance(pos) <= HANDLE_RADIUS / self.view_scale);
+                self.crop_drag = Some(match handle {
+                    Some(handle) => CropDrag::Handle(handle),
+                    None if rect.contains(pos) => CropDrag::Move(pos),
+                    None => CropDrag::New(pos.clamp(bounds.min, bounds.max).round()),
+                });
+            }
             ToolType::Number => {
                 self.add_shape(DrawShape::Number {
                     center: pos,
                     number: self.number_counter,
-                    color: self.current_color,
-                    radius: 15.0,
+                    radius: self.number_radius,
+                    format: self.number_style,
+                    style: self.badge_style(),
                 });
                 self.number_counter += 1;
             }
@@ -153,7 +554,48 @@ impl Canvas {
 
     /// 마우스 드래그 핸들러
     pub fn on_mouse_drag(&mut self, pos: Pos2) {
+        // 스포이트는 드래그하는 동안 계속 색을 추출
+        if self.current_tool == ToolType::Eyedropper {
+            self.sampled_color = self.sample_color(pos, self.sample_size).or(self.sampled_color);
+            return;
+        }
+
+        if self.current_tool == ToolType::Crop {
+            self.drag_crop(pos);
+            return;
+        }
+
+        // 선택된 도형의 핸들 이동 또는 도형 전체 이동
+        if let Some(index) = self.selected_shape_index {
+            if self.active_handle.is_some() || self.move_last_pos.is_some() {
+                if !self.layers.get(index).is_some_and(Layer::is_editable) {
+                    return;
+                }
+                if !self.edit_state_saved {
+                    self.save_state();
+                    self.redo_stack.clear();
+                    self.edit_state_saved = true;
+                }
+                let shape = &mut self.layers[index].shape;
+                if let Some(handle) = self.active_handle {
+                    shape.move_handle(handle, pos);
+                } else if let Some(last) = self.move_last_pos.replace(pos) {
+                    shape.translate(pos - last);
+                }
+                return;
+            }
+        }
+
+        // 스포트라이트 구멍 크기 조절
+        if let (Some((index, hole)), Some(start)) = (self.active_spotlight_hole, self.drag_start) {
+            if let Some(DrawShape::Spotlight { holes, .. }) = self.layers.get_mut(index).map(|layer| &mut layer.shape) {
+                holes[hole].rect = Rect::from_two_pos(start, pos);
+            }
+            return;
+        }
+
         if let Some(start) = self.drag_start {
+            let bounds = self.image_bounds().unwrap_or(Rect::EVERYTHING);
             match &mut self.current_shape {
                 Some(DrawShape::Rectangle { rect, .. }) => {
                     *rect = Rect::from_two_pos(start, pos);
@@ -163,17 +605,29 @@ impl Canvas {
                     *radius_x = (pos.x - start.x).abs() / 2.0;
                     *radius_y = (pos.y - start.y).abs() / 2.0;
                 }
-                Some(DrawShape::Arrow { end, .. }) => {
+                Some(DrawShape::Arrow { start, end, control, .. }) => {
                     *end = pos;
+                    // 그리는 동안 곡선 화살표의 제어점은 기본 위치를 따라감
+                    if let Some(control) = control {
+                        *control = shapes::default_arrow_control(*start, *end);
+                    }
                 }
                 Some(DrawShape::Line { points, .. }) => {
                     if let Some(last) = points.last_mut() {
                         *last = pos;
                     }
                 }
-                Some(DrawShape::Curve { points, .. }) => {
-                    // 곡선은 계속 점을 추가
-                    points.push(pos);
+                Some(DrawShape::Curve { points, smoothing, .. }) => {
+                    let epsilon = shapes::simplify_epsilon(*smoothing);
+                    Self::push_curve_point(points, &mut self.curve_skipped, pos, epsilon);
+                }
+                Some(DrawShape::Callout { rect, .. }) => {
+                    *rect = Rect::from_min_size(pos, egui::Vec2::ZERO);
+                }
+                Some(DrawShape::Magnifier { source, inset, .. }) => {
+                    // 원본 영역을 고르는 동안 확대 영역은 옆에 자동 배치
+                    *source = Rect::from_two_pos(start, pos);
+                    *inset = shapes::default_magnifier_inset(*source, self.magnifier_zoom, bounds);
                 }
                 _ => {}
             }
@@ -182,74 +636,531 @@ impl Canvas {
 
     /// 마우스 릴리즈 핸들러
     pub fn on_mouse_release(&mut self) {
-        if let Some(shape) = self.current_shape.take() {
+        // 드래그 중의 변경은 실행 취소 상태를 처음에 한 번만 저장하므로 끝날 때 다시 표시
+        if self.edit_state_saved || self.active_spotlight_hole.is_some() {
+            self.mark_modified();
+        }
+        self.drag_start = None;
+        self.active_handle = None;
+        self.move_last_pos = None;
+
+        // 너무 작은 자르기 영역은 취소 (이미지 전체)
+        if self.crop_drag.take().is_some() {
+            if self.crop_rect.is_some_and(|rect| rect.width() < 1.0 || rect.height() < 1.0) {
+                self.crop_rect = None;
+            }
+            return;
+        }
+
+        // 너무 작은 스포트라이트 구멍은 취소
+        if let Some((index, hole)) = self.active_spotlight_hole.take() {
+            if let Some(DrawShape::Spotlight { holes, .. }) = self.layers.get(index).map(|layer| &layer.shape) {
+                let rect = holes[hole].rect;
+                if rect.width() <= 2.0 || rect.height() <= 2.0 {
+                    if let Some(state) = self.undo_stack.pop() {
+                        self.restore(state);
+                    }
+                }
+            }
+            return;
+        }
+
+        // 말풍선은 텍스트를 입력받은 뒤에 추가
+        if let Some(DrawShape::Callout { rect, target, .. }) = &mut self.current_shape {
+            // 클릭만 한 경우 말풍선을 대상의 오른쪽 위에 배치
+            if rect.min.distance(*target) < 20.0 {
+                *rect = Rect::from_min_size(*target + egui::vec2(30.0, -50.0), egui::Vec2::ZERO);
+            }
+            self.text_input_pos = Some(rect.min);
+            self.text_input.clear();
+            self.editing_shape_index = None;
+            return;
+        }
+
+        if let Some(mut shape) = self.current_shape.take() {
+            // 곡선은 마지막으로 전체 단순화를 한 번 더 수행
+            if let DrawShape::Curve { points, smoothing, .. } = &mut shape {
+                *points = shapes::simplify_points(points, shapes::simplify_epsilon(*smoothing));
+                self.curve_skipped.clear();
+            }
+
             // 최소 크기 체크
             let bounds = shape.bounding_rect();
             if bounds.width() > 1.0 || bounds.height() > 1.0 {
                 self.add_shape(shape);
             }
         }
-        self.drag_start = None;
+    }
+
+    /// 자르기 영역 드래그 (이미지 밖으로 나가지 않고 픽셀 단위로 맞춤)
+    fn drag_crop(&mut self, pos: Pos2) {
+        let (Some(bounds), Some(drag)) = (self.image_bounds(), self.crop_drag) else {
+            return;
+        };
+        let pos = pos.clamp(bounds.min, bounds.max).round();
+        let rect = self.crop_rect.unwrap_or(bounds);
+        let rect = match drag {
+            CropDrag::Handle(handle) => {
+                // 핸들 위치에 따라 움직이는 변 (-1: 왼쪽/위, 1: 오른쪽/아래, 0: 그대로)
+                let [hx, hy] = CROP_HANDLE_SIDES[handle];
+                let mut rect = rect;
+                match hx {
+                    -1 => rect.min.x = pos.x,
+                    1 => rect.max.x = pos.x,
+                    _ => {}
+                }
+                match hy {
+                    -1 => rect.min.y = pos.y,
+                    1 => rect.max.y = pos.y,
+                    _ => {}
+                }
+                Rect::from_two_pos(rect.min, rect.max)
+            }
+            CropDrag::Move(last) => {
+                self.crop_drag = Some(CropDrag::Move(pos));
+                // 이미지 안에 머물도록 이동량 제한
+                let delta = (pos - last).clamp(bounds.min - rect.min, bounds.max - rect.max);
+                rect.translate(delta)
+            }
+            CropDrag::New(start) => Rect::from_two_pos(start, pos),
+        };
+        self.crop_rect = Some(rect);
+    }
+
+    /// 선택된 도형에서 해당 위치에 있는 핸들 찾기
+    fn handle_at(&self, pos: Pos2) -> Option<usize> {
+        let layer = self.layers.get(self.selected_shape_index?)?;
+        if !layer.is_editable() {
+            return None;
+        }
+        layer
+            .shape
+            .handles()
+            .iter()
+            .position(|handle| handle.distance(pos) <= HANDLE_RADIUS / self.view_scale)
+    }
+
+    /// 곡선에 새 점 추가 (입력과 동시에 단순화)
+    ///
+    /// 마지막 기준점에서 새 점까지의 선분이 그동안 생략된 점들을
+    /// 허용 오차 안에서 대표하면 끝점만 옮기고, 아니면 끝점을 기준점으로 확정합니다.
+    fn push_curve_point(points: &mut Vec<Pos2>, skipped: &mut Vec<Pos2>, pos: Pos2, epsilon: f32) {
+        if points.len() < 2 || epsilon <= 0.0 {
+            points.push(pos);
+            return;
+        }
+
+        let anchor = points[points.len() - 2];
+        let tail = points[points.len() - 1];
+        let representable = skipped
+            .iter()
+            .chain(std::iter::once(&tail))
+            .all(|p| shapes::distance_to_segment(*p, anchor, pos) <= epsilon);
+
+        if representable {
+            skipped.push(tail);
+            *points.last_mut().unwrap() = pos;
+        } else {
+            skipped.clear();
+            points.push(pos);
+        }
+    }
+
+    /// 텍스트나 말풍선을 더블 클릭해 내용 다시 편집
+    pub fn begin_text_edit(&mut self, pos: Pos2) {
+        let hit = self.layers.iter_mut().enumerate().rev().find_map(|(i, layer)| {
+            if !layer.is_editable() || !layer.shape.contains_point(pos) {
+                return None;
+            }
+            layer.shape.text_mut().map(|text| (i, text.clone()))
+        });
+
+        if let Some((index, text)) = hit {
+            self.selected_shape_index = Some(index);
+            self.editing_shape_index = Some(index);
+            self.text_input = text;
+            self.text_input_pos = Some(pos);
+        }
     }
 
     /// 텍스트 입력 완료
-    pub fn finish_text_input(&mut self) {
+    pub fn finish_text_input(&mut self, fonts: &Fonts) {
+        // 기존 도형의 텍스트 수정
+        if let Some(index) = self.editing_shape_index.take() {
+            self.text_input_pos = None;
+            let text = std::mem::take(&mut self.text_input);
+            if text.is_empty() || index >= self.layers.len() {
+                return;
+            }
+            self.save_state();
+            self.redo_stack.clear();
+            let shape = &mut self.layers[index].shape;
+            if let Some(current) = shape.text_mut() {
+                *current = text;
+            }
+            shape.fit_to_text(fonts);
+            return;
+        }
+
+        // 새 말풍선 완성
+        if let Some(DrawShape::Callout { .. }) = self.current_shape {
+            self.text_input_pos = None;
+            let mut callout = self.current_shape.take().unwrap();
+            let text = std::mem::take(&mut self.text_input);
+            if !text.is_empty() {
+                if let Some(current) = callout.text_mut() {
+                    *current = text;
+                }
+                callout.fit_to_text(fonts);
+                self.add_shape(callout);
+            }
+            return;
+        }
+
         if let Some(pos) = self.text_input_pos.take() {
             if !self.text_input.is_empty() {
                 self.add_shape(DrawShape::Text {
                     pos,
                     text: self.text_input.clone(),
-                    color: self.current_color,
                     font_size: 16.0,
+                    style: ShapeStyle {
+                        fill_color: self.current_fill,
+                        ..ShapeStyle::outline(self.current_color, 1.0)
+                    },
                 });
                 self.text_input.clear();
             }
         }
     }
 
+    /// 선택된 도형을 속성 패널에서 수정한 값으로 교체
+    ///
+    /// `merge`가 true이고 직전 편집이 이어지는 중이면 실행 취소 상태를 새로 저장하지 않습니다.
+    pub fn update_selected_shape(&mut self, shape: DrawShape, merge: bool) {
+        let Some(index) = self.selected_shape_index.filter(|&i| self.layers.get(i).is_some_and(|layer| !layer.locked)) else {
+            return;
+        };
+        if !merge || !self.edit_state_saved {
+            self.save_state();
+            self.redo_stack.clear();
+            self.edit_state_saved = true;
+        }
+        let renumbered = matches!(
+            (&self.layers[index].shape, &shape),
+            (DrawShape::Number { number: a, .. }, DrawShape::Number { number: b, .. }) if a != b
+        );
+        self.layers[index].shape = shape;
+        self.mark_modified();
+        if renumbered {
+            self.sync_number_counter();
+        }
+    }
+
     /// 실행 취소
     pub fn undo(&mut self) {
+        self.edit_state_saved = false;
         if let Some(state) = self.undo_stack.pop() {
-            self.redo_stack.push(self.shapes.clone());
-            self.shapes = state;
+            self.redo_stack.push(self.snapshot());
+            self.restore(state);
+            self.sync_number_counter();
         }
     }
 
     /// 다시 실행
     pub fn redo(&mut self) {
+        self.edit_state_saved = false;
         if let Some(state) = self.redo_stack.pop() {
-            self.undo_stack.push(self.shapes.clone());
-            self.shapes = state;
+            self.undo_stack.push(self.snapshot());
+            self.restore(state);
+            self.sync_number_counter();
+        }
+    }
+
+    /// 번호 시작 값 변경 (배지가 없을 때만 카운터에 바로 반영)
+    pub fn set_number_start(&mut self, start: u32) {
+        if self.number_start == start {
+            return;
+        }
+        self.number_start = start;
+        if !self.shapes().any(|s| matches!(s, DrawShape::Number { .. })) {
+            self.number_counter = start;
+        }
+    }
+
+    /// 모든 번호 배지를 도형 순서대로 시작 값부터 다시 매김
+    pub fn renumber_badges(&mut self) {
+        let mut next = self.number_start;
+        for layer in &mut self.layers {
+            if let DrawShape::Number { number, .. } = &mut layer.shape {
+                *number = next;
+                next += 1;
+            }
+        }
+        self.number_counter = next;
+    }
+
+    /// 번호 다시 매기기 (실행 취소 가능)
+    pub fn renumber_badges_with_undo(&mut self) {
+        let mut expected = self.number_start;
+        let in_order = self.shapes().all(|s| match s {
+            DrawShape::Number { number, .. } => {
+                let ok = *number == expected;
+                expected += 1;
+                ok
+            }
+            _ => true,
+        });
+        if !in_order {
+            self.save_state();
+            self.redo_stack.clear();
         }
+        self.renumber_badges();
+    }
+
+    /// 도형 목록에 맞춰 다음 번호 갱신
+    fn sync_number_counter(&mut self) {
+        self.number_counter = self
+            .shapes()
+            .filter_map(|s| match s {
+                DrawShape::Number { number, .. } => Some(*number + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(self.number_start);
+    }
+
+    /// 내용이 바뀐 것으로 표시
+    pub fn mark_modified(&mut self) {
+        self.revision += 1;
+    }
+
+    /// 내용이 바뀔 때마다 늘어나는 번호
+    pub fn revision(&self) -> u64 {
+        self.revision
     }
 
     /// 현재 상태 저장
     fn save_state(&mut self) {
-        self.undo_stack.push(self.shapes.clone());
+        self.mark_modified();
+        self.undo_stack.push(self.snapshot());
         // 최대 50개 상태만 유지
         if self.undo_stack.len() > 50 {
             self.undo_stack.remove(0);
         }
     }
 
+    /// 실행 취소 스택에 저장할 현재 상태
+    fn snapshot(&self) -> CanvasState {
+        CanvasState {
+            layers: self.layers.clone(),
+            background: self.background_image.clone(),
+        }
+    }
+
+    /// 저장된 상태로 되돌림 (배경이 바뀌었으면 텍스처도 다시 만듦)
+    fn restore(&mut self, state: CanvasState) {
+        self.mark_modified();
+        self.layers = state.layers;
+        let same_background = match (&self.background_image, &state.background) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        if !same_background {
+            self.background_image = state.background;
+            self.background_texture = None;
+            self.crop_rect = None;
+        }
+        if self.selected_shape_index.is_some_and(|i| i >= self.layers.len()) {
+            self.selected_shape_index = None;
+        }
+    }
+
+    /// 배경 이미지를 자르기/크기 조절/회전/반전하고 모든 도형을 같이 변환 (실행 취소 가능)
+    pub fn transform_image(&mut self, op: ImageOp) -> Result<[usize; 2], String> {
+        let background = self.background_image.clone().ok_or("이미지가 없습니다")?;
+        let (image, transform) = op.apply(&background).ok_or("결과 이미지가 비어 있습니다")?;
+
+        self.save_state();
+        self.redo_stack.clear();
+        for layer in &mut self.layers {
+            layer.shape.transform(&transform);
+        }
+
+        // 진행 중이던 그리기/편집은 좌표가 맞지 않으므로 취소
+        self.current_shape = None;
+        self.active_spotlight_hole = None;
+        self.text_input_pos = None;
+        self.editing_shape_index = None;
+        self.crop_rect = None;
+        self.crop_drag = None;
+
+        let size = image.size;
+        self.background_image = Some(Arc::new(image));
+        self.background_texture = None;
+        Ok(size)
+    }
+
+    /// 현재 배경과 레이어를 세션으로 저장 (배경이 없으면 None)
+    pub fn to_session(&self) -> Option<Session> {
+        let background = self.background_image.clone()?;
+        let mut session = Session::new(background, self.layers.clone());
+        session.number_start = Some(self.number_start);
+        session.next_number = Some(self.number_counter);
+        Some(session)
+    }
+
+    /// 현재 캡처와 보이는 도형을 SVG 문서로 변환 (배경 이미지가 없으면 None)
+    pub fn export_svg(&self) -> Option<Result<String, String>> {
+        let background = self.background_image.as_ref()?;
+        let shapes: Vec<&DrawShape> = self.visible_shapes().collect();
+        Some(svg::export_svg(background, &shapes, &render::export_fonts()))
+    }
+
+    /// 세션 불러오기 (실행 취소 기록과 진행 중인 편집은 모두 초기화)
+    pub fn load_session(&mut self, session: Session) {
+        self.background_image = Some(session.background);
+        self.background_texture = None;
+        self.layers = session.layers;
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.selected_shape_index = None;
+        self.current_shape = None;
+        self.active_spotlight_hole = None;
+        self.text_input_pos = None;
+        self.editing_shape_index = None;
+        self.crop_rect = None;
+        self.crop_drag = None;
+        if let Some(start) = session.number_start {
+            self.number_start = start;
+        }
+        match session.next_number {
+            Some(next) => self.number_counter = next,
+            None => self.sync_number_counter(),
+        }
+        self.mark_modified();
+    }
+
+    /// 이미지 전체 영역 (이미지 좌표)
+    pub fn image_bounds(&self) -> Option<Rect> {
+        let image = self.background_image.as_ref()?;
+        Some(Rect::from_min_size(Pos2::ZERO, image.size.map(|v| v as f32).into()))
+    }
+
     /// 선택된 도형 삭제
     pub fn delete_selected(&mut self) {
         if let Some(index) = self.selected_shape_index {
-            if index < self.shapes.len() {
+            if self.layers.get(index).is_some_and(Layer::is_editable) {
                 self.save_state();
-                self.shapes.remove(index);
+                let removed = self.layers.remove(index).shape;
                 self.selected_shape_index = None;
                 self.redo_stack.clear();
+
+                // 배지를 지우면 빈 번호가 생기지 않도록 다시 매김
+                if matches!(removed, DrawShape::Number { .. }) {
+                    self.renumber_badges();
+                }
             }
         }
     }
 
+    /// 레이어 목록에 표시할 이름 (지정한 이름이 없으면 종류와 내용으로 자동 생성)
+    pub fn layer_name(&self, index: usize) -> String {
+        let Some(layer) = self.layers.get(index) else {
+            return String::new();
+        };
+        if let Some(name) = &layer.name {
+            return name.clone();
+        }
+        let label = layer.shape.type_label();
+        match &layer.shape {
+            DrawShape::Text { text, .. } | DrawShape::Callout { text, .. } => {
+                let line = text.lines().next().unwrap_or_default();
+                let mut preview: String = line.chars().take(LAYER_NAME_PREVIEW).collect();
+                if line.chars().count() > LAYER_NAME_PREVIEW {
+                    preview.push('…');
+                }
+                format!("{}: {}", label, preview)
+            }
+            DrawShape::Number { number, format, .. } => format!("{} {}", label, format.format(*number)),
+            _ => format!("{} {}", label, layer.ordinal),
+        }
+    }
+
+    /// 레이어 선택 (레이어 패널에서 클릭)
+    pub fn select_layer(&mut self, index: usize) {
+        if index < self.layers.len() {
+            self.selected_shape_index = Some(index);
+            self.edit_state_saved = false;
+        }
+    }
+
+    /// 레이어 표시/숨기기 (실행 취소 가능)
+    pub fn set_layer_visible(&mut self, index: usize, visible: bool) {
+        self.edit_layer(index, |layer| layer.visible = visible);
+    }
+
+    /// 레이어 잠금/해제 (실행 취소 가능)
+    pub fn set_layer_locked(&mut self, index: usize, locked: bool) {
+        self.edit_layer(index, |layer| layer.locked = locked);
+    }
+
+    /// 레이어 이름 변경 (빈 이름이면 자동 이름으로 되돌림)
+    pub fn rename_layer(&mut self, index: usize, name: &str) {
+        let name = name.trim();
+        let name = (!name.is_empty()).then(|| name.to_string());
+        if self.layers.get(index).is_some_and(|layer| layer.name != name) {
+            self.edit_layer(index, |layer| layer.name = name);
+        }
+    }
+
+    /// 레이어 순서 변경 (`to`는 이동 후 인덱스)
+    pub fn move_layer(&mut self, from: usize, to: usize) {
+        if from == to || from >= self.layers.len() || to >= self.layers.len() {
+            return;
+        }
+        self.save_state();
+        self.redo_stack.clear();
+        let layer = self.layers.remove(from);
+        let is_badge = matches!(layer.shape, DrawShape::Number { .. });
+        self.layers.insert(to, layer);
+
+        // 선택은 같은 레이어를 따라감
+        self.selected_shape_index = self.selected_shape_index.map(|selected| {
+            if selected == from {
+                to
+            } else if from < selected && selected <= to {
+                selected - 1
+            } else if to <= selected && selected < from {
+                selected + 1
+            } else {
+                selected
+            }
+        });
+
+        // 배지 순서가 바뀌면 번호도 위치에 맞게 다시 매김
+        if is_badge {
+            self.renumber_badges();
+        }
+    }
+
+    /// 레이어 속성 변경 (실행 취소 가능)
+    fn edit_layer(&mut self, index: usize, edit: impl FnOnce(&mut Layer)) {
+        if index >= self.layers.len() {
+            return;
+        }
+        self.save_state();
+        self.redo_stack.clear();
+        edit(&mut self.layers[index]);
+    }
+
     /// 모든 도형 지우기
+    #[allow(dead_code)]
     pub fn clear_all(&mut self) {
-        if !self.shapes.is_empty() {
+        if !self.layers.is_empty() {
             self.save_state();
-            self.shapes.clear();
+            self.layers.clear();
             self.redo_stack.clear();
+            self.number_counter = self.number_start;
         }
     }
 
@@ -258,13 +1169,32 @@ impl Canvas {
         // 배경 이미지가 있어야 함
         let bg_image = self.background_image.as_ref()?;
 
-        // 새 이미지 생성 (배경 복사)
-        let mut result = bg_image.clone();
+        // 새 이미지 생성 (배경 복사 후 배경 효과 적용)
+        let mut background = ColorImage::clone(bg_image);
+        effects::apply_effects(&mut background, self.visible_shapes());
+        let mut result = background.clone();
+
+        // 도형들을 원본 해상도로 이미지에 그리기
+        // 이미지 레이어는 레이어 ID를 그대로 텍스처 ID로 사용
+        let mut textures = vec![(render::EXPORT_BACKGROUND_TEXTURE, &background)];
+        let mut layer_textures = HashMap::new();
+        for shape in self.visible_shapes() {
+            if let DrawShape::Image { image, .. } = shape {
+                let id = TextureId::User(image.id);
+                layer_textures.insert(image.id, id);
+                textures.push((id, &*image.pixels));
+            }
+        }
 
-        // 도형들을 이미지에 그리기
-        // 주의: 이 부분은 실제로는 더 복잡한 렌더링 로직이 필요
-        // 여기서는 간단히 배경만 반환
-        // 실제 구현에서는 egui painter를 사용하여 오프스크린 렌더링 필요
+        let fonts = render::export_fonts();
+        let ctx = PaintContext {
+            fonts: &fonts,
+            background: render::EXPORT_BACKGROUND_TEXTURE,
+            background_size: egui::vec2(background.width() as f32, background.height() as f32),
+            layer_textures: &layer_textures,
+        };
+        let shapes = self.visible_shapes().flat_map(|shape| shape.to_egui_shape(&ctx)).collect();
+        render::rasterize_shapes(&mut result, shapes, &fonts, &textures);
 
         Some(result)
     }
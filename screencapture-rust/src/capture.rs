@@ -5,6 +5,10 @@ pub struct CaptureResult {
     pub image: ImageBuffer<Rgba<u8>, Vec<u8>>,
     pub width: u32,
     pub height: u32,
+    /// 캡처할 때 맨 앞에 있던 창의 제목 (알 수 없으면 빈 문자열)
+    pub window_title: String,
+    /// 마우스 커서가 있던 모니터 이름 (알 수 없으면 빈 문자열)
+    pub monitor: String,
 }
 
 #[cfg(target_os = "windows")]
@@ -104,11 +108,59 @@ mod windows_capture {
                 image,
                 width: width as u32,
                 height: height as u32,
+                window_title: foreground_window_title(),
+                monitor: cursor_monitor_name(),
             })
         }
     }
 
+    /// 맨 앞에 있는 다른 프로그램 창의 제목 (이 앱의 창은 건너뜀)
+    fn foreground_window_title() -> String {
+        unsafe {
+            let own_process = std::process::id();
+            let mut hwnd = GetForegroundWindow();
+            while !hwnd.is_invalid() {
+                let mut process = 0u32;
+                GetWindowThreadProcessId(hwnd, Some(&mut process as *mut u32));
+                if process != own_process && IsWindowVisible(hwnd).as_bool() {
+                    let mut title = [0u16; 512];
+                    let len = GetWindowTextW(hwnd, &mut title);
+                    if len > 0 {
+                        return String::from_utf16_lossy(&title[..len as usize]);
+                    }
+                }
+                hwnd = match GetWindow(hwnd, GW_HWNDNEXT) {
+                    Ok(next) => next,
+                    Err(_) => break,
+                };
+            }
+            String::new()
+        }
+    }
+
+    /// 마우스 커서가 있는 모니터의 장치 이름 (예: "DISPLAY1")
+    fn cursor_monitor_name() -> String {
+        unsafe {
+            let mut point = POINT::default();
+            if GetCursorPos(&mut point).is_err() {
+                return String::new();
+            }
+            let monitor = MonitorFromPoint(point, MONITOR_DEFAULTTONEAREST);
+            let mut info = MONITORINFOEXW::default();
+            info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+            if !GetMonitorInfoW(monitor, &mut info.monitorInfo).as_bool() {
+                return String::new();
+            }
+            let len = info.szDevice.iter().position(|&c| c == 0).unwrap_or(info.szDevice.len());
+            // "\\.\DISPLAY1" 형식에서 앞부분 제거
+            String::from_utf16_lossy(&info.szDevice[..len])
+                .trim_start_matches(['\\', '.'])
+                .to_string()
+        }
+    }
+
     /// 가상 화면의 경계 가져오기
+    #[allow(dead_code)]
     pub fn get_virtual_screen_bounds_impl() -> (i32, i32, i32, i32) {
         unsafe {
             let x = GetSystemMetrics(SYSTEM_METRICS_INDEX(76));
@@ -138,10 +190,13 @@ mod dummy_capture {
             image,
             width,
             height,
+            window_title: String::new(),
+            monitor: String::new(),
         })
     }
 
     /// 더미 구현
+    #[allow(dead_code)]
     pub fn get_virtual_screen_bounds_impl() -> (i32, i32, i32, i32) {
         (0, 0, 1920, 1080)
     }
@@ -157,6 +212,7 @@ pub fn capture_screen() -> Result<CaptureResult, String> {
 }
 
 /// 특정 영역만 캡처
+#[allow(dead_code)]
 pub fn capture_region(x: i32, y: i32, width: i32, height: i32) -> Result<CaptureResult, String> {
     // 전체 화면을 캡처한 후 크롭
     let full_capture = capture_screen()?;
@@ -173,10 +229,13 @@ pub fn capture_region(x: i32, y: i32, width: i32, height: i32) -> Result<Capture
         image: cropped,
         width: width as u32,
         height: height as u32,
+        window_title: full_capture.window_title,
+        monitor: full_capture.monitor,
     })
 }
 
 /// 가상 화면의 경계 가져오기
+#[allow(dead_code)]
 pub fn get_virtual_screen_bounds() -> (i32, i32, i32, i32) {
     #[cfg(target_os = "windows")]
     return windows_capture::get_virtual_screen_bounds_impl();
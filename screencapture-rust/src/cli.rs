@@ -0,0 +1,31 @@
+use crate::image_format::Compression;
+
+/// 잘못된 인자를 받았을 때 출력하는 사용법
+const USAGE: &str = "Usage: screencapture-rust [--comp:quality,scale]
+  --comp:quality,scale  quality: -1 (keep setting) or 0~9 compression level,
+                        scale: 1~100 percent (default 100)";
+
+/// 명령줄 옵션 (Qt 버전과 같은 `--이름:값` 형식)
+#[derive(Debug, Default)]
+pub struct CliArgs {
+    /// `--comp:quality,scale` 저장 압축/크기 (설정보다 우선)
+    pub compression: Option<Compression>,
+}
+
+impl CliArgs {
+    /// 명령줄 인자 해석 (프로그램 이름 제외, 잘못된 인자는 사용법과 함께 오류)
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut cli = Self::default();
+        for arg in args {
+            let Some(option) = arg.strip_prefix("--") else {
+                return Err(format!("Unexpected argument: {}\n{}", arg, USAGE));
+            };
+            let (name, value) = option.split_once(':').unwrap_or((option, ""));
+            match name {
+                "comp" => cli.compression = Some(Compression::parse(value).map_err(|e| format!("{}\n{}", e, USAGE))?),
+                _ => return Err(format!("Unknown option: {}\n{}", arg, USAGE)),
+            }
+        }
+        Ok(cli)
+    }
+}
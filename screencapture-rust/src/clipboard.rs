@@ -1,3 +1,7 @@
+use crate::color_format::ColorFormat;
+use crate::image_format::{ExportOptions, ImageFormat};
+use crate::png_optimize::SizeReport;
+use crate::session::{Session, SESSION_EXTENSION};
 use arboard::{Clipboard, ImageData};
 use egui::{Color32, ColorImage};
 use image::{ImageBuffer, Rgba};
@@ -42,55 +46,144 @@ pub fn copy_text_to_clipboard(text: &str) -> Result<(), String> {
 
 /// 색상을 RGB 형식으로 복사
 pub fn copy_color_rgb(color: Color32) -> Result<(), String> {
-    let text = format!("rgb({}, {}, {})", color.r(), color.g(), color.b());
-    copy_text_to_clipboard(&text)
+    copy_color(color, ColorFormat::Rgb)
 }
 
 /// 색상을 HEX 형식으로 복사
 pub fn copy_color_hex(color: Color32) -> Result<(), String> {
-    let text = format!("#{:02X}{:02X}{:02X}", color.r(), color.g(), color.b());
-    copy_text_to_clipboard(&text)
+    copy_color(color, ColorFormat::Hex)
 }
 
 /// 색상을 CMYK 형식으로 복사
 pub fn copy_color_cmyk(color: Color32) -> Result<(), String> {
-    let r = color.r() as f32 / 255.0;
-    let g = color.g() as f32 / 255.0;
-    let b = color.b() as f32 / 255.0;
-
-    let k = 1.0 - r.max(g).max(b);
-    let c = if k < 1.0 { (1.0 - r - k) / (1.0 - k) } else { 0.0 };
-    let m = if k < 1.0 { (1.0 - g - k) / (1.0 - k) } else { 0.0 };
-    let y = if k < 1.0 { (1.0 - b - k) / (1.0 - k) } else { 0.0 };
+    copy_color(color, ColorFormat::Cmyk)
+}
 
-    let text = format!(
-        "cmyk({:.0}%, {:.0}%, {:.0}%, {:.0}%)",
-        c * 100.0,
-        m * 100.0,
-        y * 100.0,
-        k * 100.0
-    );
-    copy_text_to_clipboard(&text)
+/// 색상을 지정한 형식으로 복사
+pub fn copy_color(color: Color32, format: ColorFormat) -> Result<(), String> {
+    copy_text_to_clipboard(&format.format(color))
 }
 
 /// ColorImage를 파일로 저장
-pub fn save_image_to_file(image: &ColorImage, path: &Path) -> Result<(), String> {
-    let width = image.width() as u32;
-    let height = image.height() as u32;
+///
+/// 형식은 확장자로 정하고 (알 수 없으면 `options.format`), 품질/크기/알파 제거는 `options`를 따릅니다.
+/// PNG로 저장할 때 `session`이 있으면 비공개 청크로 넣어 두어 나중에 다시 열어 편집할 수 있습니다.
+/// PNG 최적화를 켰으면 최적화 전후 크기를 반환합니다.
+pub fn save_image_to_file(
+    image: &ColorImage,
+    path: &Path,
+    options: &ExportOptions,
+    session: Option<&Session>,
+) -> Result<Option<SizeReport>, String> {
+    let format = ImageFormat::from_path(path).unwrap_or(options.format);
+    let (mut bytes, report) = options.encode(image, format)?;
+    // 세션 청크는 최적화가 끝난 뒤에 넣어야 지워지지 않음
+    if let (ImageFormat::Png, Some(session)) = (format, session) {
+        bytes = session.embed_in_png(&bytes)?;
+    }
+    std::fs::write(path, bytes).map_err(|e| format!("Failed to save image: {}", e))?;
+    Ok(report)
+}
+
+/// ColorImage를 PNG 바이트로 압축
+pub fn encode_png(image: &ColorImage) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    color_image_to_image_buffer(image)
+        .write_to(&mut std::io::Cursor::new(&mut bytes), image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    Ok(bytes)
+}
+
+/// PNG 바이트를 ColorImage로 변환
+pub fn decode_png(bytes: &[u8]) -> Result<ColorImage, String> {
+    let image = image::load_from_memory_with_format(bytes, image::ImageFormat::Png)
+        .map_err(|e| format!("Failed to decode PNG: {}", e))?;
+    Ok(image_buffer_to_color_image(&image.to_rgba8()))
+}
+
+/// 클립보드의 이미지 가져오기
+pub fn paste_image_from_clipboard() -> Result<ColorImage, String> {
+    let mut clipboard = Clipboard::new().map_err(|e| format!("Failed to access clipboard: {}", e))?;
+
+    let image = clipboard
+        .get_image()
+        .map_err(|e| format!("No image in clipboard: {}", e))?;
+
+    Ok(ColorImage::from_rgba_unmultiplied([image.width, image.height], &image.bytes))
+}
+
+/// 이미지 파일 불러오기
+pub fn load_image_from_file(path: &Path) -> Result<ColorImage, String> {
+    let image = image::open(path).map_err(|e| format!("Failed to open image: {}", e))?;
+    Ok(image_buffer_to_color_image(&image.to_rgba8()))
+}
+
+/// 파일 대화상자로 불러올 이미지 선택
+pub fn show_open_image_dialog() -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("이미지", &["png", "jpg", "jpeg", "bmp", "gif", "webp", "tif", "tiff"])
+        .pick_file()
+}
 
-    // ColorImage를 image crate의 ImageBuffer로 변환
-    let mut img_buffer = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(width, height);
+/// 파일 대화상자로 편집할 이미지나 세션 파일 선택
+pub fn show_open_file_dialog() -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("이미지 및 세션", &[SESSION_EXTENSION, "png", "jpg", "jpeg", "bmp", "gif", "webp", "tif", "tiff"])
+        .add_filter("주석 세션", &[SESSION_EXTENSION])
+        .pick_file()
+}
+
+/// 파일 대화상자로 PDF로 합칠 세션/이미지 파일 여러 개 선택
+pub fn show_open_pages_dialog() -> Option<Vec<std::path::PathBuf>> {
+    rfd::FileDialog::new()
+        .add_filter("세션 및 이미지", &[SESSION_EXTENSION, "png", "jpg", "jpeg", "bmp", "gif", "webp", "tif", "tiff"])
+        .pick_files()
+}
 
-    for (i, pixel) in image.pixels.iter().enumerate() {
-        let x = (i % image.width()) as u32;
-        let y = (i / image.width()) as u32;
-        img_buffer.put_pixel(x, y, Rgba([pixel.r(), pixel.g(), pixel.b(), pixel.a()]));
+/// 파일 대화상자로 저장 경로 선택 (Linux는 XDG 포털 사용)
+///
+/// `filters`는 (이름, 확장자) 목록이며 첫 번째가 기본으로 선택됩니다.
+pub fn show_save_dialog(directory: &Path, file_name: &str, filters: &[(&str, &str)]) -> Option<std::path::PathBuf> {
+    let mut dialog = rfd::FileDialog::new()
+        .set_directory(directory)
+        .set_file_name(file_name);
+    for (name, extension) in filters {
+        dialog = dialog.add_filter(*name, &[*extension]);
     }
+    dialog.save_file()
+}
 
-    // 파일로 저장
-    img_buffer
-        .save(path)
-        .map_err(|e| format!("Failed to save image: {}", e))
+/// 폴더 선택 대화상자
+pub fn show_pick_folder_dialog(directory: &Path) -> Option<std::path::PathBuf> {
+    rfd::FileDialog::new().set_directory(directory).pick_folder()
+}
+
+/// 이미 있는 파일을 덮어쓸지 묻기
+pub fn confirm_overwrite(path: &Path) -> bool {
+    confirm("파일 덮어쓰기", &format!("{}\n이미 있는 파일입니다. 덮어쓸까요?", path.display()))
+}
+
+/// 예/아니요 확인 대화상자 (예를 누르면 true)
+pub fn confirm(title: &str, description: &str) -> bool {
+    let result = rfd::MessageDialog::new()
+        .set_level(rfd::MessageLevel::Warning)
+        .set_title(title)
+        .set_description(description)
+        .set_buttons(rfd::MessageButtons::YesNo)
+        .show();
+    result == rfd::MessageDialogResult::Yes
+}
+
+/// ColorImage를 image crate의 ImageBuffer로 변환
+pub fn color_image_to_image_buffer(image: &ColorImage) -> ImageBuffer<Rgba<u8>, Vec<u8>> {
+    let mut buffer = ImageBuffer::<Rgba<u8>, Vec<u8>>::new(image.width() as u32, image.height() as u32);
+
+    // Color32는 알파가 곱해진 값이므로 되돌려서 저장
+    for (pixel, color) in buffer.pixels_mut().zip(&image.pixels) {
+        *pixel = Rgba(color.to_srgba_unmultiplied());
+    }
+
+    buffer
 }
 
 /// image crate의 ImageBuffer를 ColorImage로 변환
@@ -98,9 +191,10 @@ pub fn image_buffer_to_color_image(buffer: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> C
     let width = buffer.width() as usize;
     let height = buffer.height() as usize;
 
+    // image crate는 알파가 곱해지지 않은 RGBA, Color32는 곱해진 값
     let pixels: Vec<Color32> = buffer
         .pixels()
-        .map(|p| Color32::from_rgba_premultiplied(p[0], p[1], p[2], p[3]))
+        .map(|p| Color32::from_rgba_unmultiplied(p[0], p[1], p[2], p[3]))
         .collect();
 
     ColorImage {
@@ -109,14 +203,37 @@ pub fn image_buffer_to_color_image(buffer: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> C
     }
 }
 
-/// 파일 대화상자를 통해 저장 경로 선택
-pub fn show_save_dialog() -> Option<std::path::PathBuf> {
-    use std::process::Command;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 반투명 픽셀이 있는 2x1 이미지
+    fn translucent_image() -> ColorImage {
+        ColorImage {
+            size: [2, 1],
+            pixels: vec![
+                Color32::from_rgba_unmultiplied(200, 100, 50, 128),
+                Color32::from_rgba_unmultiplied(10, 220, 30, 255),
+            ],
+        }
+    }
+
+    #[test]
+    fn saved_png_has_unmultiplied_colors() {
+        let png = encode_png(&translucent_image()).unwrap();
+        let decoded = image::load_from_memory_with_format(&png, image::ImageFormat::Png).unwrap().to_rgba8();
+        let [r, g, b, a] = decoded.get_pixel(0, 0).0;
+        // 알파가 곱해진 값(100, 50, 25)이 아니라 원래 색으로 저장되어야 다른 프로그램에서 어두워지지 않음
+        assert_eq!(a, 128);
+        assert!(r.abs_diff(200) <= 2 && g.abs_diff(100) <= 2 && b.abs_diff(50) <= 2, "{:?}", [r, g, b]);
+        assert_eq!(decoded.get_pixel(1, 0).0, [10, 220, 30, 255]);
+    }
 
-    // Windows 파일 대화상자는 실제 환경에서만 작동
-    // 여기서는 기본 경로 반환
-    let home = std::env::var("USERPROFILE").unwrap_or_else(|_| ".".to_string());
-    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let filename = format!("screenshot_{}.png", timestamp);
-    Some(std::path::PathBuf::from(home).join("Pictures").join(filename))
+    #[test]
+    fn png_round_trip_keeps_colors() {
+        let image = translucent_image();
+        let decoded = decode_png(&encode_png(&image).unwrap()).unwrap();
+        assert_eq!(decoded.size, image.size);
+        assert_eq!(decoded.pixels, image.pixels);
+    }
 }
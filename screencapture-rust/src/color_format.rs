@@ -0,0 +1,196 @@
+use egui::Color32;
+
+/// 색상 정보 복사 형식
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorFormat {
+    Rgb,        // rgb(30, 90, 200)
+    Hex,        // #1E5AC8
+    Cmyk,       // cmyk(85%, 55%, 0%, 22%)
+    Hsl,        // hsl(219, 74%, 45%)
+    Hsv,        // hsv(219, 85%, 78%)
+    Lab,        // lab(40.83, 22.92, -62.39)
+    FloatRgb,   // 0.118, 0.353, 0.784
+    HexLiteral, // 0x1E5AC8
+    Swift,      // UIColor(red: ..., green: ..., blue: ..., alpha: ...)
+    Css,        // color: rgba(30, 90, 200, 1);
+    Android,    // <color name="color_1e5ac8">#FF1E5AC8</color>
+}
+
+impl ColorFormat {
+    pub const ALL: [ColorFormat; 11] = [
+        ColorFormat::Rgb,
+        ColorFormat::Hex,
+        ColorFormat::Cmyk,
+        ColorFormat::Hsl,
+        ColorFormat::Hsv,
+        ColorFormat::Lab,
+        ColorFormat::FloatRgb,
+        ColorFormat::HexLiteral,
+        ColorFormat::Swift,
+        ColorFormat::Css,
+        ColorFormat::Android,
+    ];
+
+    /// 메뉴에 표시할 이름
+    pub fn label(self) -> &'static str {
+        match self {
+            ColorFormat::Rgb => "RGB",
+            ColorFormat::Hex => "HEX",
+            ColorFormat::Cmyk => "CMYK",
+            ColorFormat::Hsl => "HSL",
+            ColorFormat::Hsv => "HSV",
+            ColorFormat::Lab => "CIE Lab",
+            ColorFormat::FloatRgb => "RGB (0~1)",
+            ColorFormat::HexLiteral => "0xRRGGBB",
+            ColorFormat::Swift => "Swift (UIColor)",
+            ColorFormat::Css => "CSS",
+            ColorFormat::Android => "Android (XML)",
+        }
+    }
+
+    /// 고정 단축키 (메뉴에 함께 표시)
+    pub fn shortcut(self) -> Option<&'static str> {
+        match self {
+            ColorFormat::Rgb => Some("Ctrl+R"),
+            ColorFormat::Hex => Some("Ctrl+H"),
+            ColorFormat::Cmyk => Some("Ctrl+K"),
+            _ => None,
+        }
+    }
+
+    /// 색상을 이 형식의 문자열로 변환
+    pub fn format(self, color: Color32) -> String {
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        let [rf, gf, bf, af] = [r, g, b, a].map(|v| v as f32 / 255.0);
+        match self {
+            ColorFormat::Rgb => format!("rgb({}, {}, {})", r, g, b),
+            ColorFormat::Hex => format!("#{:02X}{:02X}{:02X}", r, g, b),
+            ColorFormat::Cmyk => {
+                let k = 1.0 - rf.max(gf).max(bf);
+                let c = if k < 1.0 { (1.0 - rf - k) / (1.0 - k) } else { 0.0 };
+                let m = if k < 1.0 { (1.0 - gf - k) / (1.0 - k) } else { 0.0 };
+                let y = if k < 1.0 { (1.0 - bf - k) / (1.0 - k) } else { 0.0 };
+                format!(
+                    "cmyk({:.0}%, {:.0}%, {:.0}%, {:.0}%)",
+                    c * 100.0,
+                    m * 100.0,
+                    y * 100.0,
+                    k * 100.0
+                )
+            }
+            ColorFormat::Hsl => {
+                let (h, s, l) = to_hsl(rf, gf, bf);
+                format!("hsl({:.0}, {:.0}%, {:.0}%)", h, s * 100.0, l * 100.0)
+            }
+            ColorFormat::Hsv => {
+                let (h, s, v) = to_hsv(rf, gf, bf);
+                format!("hsv({:.0}, {:.0}%, {:.0}%)", h, s * 100.0, v * 100.0)
+            }
+            ColorFormat::Lab => {
+                let (l, a, b) = to_lab(rf, gf, bf);
+                format!("lab({:.2}, {:.2}, {:.2})", l, a, b)
+            }
+            ColorFormat::FloatRgb => format!("{:.3}, {:.3}, {:.3}", rf, gf, bf),
+            ColorFormat::HexLiteral => format!("0x{:02X}{:02X}{:02X}", r, g, b),
+            ColorFormat::Swift => format!(
+                "UIColor(red: {:.3}, green: {:.3}, blue: {:.3}, alpha: {:.3})",
+                rf, gf, bf, af
+            ),
+            ColorFormat::Css => format!("color: rgba({}, {}, {}, {});", r, g, b, trim_float(af)),
+            ColorFormat::Android => format!(
+                "<color name=\"color_{:02x}{:02x}{:02x}\">#{:02X}{:02X}{:02X}{:02X}</color>",
+                r, g, b, a, r, g, b
+            ),
+        }
+    }
+}
+
+/// 색상 복사 대상 (스포이트로 추출한 색, 없으면 펜 색)
+pub fn color_to_copy(sampled: Option<Color32>, pen: Color32) -> Color32 {
+    sampled.unwrap_or(pen)
+}
+
+/// 사용자 템플릿에서 쓸 수 있는 자리표시자 설명
+pub const TEMPLATE_HELP: &str = "{r} {g} {b} {a}: 0~255, {rf} {gf} {bf} {af}: 0~1, {hex}: RRGGBB, {hexa}: RRGGBBAA, \
+{h} {s} {l}: HSL, {v}: HSV 명도";
+
+/// 사용자 템플릿의 자리표시자를 색상 값으로 바꿈 (예: "rgba({r},{g},{b},{a})")
+pub fn expand_template(template: &str, color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    let [rf, gf, bf, af] = [r, g, b, a].map(|v| v as f32 / 255.0);
+    let (h, s, l) = to_hsl(rf, gf, bf);
+    let (_, _, v) = to_hsv(rf, gf, bf);
+
+    let values = [
+        ("{r}", r.to_string()),
+        ("{g}", g.to_string()),
+        ("{b}", b.to_string()),
+        ("{a}", a.to_string()),
+        ("{rf}", format!("{:.3}", rf)),
+        ("{gf}", format!("{:.3}", gf)),
+        ("{bf}", format!("{:.3}", bf)),
+        ("{af}", format!("{:.3}", af)),
+        ("{hex}", format!("{:02X}{:02X}{:02X}", r, g, b)),
+        ("{hexa}", format!("{:02X}{:02X}{:02X}{:02X}", r, g, b, a)),
+        ("{h}", format!("{:.0}", h)),
+        ("{s}", format!("{:.0}", s * 100.0)),
+        ("{l}", format!("{:.0}", l * 100.0)),
+        ("{v}", format!("{:.0}", v * 100.0)),
+    ];
+    values
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| text.replace(name, value))
+}
+
+/// 불필요한 소수점 0 제거 (1.00 → 1, 0.50 → 0.5)
+fn trim_float(value: f32) -> String {
+    let text = format!("{:.2}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// RGB(0~1) → 색조(0~360), 채도, 밝기
+fn to_hsl(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    let s = if d == 0.0 { 0.0 } else { d / (1.0 - (2.0 * l - 1.0).abs()) };
+    (hue(r, g, b, max, d), s, l)
+}
+
+/// RGB(0~1) → 색조(0~360), 채도, 명도
+fn to_hsv(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let d = max - r.min(g).min(b);
+    let s = if max == 0.0 { 0.0 } else { d / max };
+    (hue(r, g, b, max, d), s, max)
+}
+
+fn hue(r: f32, g: f32, b: f32, max: f32, d: f32) -> f32 {
+    if d == 0.0 {
+        return 0.0;
+    }
+    let h = if max == r {
+        (g - b) / d
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h * 60.0).rem_euclid(360.0)
+}
+
+/// sRGB(0~1) → CIE L*a*b* (D65 기준)
+fn to_lab(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let linear = |c: f32| if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) };
+    let (r, g, b) = (linear(r), linear(g), linear(b));
+
+    let x = (0.4124564 * r + 0.3575761 * g + 0.1804375 * b) / 0.95047;
+    let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+    let z = (0.0193339 * r + 0.119192 * g + 0.9503041 * b) / 1.08883;
+
+    let delta: f32 = 6.0 / 29.0;
+    let f = |t: f32| if t > delta.powi(3) { t.cbrt() } else { t / (3.0 * delta * delta) + 4.0 / 29.0 };
+    let (fx, fy, fz) = (f(x), f(y), f(z));
+    (116.0 * fy - 16.0, 500.0 * (fx - fy), 200.0 * (fy - fz))
+}
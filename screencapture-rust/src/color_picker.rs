@@ -0,0 +1,213 @@
+use crate::config::{Config, Palette};
+use egui::color_picker::{self, Alpha};
+use egui::{Color32, PopupCloseBehavior, Response, Ui};
+
+/// 색상 선택 팝업 (HSV/RGB, HEX 입력, 투명도, 최근 색, 사용자 팔레트)
+pub struct ColorPicker {
+    popup_id: egui::Id,
+
+    /// HEX 입력란 내용과 형식 오류 여부
+    hex_text: String,
+    hex_error: bool,
+
+    /// 새 팔레트 이름 입력란
+    new_palette_name: String,
+
+    /// 팝업을 열었을 때의 색 (닫을 때 바뀌었으면 최근 색에 추가)
+    opened_with: Option<Color32>,
+}
+
+impl ColorPicker {
+    pub fn new(id_salt: &str) -> Self {
+        Self {
+            popup_id: egui::Id::new(("color_picker", id_salt)),
+            hex_text: String::new(),
+            hex_error: false,
+            new_palette_name: String::new(),
+            opened_with: None,
+        }
+    }
+
+    /// 선택기 버튼과 팝업 (색이 바뀌면 true)
+    pub fn ui(&mut self, ui: &mut Ui, color: &mut Color32, config: &mut Config) -> bool {
+        let before = *color;
+
+        let button = ui.button("🎨").on_hover_text("색상 선택기 (HSV/RGB, HEX, 투명도, 팔레트)");
+        if button.clicked() {
+            ui.memory_mut(|mem| mem.toggle_popup(self.popup_id));
+        }
+
+        if ui.memory(|mem| mem.is_popup_open(self.popup_id)) && self.opened_with.is_none() {
+            self.opened_with = Some(*color);
+            self.hex_text = format_hex(*color);
+            self.hex_error = false;
+        }
+
+        egui::popup_below_widget(ui, self.popup_id, &button, PopupCloseBehavior::CloseOnClickOutside, |ui| {
+            ui.set_min_width(280.0);
+            self.popup_ui(ui, color, config);
+        });
+
+        // 팝업이 닫힐 때 새로 고른 색을 최근 색에 기록
+        if !ui.memory(|mem| mem.is_popup_open(self.popup_id)) {
+            if let Some(initial) = self.opened_with.take() {
+                if *color != initial {
+                    config.add_recent_color(*color);
+                }
+            }
+        }
+
+        *color != before
+    }
+
+    fn popup_ui(&mut self, ui: &mut Ui, color: &mut Color32, config: &mut Config) {
+        let mut picked = None;
+
+        // HSV 평면, 색조/투명도 슬라이더, RGBA 값
+        if color_picker::color_picker_color32(ui, color, Alpha::OnlyBlend) {
+            self.hex_text = format_hex(*color);
+            self.hex_error = false;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("HEX:");
+            let response = ui.add(egui::TextEdit::singleline(&mut self.hex_text).desired_width(90.0));
+            if response.changed() {
+                match parse_hex(&self.hex_text) {
+                    Some(parsed) => {
+                        *color = parsed;
+                        self.hex_error = false;
+                    }
+                    None => self.hex_error = true,
+                }
+            }
+            if self.hex_error {
+                ui.colored_label(Color32::RED, "#RGB, #RRGGBB, #RRGGBBAA");
+            }
+        });
+
+        if !config.recent_colors.is_empty() {
+            ui.separator();
+            ui.label("최근 색:");
+            ui.horizontal_wrapped(|ui| {
+                for &recent in &config.recent_colors {
+                    if color_swatch(ui, recent, *color == recent, 18.0).clicked() {
+                        picked = Some(recent);
+                    }
+                }
+            });
+        }
+
+        ui.separator();
+        ui.label("팔레트:");
+
+        let mut changed = false;
+        let mut remove_palette = None;
+        for (i, palette) in config.palettes.iter_mut().enumerate() {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(&palette.name);
+
+                let mut remove_color = None;
+                for (j, &swatch) in palette.colors.iter().enumerate() {
+                    let response = color_swatch(ui, swatch, *color == swatch, 18.0)
+                        .on_hover_text("클릭: 선택, 오른쪽 클릭: 팔레트에서 제거");
+                    if response.clicked() {
+                        picked = Some(swatch);
+                    }
+                    if response.secondary_clicked() {
+                        remove_color = Some(j);
+                    }
+                }
+                if let Some(j) = remove_color {
+                    palette.colors.remove(j);
+                    changed = true;
+                }
+
+                if ui.small_button("+").on_hover_text("현재 색 추가").clicked() {
+                    palette.colors.push(*color);
+                    changed = true;
+                }
+                if ui.small_button("🗑").on_hover_text("팔레트 삭제").clicked() {
+                    remove_palette = Some(i);
+                }
+            });
+        }
+        if let Some(i) = remove_palette {
+            config.palettes.remove(i);
+            changed = true;
+        }
+
+        ui.horizontal(|ui| {
+            ui.add(egui::TextEdit::singleline(&mut self.new_palette_name).hint_text("팔레트 이름").desired_width(140.0));
+            let name = self.new_palette_name.trim();
+            if ui.add_enabled(!name.is_empty(), egui::Button::new("새 팔레트")).clicked() {
+                config.palettes.push(Palette {
+                    name: name.to_string(),
+                    colors: vec![*color],
+                });
+                self.new_palette_name.clear();
+                changed = true;
+            }
+        });
+
+        if changed {
+            config.mark_changed();
+        }
+        if let Some(picked) = picked {
+            *color = picked;
+            self.hex_text = format_hex(picked);
+            self.hex_error = false;
+        }
+    }
+}
+
+/// 색상 견본 버튼 (반투명 색은 체크 무늬 위에 표시)
+pub fn color_swatch(ui: &mut Ui, color: Color32, selected: bool, side: f32) -> Response {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(side, side), egui::Sense::click());
+
+    if ui.is_rect_visible(rect) {
+        let visuals = ui.style().interact(&response);
+        let stroke = if selected {
+            egui::Stroke::new(2.0, Color32::WHITE)
+        } else {
+            visuals.bg_stroke
+        };
+
+        if color.is_opaque() {
+            ui.painter().rect_filled(rect, 2.0, color);
+        } else {
+            color_picker::show_color_at(ui.painter(), color, rect);
+        }
+        ui.painter().rect_stroke(rect, 2.0, stroke);
+    }
+
+    response
+}
+
+/// 색상을 "#RRGGBB" (불투명) 또는 "#RRGGBBAA" 문자열로 변환
+pub fn format_hex(color: Color32) -> String {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    if a == 255 {
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    } else {
+        format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+    }
+}
+
+/// "#RGB", "#RRGGBB", "#RRGGBBAA" 형식 해석 (#은 생략 가능)
+pub fn parse_hex(text: &str) -> Option<Color32> {
+    let hex = text.trim().trim_start_matches('#');
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let byte = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    match hex.len() {
+        3 => {
+            let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|v| v * 17);
+            Some(Color32::from_rgb(digit(0)?, digit(1)?, digit(2)?))
+        }
+        6 => Some(Color32::from_rgb(byte(0)?, byte(2)?, byte(4)?)),
+        8 => Some(Color32::from_rgba_unmultiplied(byte(0)?, byte(2)?, byte(4)?, byte(6)?)),
+        _ => None,
+    }
+}
@@ -0,0 +1,188 @@
+use crate::file_naming::SaveOptions;
+use crate::history::HistoryOptions;
+use crate::image_format::ExportOptions;
+use crate::pdf::PdfOptions;
+use egui::{Color32, Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 최근 사용한 색상 최대 개수
+const MAX_RECENT_COLORS: usize = 12;
+
+/// 최근 파일 최대 개수
+const MAX_RECENT_FILES: usize = 10;
+
+/// 설정 파일 이름
+const CONFIG_FILE: &str = "config.json";
+
+/// 사용자 색상 팔레트
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Palette {
+    pub name: String,
+    #[serde(with = "hex_colors")]
+    pub colors: Vec<Color32>,
+}
+
+/// 사용자 정의 색상 복사 형식 (예: "rgba({r},{g},{b},{a})")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorTemplate {
+    pub name: String,
+    pub template: String,
+    /// 단축키 (예: "Ctrl+Shift+1", 비어 있으면 없음)
+    #[serde(default)]
+    pub shortcut: String,
+}
+
+impl ColorTemplate {
+    /// 단축키 해석 ("Ctrl", "Shift", "Alt" 조합 + 키 이름, 비어 있거나 잘못되면 None)
+    pub fn keyboard_shortcut(&self) -> Option<KeyboardShortcut> {
+        let mut modifiers = Modifiers::NONE;
+        let mut key = None;
+        for part in self.shortcut.split('+').map(str::trim) {
+            match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers = modifiers | Modifiers::CTRL,
+                "shift" => modifiers = modifiers | Modifiers::SHIFT,
+                "alt" => modifiers = modifiers | Modifiers::ALT,
+                _ if key.is_none() => key = Some(Key::from_name(part)?),
+                _ => return None,
+            }
+        }
+        Some(KeyboardShortcut::new(modifiers, key?))
+    }
+}
+
+/// 사용자 설정 (설정 디렉터리의 config.json)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// 최근 사용한 색상 (최신 순)
+    #[serde(with = "hex_colors")]
+    pub recent_colors: Vec<Color32>,
+    pub palettes: Vec<Palette>,
+    pub color_templates: Vec<ColorTemplate>,
+    pub pdf: PdfOptions,
+    /// 이미지 저장 형식, 품질, 크기
+    pub export: ExportOptions,
+    /// 저장 폴더, 파일 이름 템플릿, 같은 이름 처리
+    pub save: SaveOptions,
+    /// 캡처 기록 보관 개수와 기간
+    pub history: HistoryOptions,
+    /// 최근 열거나 저장한 파일 (최신 순)
+    pub recent_files: Vec<PathBuf>,
+
+    /// 저장하지 않은 변경 사항이 있는지 여부
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl Config {
+    /// 설정 파일 불러오기 (파일이 없으면 기본값, 잘못된 파일은 기본값으로 덮어쓰지 않도록 `.bak`으로 옮김)
+    pub fn load() -> Result<Self, String> {
+        let path = config_dir().join(CONFIG_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&text).or_else(|e| {
+                let backup = back_up_file(&path)?;
+                Err(format!("Invalid config (moved to {}): {}", backup.display(), e))
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(format!("Failed to read config {}: {}", path.display(), e)),
+        }
+    }
+
+    /// 설정 파일 저장
+    pub fn save(&self) -> Result<(), String> {
+        let dir = config_dir();
+        std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let text = serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize config: {}", e))?;
+        std::fs::write(dir.join(CONFIG_FILE), text).map_err(|e| format!("Failed to write config: {}", e))
+    }
+
+    /// 변경 사항 표시 (다음 프레임에 저장됨)
+    pub fn mark_changed(&mut self) {
+        self.changed = true;
+    }
+
+    /// 변경 사항이 있었는지 확인하고 표시를 지움
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// 최근 사용한 색상 맨 앞에 추가 (중복 제거)
+    pub fn add_recent_color(&mut self, color: Color32) {
+        if self.recent_colors.first() == Some(&color) {
+            return;
+        }
+        self.recent_colors.retain(|c| *c != color);
+        self.recent_colors.insert(0, color);
+        self.recent_colors.truncate(MAX_RECENT_COLORS);
+        self.mark_changed();
+    }
+
+    /// 최근 파일 맨 앞에 추가 (중복 제거)
+    pub fn add_recent_file(&mut self, path: &Path) {
+        if self.recent_files.first().map(PathBuf::as_path) == Some(path) {
+            return;
+        }
+        self.recent_files.retain(|p| p != path);
+        self.recent_files.insert(0, path.to_path_buf());
+        self.recent_files.truncate(MAX_RECENT_FILES);
+        self.mark_changed();
+    }
+
+    /// 최근 파일 목록에서 제거 (열 수 없게 된 파일)
+    pub fn remove_recent_file(&mut self, path: &Path) {
+        let count = self.recent_files.len();
+        self.recent_files.retain(|p| p != path);
+        if self.recent_files.len() != count {
+            self.mark_changed();
+        }
+    }
+}
+
+/// 읽을 수 없는 파일을 "<이름>.bak"으로 옮겨 새로 저장할 때 덮어쓰지 않게 함 (옮긴 경로 반환)
+pub fn back_up_file(path: &Path) -> Result<PathBuf, String> {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    let backup = path.with_file_name(name);
+    std::fs::rename(path, &backup).map_err(|e| format!("Failed to back up {}: {}", path.display(), e))?;
+    Ok(backup)
+}
+
+/// 설정 파일이 저장되는 디렉터리
+///
+/// Windows는 `%APPDATA%\ScreenCapture`, 그 외에는 `$XDG_CONFIG_HOME/screencapture`
+/// (없으면 `~/.config/screencapture`)를 사용합니다.
+pub fn config_dir() -> PathBuf {
+    if let Some(appdata) = std::env::var_os("APPDATA") {
+        return PathBuf::from(appdata).join("ScreenCapture");
+    }
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME").filter(|v| !v.is_empty()) {
+        return PathBuf::from(xdg).join("screencapture");
+    }
+    let home = std::env::var_os("HOME").unwrap_or_else(|| ".".into());
+    PathBuf::from(home).join(".config").join("screencapture")
+}
+
+/// 색상 목록을 사람이 편집하기 쉬운 "#RRGGBBAA" 문자열로 저장
+mod hex_colors {
+    use egui::Color32;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(colors: &[Color32], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(colors.iter().map(|c| {
+            let [r, g, b, a] = c.to_srgba_unmultiplied();
+            format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a)
+        }))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Color32>, D::Error> {
+        let texts = Vec::<String>::deserialize(deserializer)?;
+        texts
+            .iter()
+            .map(|text| {
+                crate::color_picker::parse_hex(text)
+                    .ok_or_else(|| serde::de::Error::custom(format!("invalid color: {}", text)))
+            })
+            .collect()
+    }
+}
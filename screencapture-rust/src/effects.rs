@@ -0,0 +1,91 @@
+use crate::shapes::{DrawShape, SpotlightHole};
+use egui::{Color32, ColorImage, Pos2};
+use std::hash::{Hash, Hasher};
+
+/// 배경에 적용되는 효과(스포트라이트)들의 해시
+///
+/// 값이 바뀌었을 때만 배경 텍스처를 다시 만들기 위해 사용합니다.
+pub fn effects_key<'a>(shapes: impl Iterator<Item = &'a DrawShape>) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    for shape in shapes {
+        if let DrawShape::Spotlight { holes, opacity, desaturate, .. } = shape {
+            opacity.to_bits().hash(&mut hasher);
+            desaturate.hash(&mut hasher);
+            for hole in holes {
+                for v in [hole.rect.min.x, hole.rect.min.y, hole.rect.max.x, hole.rect.max.y] {
+                    v.to_bits().hash(&mut hasher);
+                }
+                hole.ellipse.hash(&mut hasher);
+            }
+        }
+    }
+    hasher.finish()
+}
+
+/// 도형 목록의 모든 스포트라이트를 순서대로 배경에 적용
+pub fn apply_effects<'a>(image: &mut ColorImage, shapes: impl Iterator<Item = &'a DrawShape>) {
+    for shape in shapes {
+        if let DrawShape::Spotlight { holes, opacity, desaturate, .. } = shape {
+            apply_spotlight(image, holes, *opacity, *desaturate);
+        }
+    }
+}
+
+/// 구멍 바깥을 어둡게(선택적으로 흑백으로) 만들기
+pub fn apply_spotlight(image: &mut ColorImage, holes: &[SpotlightHole], opacity: f32, desaturate: bool) {
+    let width = image.width();
+    let keep = 1.0 - opacity.clamp(0.0, 1.0);
+
+    for (i, pixel) in image.pixels.iter_mut().enumerate() {
+        let p = Pos2::new((i % width) as f32 + 0.5, (i / width) as f32 + 0.5);
+        let coverage = 1.0 - hole_coverage(p, holes);
+        if coverage <= 0.0 {
+            continue;
+        }
+
+        let [r, g, b, a] = pixel.to_array().map(|c| c as f32);
+        let (mut er, mut eg, mut eb) = (r, g, b);
+        if desaturate {
+            let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+            (er, eg, eb) = (luma, luma, luma);
+        }
+        er *= keep;
+        eg *= keep;
+        eb *= keep;
+
+        let mix = |orig: f32, effect: f32| (orig + (effect - orig) * coverage).round().clamp(0.0, 255.0) as u8;
+        *pixel = Color32::from_rgba_premultiplied(mix(r, er), mix(g, eg), mix(b, eb), a as u8);
+    }
+}
+
+/// 픽셀 중심이 구멍 안에 있는 정도 (0.0 ~ 1.0, 경계는 1픽셀 안티앨리어싱)
+fn hole_coverage(p: Pos2, holes: &[SpotlightHole]) -> f32 {
+    holes
+        .iter()
+        .map(|hole| (0.5 - signed_distance(p, hole)).clamp(0.0, 1.0))
+        .fold(0.0, f32::max)
+}
+
+/// 구멍 경계까지의 부호 있는 거리 (안쪽이 음수)
+fn signed_distance(p: Pos2, hole: &SpotlightHole) -> f32 {
+    let center = hole.rect.center();
+    let half = hole.rect.size() * 0.5;
+    let d = p - center;
+
+    if !hole.ellipse {
+        return (d.x.abs() - half.x).max(d.y.abs() - half.y);
+    }
+
+    if half.x <= 0.0 || half.y <= 0.0 {
+        return f32::INFINITY;
+    }
+    // 타원 방정식 값을 기울기로 나눠 거리로 근사
+    let nx = d.x / half.x;
+    let ny = d.y / half.y;
+    let k = (nx * nx + ny * ny).sqrt();
+    if k <= f32::EPSILON {
+        return -half.x.min(half.y);
+    }
+    let grad = egui::vec2(nx / half.x, ny / half.y).length() / k;
+    (k - 1.0) / grad
+}
@@ -0,0 +1,267 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 기본 파일 이름 템플릿 (예전 고정 이름 "screenshot_20240101_120000"과 같음)
+pub const DEFAULT_TEMPLATE: &str = "screenshot_{date}_{time}";
+
+/// 파일 이름 템플릿 도움말
+pub const FILE_NAME_HELP: &str = "{date}: 날짜 (20240131), {time}: 시각 (153045), \
+{counter}: 저장할 때마다 1씩 늘어나는 번호 (001), {window_title}: 캡처할 때 맨 앞에 있던 창 제목, \
+{monitor}: 마우스 커서가 있던 모니터 이름";
+
+/// 템플릿 결과가 비었을 때 쓰는 이름
+const FALLBACK_NAME: &str = "screenshot";
+
+/// 파일 이름 최대 길이 (확장자 제외, 문자 수)
+const MAX_NAME_CHARS: usize = 120;
+
+/// 파일 이름에 쓸 수 없는 문자 (Windows 기준)
+const INVALID_CHARS: [char; 9] = ['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Windows에서 확장자와 관계없이 파일 이름으로 쓸 수 없는 장치 이름
+const RESERVED_NAMES: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8", "COM9", "LPT1", "LPT2",
+    "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// 같은 이름의 파일이 이미 있을 때의 처리
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CollisionPolicy {
+    Overwrite,
+    /// "이름 (2).png"처럼 번호 붙이기
+    Increment,
+    Ask,
+}
+
+impl CollisionPolicy {
+    pub const ALL: [CollisionPolicy; 3] = [
+        CollisionPolicy::Overwrite,
+        CollisionPolicy::Increment,
+        CollisionPolicy::Ask,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CollisionPolicy::Overwrite => "덮어쓰기",
+            CollisionPolicy::Increment => "번호 붙이기",
+            CollisionPolicy::Ask => "물어보기",
+        }
+    }
+}
+
+/// 캡처할 때의 정보 (파일 이름 템플릿에 사용)
+#[derive(Debug, Clone, Default)]
+pub struct CaptureInfo {
+    /// 캡처 시각 (None이면 저장하는 시각 사용)
+    pub captured_at: Option<DateTime<Local>>,
+    pub window_title: String,
+    pub monitor: String,
+}
+
+/// 저장 위치와 파일 이름 설정
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveOptions {
+    /// 기본 저장 폴더 (None이면 사진 폴더)
+    pub directory: Option<PathBuf>,
+    /// 파일 이름 템플릿 (확장자 제외)
+    pub template: String,
+    pub collision: CollisionPolicy,
+    /// 저장할 때마다 파일 대화상자 표시 (끄면 저장 폴더에 바로 저장)
+    pub show_dialog: bool,
+    /// 다음 {counter} 값
+    pub counter: u32,
+}
+
+impl Default for SaveOptions {
+    fn default() -> Self {
+        Self {
+            directory: None,
+            template: DEFAULT_TEMPLATE.to_string(),
+            collision: CollisionPolicy::Increment,
+            show_dialog: true,
+            counter: 1,
+        }
+    }
+}
+
+impl SaveOptions {
+    /// 저장 폴더 (없으면 만듦)
+    pub fn ensure_directory(&self) -> Result<PathBuf, String> {
+        let directory = self.directory.clone().unwrap_or_else(pictures_dir);
+        std::fs::create_dir_all(&directory)
+            .map_err(|e| format!("Failed to create {}: {}", directory.display(), e))?;
+        Ok(directory)
+    }
+
+    /// 템플릿으로 만든 파일 이름 (확장자 포함)
+    pub fn file_name(&self, info: &CaptureInfo, extension: &str) -> String {
+        format!("{}.{}", expand_template(&self.template, info, self.counter), extension)
+    }
+
+    /// 템플릿에 {counter}가 있으면 다음 번호로 넘김 (넘겼으면 true)
+    pub fn advance_counter(&mut self) -> bool {
+        if !self.template.contains("{counter}") {
+            return false;
+        }
+        self.counter = self.counter.wrapping_add(1).max(1);
+        true
+    }
+}
+
+/// 템플릿의 항목을 캡처 정보로 바꾸고 파일 이름에 쓸 수 없는 문자를 정리
+///
+/// 알 수 없는 항목은 그대로 둡니다.
+pub fn expand_template(template: &str, info: &CaptureInfo, counter: u32) -> String {
+    let time = info.captured_at.unwrap_or_else(Local::now);
+    let name = template
+        .replace("{date}", &time.format("%Y%m%d").to_string())
+        .replace("{time}", &time.format("%H%M%S").to_string())
+        .replace("{counter}", &format!("{:03}", counter))
+        .replace("{window_title}", info.window_title.trim())
+        .replace("{monitor}", info.monitor.trim());
+    let name = sanitize_file_name(&name);
+    if name.is_empty() {
+        FALLBACK_NAME.to_string()
+    } else {
+        name
+    }
+}
+
+/// 파일 이름에 쓸 수 없는 문자를 '_'로 바꾸고 앞뒤 공백과 끝의 '.' 제거
+///
+/// "CON", "nul.backup"처럼 장치 이름으로 시작하면 장치 이름 뒤에 '_'를 붙입니다.
+pub fn sanitize_file_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_control() || INVALID_CHARS.contains(&c) { '_' } else { c })
+        .take(MAX_NAME_CHARS)
+        .collect();
+    let mut name = name.trim_start().trim_end_matches(|c: char| c == '.' || c.is_whitespace()).to_string();
+    let device = name.split('.').next().unwrap_or_default().trim_end();
+    if RESERVED_NAMES.iter().any(|reserved| reserved.eq_ignore_ascii_case(device)) {
+        name.insert(device.len(), '_');
+    }
+    name
+}
+
+/// 같은 이름의 파일이 없을 때까지 "이름 (2).확장자", "이름 (3).확장자" … 시도
+pub fn unique_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let extension = path.extension().map(|e| e.to_string_lossy().into_owned());
+    (2u32..)
+        .map(|n| {
+            let name = match &extension {
+                Some(extension) => format!("{} ({}).{}", stem, n, extension),
+                None => format!("{} ({})", stem, n),
+            };
+            path.with_file_name(name)
+        })
+        .find(|candidate| !candidate.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// 기본 저장 폴더 (사진 폴더)
+///
+/// Windows는 `%USERPROFILE%\Pictures`, 그 외에는 `$XDG_PICTURES_DIR`
+/// (없으면 `~/.config/user-dirs.dirs`의 값, 그것도 없으면 `~/Pictures`)를 사용합니다.
+pub fn pictures_dir() -> PathBuf {
+    if let Some(profile) = std::env::var_os("USERPROFILE").filter(|v| !v.is_empty()) {
+        return PathBuf::from(profile).join("Pictures");
+    }
+    let home = PathBuf::from(std::env::var_os("HOME").unwrap_or_else(|| ".".into()));
+    if let Some(xdg) = std::env::var_os("XDG_PICTURES_DIR").filter(|v| !v.is_empty()) {
+        return PathBuf::from(xdg);
+    }
+    user_dirs_pictures(&home).unwrap_or_else(|| home.join("Pictures"))
+}
+
+/// `~/.config/user-dirs.dirs`의 XDG_PICTURES_DIR="$HOME/..." 줄 읽기
+fn user_dirs_pictures(home: &Path) -> Option<PathBuf> {
+    let config = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".config"));
+    let text = std::fs::read_to_string(config.join("user-dirs.dirs")).ok()?;
+    let value = text
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("XDG_PICTURES_DIR="))?
+        .trim_matches('"');
+    let path = match value.strip_prefix("$HOME") {
+        Some(rest) => home.join(rest.trim_start_matches('/')),
+        None => PathBuf::from(value),
+    };
+    // 사진 폴더를 홈 폴더로 지정한 경우는 쓰지 않음
+    (path != home && path.is_absolute()).then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn replaces_reserved_characters() {
+        assert_eq!(sanitize_file_name("a<b>c:d\"e/f\\g|h?i*j"), "a_b_c_d_e_f_g_h_i_j");
+        assert_eq!(sanitize_file_name("tab\there"), "tab_here");
+        assert_eq!(sanitize_file_name("  name. . "), "name");
+        assert_eq!(sanitize_file_name(&"x".repeat(200)).chars().count(), MAX_NAME_CHARS);
+    }
+
+    #[test]
+    fn renames_windows_device_names() {
+        assert_eq!(sanitize_file_name("CON"), "CON_");
+        assert_eq!(sanitize_file_name("nul"), "nul_");
+        assert_eq!(sanitize_file_name("com1.backup"), "com1_.backup");
+        assert_eq!(sanitize_file_name("LPT9 "), "LPT9_");
+        assert_eq!(sanitize_file_name("CONSOLE"), "CONSOLE");
+        assert_eq!(sanitize_file_name("COM10"), "COM10");
+    }
+
+    #[test]
+    fn expands_every_placeholder() {
+        let info = CaptureInfo {
+            captured_at: Some(Local.with_ymd_and_hms(2024, 1, 31, 15, 30, 45).unwrap()),
+            window_title: " Editor: notes.txt ".to_string(),
+            monitor: "DISPLAY1".to_string(),
+        };
+        assert_eq!(expand_template("{date}", &info, 1), "20240131");
+        assert_eq!(expand_template("{time}", &info, 1), "153045");
+        assert_eq!(expand_template("{counter}", &info, 7), "007");
+        assert_eq!(expand_template("{counter}", &info, 1234), "1234");
+        assert_eq!(expand_template("{window_title}", &info, 1), "Editor_ notes.txt");
+        assert_eq!(expand_template("{monitor}", &info, 1), "DISPLAY1");
+        assert_eq!(expand_template(DEFAULT_TEMPLATE, &info, 1), "screenshot_20240131_153045");
+        assert_eq!(expand_template("{unknown}_{date}", &info, 1), "{unknown}_20240131");
+    }
+
+    #[test]
+    fn empty_template_falls_back() {
+        let info = CaptureInfo::default();
+        assert_eq!(expand_template("{window_title}", &info, 1), FALLBACK_NAME);
+        assert_eq!(expand_template(" . ", &info, 1), FALLBACK_NAME);
+    }
+
+    #[test]
+    fn unique_path_numbers_existing_files() {
+        let dir = std::env::temp_dir().join(format!("screencapture-naming-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("shot.png");
+        assert_eq!(unique_path(&path), path);
+
+        std::fs::write(&path, b"").unwrap();
+        assert_eq!(unique_path(&path), dir.join("shot (2).png"));
+        std::fs::write(dir.join("shot (2).png"), b"").unwrap();
+        assert_eq!(unique_path(&path), dir.join("shot (3).png"));
+
+        let bare = dir.join("notes");
+        std::fs::write(&bare, b"").unwrap();
+        assert_eq!(unique_path(&bare), dir.join("notes (2)"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,235 @@
+use crate::config::Config;
+use crate::history::History;
+use egui::{TextureHandle, Ui};
+use std::collections::{HashMap, HashSet};
+
+/// 썸네일 표시 크기 (긴 변, 포인트)
+const CARD_IMAGE_SIZE: f32 = 160.0;
+
+/// 캡처 기록 갤러리 창
+#[derive(Default)]
+pub struct Gallery {
+    pub open: bool,
+    search: String,
+    /// 일괄 삭제할 항목
+    selected: HashSet<String>,
+    /// 항목별 (수정 시각, 썸네일 텍스처, 읽지 못했으면 None)
+    thumbnails: HashMap<String, (String, Option<TextureHandle>)>,
+    /// 태그를 편집하는 중인 항목과 입력 중인 태그 (쉼표로 구분)
+    editing_tags: Option<(String, String)>,
+}
+
+/// 갤러리에서 발생한 액션
+#[derive(Debug, Clone, PartialEq)]
+pub enum GalleryAction {
+    None,
+    /// 세션을 캔버스로 불러와 다시 편집
+    Open(String),
+    SetTags(String, Vec<String>),
+    Delete(Vec<String>),
+}
+
+impl Gallery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 갤러리 창 렌더링 (`current`는 지금 편집 중인 항목)
+    pub fn ui(&mut self, ctx: &egui::Context, history: &History, current: Option<&str>, config: &mut Config) -> GalleryAction {
+        let mut action = GalleryAction::None;
+        let mut open = self.open;
+
+        // 지워진 항목 정리
+        self.thumbnails.retain(|id, _| history.get(id).is_some());
+        self.selected.retain(|id| history.get(id).is_some());
+
+        egui::Window::new("캡처 기록")
+            .open(&mut open)
+            .default_size([720.0, 480.0])
+            .show(ctx, |ui| {
+                Self::options_ui(ui, config);
+                ui.separator();
+
+                let visible: Vec<String> = history
+                    .entries
+                    .iter()
+                    .filter(|entry| entry.matches(&self.search))
+                    .map(|entry| entry.id.clone())
+                    .collect();
+
+                ui.horizontal(|ui| {
+                    ui.label("🔍");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.search)
+                            .hint_text("창 제목, 태그, 날짜 검색")
+                            .desired_width(220.0),
+                    );
+                    if !self.search.is_empty() && ui.small_button("✖").clicked() {
+                        self.search.clear();
+                    }
+
+                    ui.separator();
+                    if ui.button("모두 선택").on_hover_text("검색 결과 전체 선택").clicked() {
+                        self.selected.extend(visible.iter().cloned());
+                    }
+                    if ui.add_enabled(!self.selected.is_empty(), egui::Button::new("선택 해제")).clicked() {
+                        self.selected.clear();
+                    }
+                    let delete = egui::Button::new(format!("🗑 선택 삭제 ({})", self.selected.len()));
+                    if ui.add_enabled(!self.selected.is_empty(), delete).clicked() {
+                        // 삭제를 취소할 수 있으므로 선택은 그대로 두고, 지운 항목은 다음 프레임에 정리됨
+                        action = GalleryAction::Delete(self.selected.iter().cloned().collect());
+                    }
+                });
+                ui.separator();
+
+                if history.entries.is_empty() {
+                    ui.label("저장된 캡처가 없습니다");
+                    return;
+                }
+                if visible.is_empty() {
+                    ui.label("검색 결과가 없습니다");
+                    return;
+                }
+
+                egui::ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+                    ui.horizontal_wrapped(|ui| {
+                        for id in &visible {
+                            let is_current = current == Some(id.as_str());
+                            if let Some(card) = self.card_ui(ui, history, id, is_current) {
+                                action = card;
+                            }
+                        }
+                    });
+                });
+            });
+
+        self.open = open;
+        action
+    }
+
+    /// 기록 저장 여부와 보관 개수/기간
+    fn options_ui(ui: &mut Ui, config: &mut Config) {
+        let options = &mut config.history;
+        let before = *options;
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut options.enabled, "캡처할 때마다 기록에 저장");
+            ui.separator();
+            ui.label("최대");
+            ui.add(egui::DragValue::new(&mut options.max_count).range(0..=10_000).suffix("개"))
+                .on_hover_text("0 = 제한 없음");
+            ui.label("보관 기간");
+            ui.add(egui::DragValue::new(&mut options.max_age_days).range(0..=3650).suffix("일"))
+                .on_hover_text("0 = 제한 없음");
+        })
+        .response
+        .on_hover_text("제한을 넘은 오래된 기록은 다음 캡처 때 삭제됩니다");
+        if *options != before {
+            config.mark_changed();
+        }
+    }
+
+    /// 기록 한 항목 (썸네일, 선택, 시각, 창 제목, 태그)
+    fn card_ui(&mut self, ui: &mut Ui, history: &History, id: &str, current: bool) -> Option<GalleryAction> {
+        let entry = history.get(id)?;
+        let mut action = None;
+
+        let stroke = if current {
+            egui::Stroke::new(2.0, ui.visuals().selection.bg_fill)
+        } else {
+            ui.visuals().widgets.noninteractive.bg_stroke
+        };
+        egui::Frame::group(ui.style()).stroke(stroke).show(ui, |ui| {
+            ui.set_width(CARD_IMAGE_SIZE);
+            ui.vertical(|ui| {
+                match self.thumbnail(ui.ctx(), history, id) {
+                    Some(texture) => {
+                        let size = texture.size_vec2();
+                        let scale = (CARD_IMAGE_SIZE / size.x.max(size.y)).min(1.0);
+                        let image = egui::Image::new((texture.id(), size * scale)).sense(egui::Sense::click());
+                        let response = ui.add(image).on_hover_text("더블 클릭해서 열기");
+                        if response.double_clicked() {
+                            action = Some(GalleryAction::Open(id.to_string()));
+                        }
+                    }
+                    None => {
+                        ui.allocate_exact_size(egui::vec2(CARD_IMAGE_SIZE, CARD_IMAGE_SIZE * 0.6), egui::Sense::hover());
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    let mut selected = self.selected.contains(id);
+                    if ui.checkbox(&mut selected, "").on_hover_text("삭제할 항목으로 선택").changed() {
+                        if selected {
+                            self.selected.insert(id.to_string());
+                        } else {
+                            self.selected.remove(id);
+                        }
+                    }
+                    ui.label(entry.time_label());
+                    if ui.small_button("열기").clicked() {
+                        action = Some(GalleryAction::Open(id.to_string()));
+                    }
+                });
+
+                let title = if entry.window_title.is_empty() { "(창 제목 없음)" } else { &entry.window_title };
+                ui.add(egui::Label::new(egui::RichText::new(title).small()).truncate())
+                    .on_hover_text(format!(
+                        "{}\n{}x{}, 도형 {}개{}",
+                        title,
+                        entry.size[0],
+                        entry.size[1],
+                        entry.shape_count,
+                        if entry.monitor.is_empty() { String::new() } else { format!(", {}", entry.monitor) }
+                    ));
+
+                match &mut self.editing_tags {
+                    Some((editing, text)) if editing == id => {
+                        let response = ui.add(egui::TextEdit::singleline(text).hint_text("태그1, 태그2").desired_width(f32::INFINITY));
+                        if !response.has_focus() && !response.lost_focus() {
+                            response.request_focus();
+                        }
+                        if response.lost_focus() {
+                            // ESC는 취소, 그 외(Enter, 다른 곳 클릭)는 적용
+                            if !ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                                let tags = text.split(',').map(str::to_string).collect();
+                                action = Some(GalleryAction::SetTags(id.to_string(), tags));
+                            }
+                            self.editing_tags = None;
+                        }
+                    }
+                    _ => {
+                        ui.horizontal_wrapped(|ui| {
+                            for tag in &entry.tags {
+                                if ui.small_button(format!("#{}", tag)).on_hover_text("이 태그로 검색").clicked() {
+                                    self.search = format!("#{}", tag);
+                                }
+                            }
+                            if ui.small_button("🏷").on_hover_text("태그 편집").clicked() {
+                                self.editing_tags = Some((id.to_string(), entry.tags.join(", ")));
+                            }
+                        });
+                    }
+                }
+            });
+        });
+
+        action
+    }
+
+    /// 썸네일 텍스처 (편집 내용이 바뀌면 다시 불러옴, 읽지 못하면 None)
+    fn thumbnail(&mut self, ctx: &egui::Context, history: &History, id: &str) -> Option<TextureHandle> {
+        let entry = history.get(id)?;
+        if let Some((modified_at, texture)) = self.thumbnails.get(id) {
+            if *modified_at == entry.modified_at {
+                return texture.clone();
+            }
+        }
+        let texture = history
+            .load_thumbnail(id)
+            .ok()
+            .map(|image| ctx.load_texture(format!("history_{}", id), image, egui::TextureOptions::LINEAR));
+        self.thumbnails.insert(id.to_string(), (entry.modified_at.clone(), texture.clone()));
+        texture
+    }
+}
@@ -0,0 +1,274 @@
+use crate::clipboard::{color_image_to_image_buffer, decode_png, encode_png, image_buffer_to_color_image};
+use crate::config::{back_up_file, config_dir};
+use crate::file_naming::CaptureInfo;
+use crate::session::{Session, SESSION_EXTENSION};
+use chrono::{DateTime, Local};
+use egui::ColorImage;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// 기록 폴더 안의 목록 파일
+const INDEX_FILE: &str = "history.json";
+
+/// 썸네일 긴 변의 최대 크기 (픽셀)
+const THUMBNAIL_SIZE: u32 = 256;
+
+/// 캡처 기록 보관 설정
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HistoryOptions {
+    /// 캡처할 때마다 기록에 저장
+    pub enabled: bool,
+    /// 최대 보관 개수 (0이면 제한 없음)
+    pub max_count: usize,
+    /// 최대 보관 기간 (일, 0이면 제한 없음)
+    pub max_age_days: u32,
+}
+
+impl Default for HistoryOptions {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_count: 100,
+            max_age_days: 30,
+        }
+    }
+}
+
+/// 기록 한 항목 (세션 파일과 썸네일은 `id`로 찾음)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: String,
+    /// 캡처한 시각 (RFC 3339)
+    pub captured_at: String,
+    /// 마지막으로 편집 내용을 저장한 시각 (RFC 3339, 썸네일 캐시 구분용)
+    pub modified_at: String,
+    #[serde(default)]
+    pub window_title: String,
+    #[serde(default)]
+    pub monitor: String,
+    pub size: [usize; 2],
+    #[serde(default)]
+    pub shape_count: usize,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl HistoryEntry {
+    pub fn captured_at(&self) -> Option<DateTime<Local>> {
+        DateTime::parse_from_rfc3339(&self.captured_at)
+            .ok()
+            .map(|time| time.with_timezone(&Local))
+    }
+
+    /// 목록에 표시할 캡처 시각 ("2024-01-31 15:30")
+    pub fn time_label(&self) -> String {
+        self.captured_at()
+            .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_else(|| self.captured_at.clone())
+    }
+
+    /// 파일 이름 템플릿에 쓸 캡처 정보
+    pub fn capture_info(&self) -> CaptureInfo {
+        CaptureInfo {
+            captured_at: self.captured_at(),
+            window_title: self.window_title.clone(),
+            monitor: self.monitor.clone(),
+        }
+    }
+
+    /// 검색어의 모든 단어가 창 제목, 모니터, 태그, 캡처 시각 중 하나에 들어 있는지 (대소문자 무시)
+    pub fn matches(&self, query: &str) -> bool {
+        let haystack = format!(
+            "{} {} {} {}",
+            self.window_title,
+            self.monitor,
+            self.tags.join(" "),
+            self.time_label()
+        )
+        .to_lowercase();
+        query
+            .split_whitespace()
+            .all(|word| haystack.contains(&word.trim_start_matches('#').to_lowercase()))
+    }
+}
+
+/// 캡처 기록 저장소 (설정 디렉터리의 history 폴더)
+pub struct History {
+    dir: PathBuf,
+    /// 최신 순
+    pub entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    /// 기록 목록 불러오기 (목록 파일이 없으면 빈 기록)
+    ///
+    /// 목록 파일이 깨졌으면 새 목록이 덮어쓰지 않도록 "history.json.bak"으로 옮기고 오류를 반환합니다.
+    pub fn load() -> Result<Self, String> {
+        let dir = history_dir();
+        let path = dir.join(INDEX_FILE);
+        let entries = match std::fs::read_to_string(&path) {
+            Ok(text) => match serde_json::from_str(&text) {
+                Ok(entries) => entries,
+                Err(e) => {
+                    let backup = back_up_file(&path)?;
+                    return Err(format!("Invalid history index (moved to {}): {}", backup.display(), e));
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(format!("Failed to read history index: {}", e)),
+        };
+        Ok(Self { dir, entries })
+    }
+
+    /// 목록을 읽지 못했을 때 쓰는 빈 기록 (다음에 저장하면 목록을 새로 만듦)
+    pub fn empty() -> Self {
+        Self {
+            dir: history_dir(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// 새 캡처를 기록에 추가하고 항목 ID 반환
+    ///
+    /// `image`는 도형까지 합성한 이미지로 썸네일에 사용합니다.
+    pub fn add(&mut self, session: &Session, image: &ColorImage, info: &CaptureInfo) -> Result<String, String> {
+        let now = Local::now();
+        let captured_at = info.captured_at.unwrap_or(now);
+        let mut id = captured_at.format("%Y%m%d_%H%M%S_%3f").to_string();
+        while self.get(&id).is_some() {
+            id.push('_');
+        }
+
+        self.write_files(&id, session, image)?;
+        self.entries.insert(
+            0,
+            HistoryEntry {
+                id: id.clone(),
+                captured_at: captured_at.to_rfc3339(),
+                modified_at: now.to_rfc3339(),
+                window_title: info.window_title.clone(),
+                monitor: info.monitor.clone(),
+                size: session.background.size,
+                shape_count: session.layers.len(),
+                tags: Vec::new(),
+            },
+        );
+        self.save_index()?;
+        Ok(id)
+    }
+
+    /// 기록 항목의 세션과 썸네일을 현재 편집 내용으로 바꿈
+    pub fn update(&mut self, id: &str, session: &Session, image: &ColorImage) -> Result<(), String> {
+        if self.get(id).is_none() {
+            return Ok(());
+        }
+        self.write_files(id, session, image)?;
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) {
+            entry.modified_at = Local::now().to_rfc3339();
+            entry.size = session.background.size;
+            entry.shape_count = session.layers.len();
+        }
+        self.save_index()
+    }
+
+    /// 기록 항목의 세션 불러오기
+    pub fn load_session(&self, id: &str) -> Result<Session, String> {
+        Session::load(&self.session_path(id))
+    }
+
+    /// 기록 항목의 썸네일 불러오기
+    pub fn load_thumbnail(&self, id: &str) -> Result<ColorImage, String> {
+        let bytes = std::fs::read(self.thumbnail_path(id)).map_err(|e| format!("Failed to read thumbnail: {}", e))?;
+        decode_png(&bytes)
+    }
+
+    /// 태그 바꾸기 (공백 제거, 빈 태그와 중복 제외)
+    pub fn set_tags(&mut self, id: &str, tags: Vec<String>) -> Result<(), String> {
+        let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == id) else {
+            return Ok(());
+        };
+        entry.tags.clear();
+        for tag in tags {
+            let tag = tag.trim().trim_start_matches('#').to_string();
+            if !tag.is_empty() && !entry.tags.contains(&tag) {
+                entry.tags.push(tag);
+            }
+        }
+        self.save_index()
+    }
+
+    /// 기록 항목과 파일 삭제
+    pub fn remove(&mut self, ids: &[String]) -> Result<(), String> {
+        for id in ids {
+            // 이미 지워진 파일은 무시
+            let _ = std::fs::remove_file(self.session_path(id));
+            let _ = std::fs::remove_file(self.thumbnail_path(id));
+        }
+        self.entries.retain(|entry| !ids.contains(&entry.id));
+        self.save_index()
+    }
+
+    /// 보관 개수와 기간을 넘은 오래된 항목 삭제 (`keep`은 지우지 않음) 후 삭제한 개수 반환
+    pub fn apply_retention(&mut self, options: &HistoryOptions, keep: Option<&str>) -> Result<usize, String> {
+        let oldest = (options.max_age_days > 0).then(|| Local::now() - chrono::Duration::days(options.max_age_days as i64));
+        let expired: Vec<String> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(i, entry)| {
+                let too_many = options.max_count > 0 && *i >= options.max_count;
+                let too_old = match (oldest, entry.captured_at()) {
+                    (Some(oldest), Some(time)) => time < oldest,
+                    _ => false,
+                };
+                (too_many || too_old) && Some(entry.id.as_str()) != keep
+            })
+            .map(|(_, entry)| entry.id.clone())
+            .collect();
+        if !expired.is_empty() {
+            self.remove(&expired)?;
+        }
+        Ok(expired.len())
+    }
+
+    fn write_files(&self, id: &str, session: &Session, image: &ColorImage) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+        session.save(&self.session_path(id))?;
+        std::fs::write(self.thumbnail_path(id), encode_png(&thumbnail(image))?)
+            .map_err(|e| format!("Failed to write thumbnail: {}", e))
+    }
+
+    fn save_index(&self) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+        let text = serde_json::to_string_pretty(&self.entries).map_err(|e| format!("Failed to serialize history: {}", e))?;
+        std::fs::write(self.dir.join(INDEX_FILE), text).map_err(|e| format!("Failed to write history index: {}", e))
+    }
+
+    fn session_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", id, SESSION_EXTENSION))
+    }
+
+    fn thumbnail_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.png", id))
+    }
+}
+
+/// 기록이 저장되는 폴더
+pub fn history_dir() -> PathBuf {
+    config_dir().join("history")
+}
+
+/// 긴 변이 `THUMBNAIL_SIZE` 이하가 되도록 줄인 이미지
+fn thumbnail(image: &ColorImage) -> ColorImage {
+    let [width, height] = image.size;
+    let scale = (THUMBNAIL_SIZE as f32 / width.max(height).max(1) as f32).min(1.0);
+    let width = ((width as f32 * scale).round() as u32).max(1);
+    let height = ((height as f32 * scale).round() as u32).max(1);
+    let resized = image::imageops::thumbnail(&color_image_to_image_buffer(image), width, height);
+    image_buffer_to_color_image(&resized)
+}
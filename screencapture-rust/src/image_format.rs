@@ -0,0 +1,277 @@
+use crate::clipboard::{color_image_to_image_buffer, image_buffer_to_color_image};
+use crate::png_optimize::{self, PngOptimize, SizeReport};
+use egui::{Color32, ColorImage};
+use image::codecs::bmp::BmpEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType as PngFilter, PngEncoder};
+use image::codecs::tiff::TiffEncoder;
+use image::imageops::{self, FilterType};
+use image::{ExtendedColorType, ImageEncoder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use webp::PixelLayout;
+
+/// 이미지 저장 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    WebP,
+    Bmp,
+    Tiff,
+}
+
+impl ImageFormat {
+    pub const ALL: [ImageFormat; 5] = [
+        ImageFormat::Png,
+        ImageFormat::Jpeg,
+        ImageFormat::WebP,
+        ImageFormat::Bmp,
+        ImageFormat::Tiff,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "PNG",
+            ImageFormat::Jpeg => "JPEG",
+            ImageFormat::WebP => "WebP",
+            ImageFormat::Bmp => "BMP",
+            ImageFormat::Tiff => "TIFF",
+        }
+    }
+
+    /// 기본 파일 확장자
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::WebP => "webp",
+            ImageFormat::Bmp => "bmp",
+            ImageFormat::Tiff => "tif",
+        }
+    }
+
+    /// 파일 확장자로 형식 판별 (알 수 없는 확장자면 None)
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_ascii_lowercase();
+        match extension.as_str() {
+            "png" => Some(ImageFormat::Png),
+            "jpg" | "jpeg" => Some(ImageFormat::Jpeg),
+            "webp" => Some(ImageFormat::WebP),
+            "bmp" => Some(ImageFormat::Bmp),
+            "tif" | "tiff" => Some(ImageFormat::Tiff),
+            _ => None,
+        }
+    }
+
+    /// 알파 채널을 저장할 수 있는지 여부
+    pub fn supports_alpha(self) -> bool {
+        self != ImageFormat::Jpeg
+    }
+}
+
+/// 이미지 저장 설정
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportOptions {
+    /// 저장 대화상자의 기본 형식
+    pub format: ImageFormat,
+    /// JPEG/WebP 품질 (1~100, WebP는 100이면 무손실)
+    #[serde(alias = "jpeg_quality")]
+    pub quality: u8,
+    /// PNG 압축 단계 (0 = 압축 안 함 ~ 9 = 최대, None이면 기본값)
+    pub png_level: Option<u8>,
+    /// 저장/복사할 때의 크기 비율 (1~100%)
+    pub scale_percent: u32,
+    /// 투명한 부분을 배경색으로 채움 (JPEG은 항상 채움)
+    pub flatten_alpha: bool,
+    pub background: Color32,
+    /// PNG 최적화 (팔레트/비트 깊이 축소, 필터 비교)
+    pub png_optimize: PngOptimize,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            format: ImageFormat::Png,
+            quality: 90,
+            png_level: None,
+            scale_percent: 100,
+            flatten_alpha: false,
+            background: Color32::WHITE,
+            png_optimize: PngOptimize::default(),
+        }
+    }
+}
+
+impl ExportOptions {
+    /// `--comp:quality,scale` 적용 (quality -1은 설정 유지, 0~9는 압축 단계)
+    ///
+    /// Qt 버전과 같이 압축 단계가 높을수록 파일이 작아집니다. JPEG/WebP 품질은 단계마다 10씩 낮춥니다.
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        if let Some(level) = compression.level {
+            self.png_level = Some(level);
+            self.quality = (100 - level * 10).max(10);
+        }
+        self.scale_percent = compression.scale_percent;
+        self
+    }
+
+    /// 저장 전 크기 조절과 알파 제거
+    pub fn prepare(&self, image: &ColorImage, format: ImageFormat) -> ColorImage {
+        let mut image = scale_image(image, self.scale_percent);
+        if self.flatten_alpha || !format.supports_alpha() {
+            flatten_alpha(&mut image, self.background);
+        }
+        image
+    }
+
+    /// 이미지를 지정한 형식의 파일 데이터로 변환
+    ///
+    /// PNG 최적화를 켰으면 최적화 전후 크기도 함께 반환합니다.
+    pub fn encode(&self, image: &ColorImage, format: ImageFormat) -> Result<(Vec<u8>, Option<SizeReport>), String> {
+        let image = self.prepare(image, format);
+        let buffer = color_image_to_image_buffer(&image);
+        let (width, height) = buffer.dimensions();
+
+        // 투명한 픽셀이 없으면 알파 채널 없이 저장 (PNG는 최적화 단계에서 처리)
+        let opaque = image.pixels.iter().all(|pixel| pixel.a() == u8::MAX);
+        let rgb;
+        let (data, color_type) = if opaque && format != ImageFormat::Png {
+            rgb = image::DynamicImage::ImageRgba8(buffer.clone()).to_rgb8();
+            (rgb.as_raw(), ExtendedColorType::Rgb8)
+        } else {
+            (buffer.as_raw(), ExtendedColorType::Rgba8)
+        };
+
+        let mut bytes = Vec::new();
+        let result = match format {
+            ImageFormat::Png => {
+                let compression = match self.png_level {
+                    None => CompressionType::Default,
+                    Some(0) => CompressionType::Uncompressed,
+                    Some(level) => CompressionType::Level(level.min(9)),
+                };
+                PngEncoder::new_with_quality(&mut bytes, compression, PngFilter::Adaptive)
+                    .write_image(data, width, height, color_type)
+            }
+            ImageFormat::Jpeg => JpegEncoder::new_with_quality(&mut bytes, self.quality.clamp(1, 100))
+                .write_image(data, width, height, color_type),
+            ImageFormat::WebP => {
+                // image 크레이트는 무손실 WebP만 쓸 수 있으므로 libwebp 사용
+                let layout = if color_type == ExtendedColorType::Rgb8 { PixelLayout::Rgb } else { PixelLayout::Rgba };
+                let quality = self.quality.clamp(1, 100);
+                let webp = webp::Encoder::new(data, layout, width, height)
+                    .encode_simple(quality == 100, quality as f32)
+                    .map_err(|e| format!("Failed to encode {}: {:?}", format.label(), e))?;
+                bytes.extend_from_slice(&webp);
+                Ok(())
+            }
+            ImageFormat::Bmp => BmpEncoder::new(&mut bytes).write_image(data, width, height, color_type),
+            ImageFormat::Tiff => TiffEncoder::new(std::io::Cursor::new(&mut bytes)).write_image(data, width, height, color_type),
+        };
+        result.map_err(|e| format!("Failed to encode {}: {}", format.label(), e))?;
+
+        if format == ImageFormat::Png && self.png_optimize.enabled {
+            let (bytes, report) = png_optimize::optimize_png(&image, &self.png_optimize, self.png_level, bytes)?;
+            return Ok((bytes, Some(report)));
+        }
+        Ok((bytes, None))
+    }
+}
+
+/// 명령줄 `--comp:quality,scale` 값
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Compression {
+    /// 압축 단계 0~9 (None이면 기본값, 명령줄의 -1)
+    pub level: Option<u8>,
+    /// 크기 비율 1~100%
+    pub scale_percent: u32,
+}
+
+impl Compression {
+    /// "quality,scale" 해석 (scale은 생략하면 100)
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut parts = value.split(',').map(str::trim);
+        let quality: i32 = parts
+            .next()
+            .unwrap_or_default()
+            .parse()
+            .map_err(|_| format!("Invalid --comp quality: {}", value))?;
+        let level = match quality {
+            -1 => None,
+            0..=9 => Some(quality as u8),
+            _ => return Err(format!("--comp quality must be -1~9: {}", quality)),
+        };
+        let scale_percent = match parts.next() {
+            Some(scale) => scale.parse().map_err(|_| format!("Invalid --comp scale: {}", value))?,
+            None => 100,
+        };
+        if !(1..=100).contains(&scale_percent) {
+            return Err(format!("--comp scale must be 1~100: {}", scale_percent));
+        }
+        Ok(Self { level, scale_percent })
+    }
+}
+
+/// 비율(%)로 이미지 크기 조절 (100%면 그대로)
+pub fn scale_image(image: &ColorImage, percent: u32) -> ColorImage {
+    if percent >= 100 {
+        return image.clone();
+    }
+    let scale = percent.max(1) as f32 / 100.0;
+    let width = ((image.width() as f32 * scale).round() as u32).max(1);
+    let height = ((image.height() as f32 * scale).round() as u32).max(1);
+    let resized = imageops::resize(&color_image_to_image_buffer(image), width, height, FilterType::Lanczos3);
+    image_buffer_to_color_image(&resized)
+}
+
+/// 투명한 부분을 배경색 위에 합성해 불투명하게 만듦
+pub fn flatten_alpha(image: &mut ColorImage, background: Color32) {
+    let background = background.to_opaque();
+    for pixel in &mut image.pixels {
+        // Color32는 알파가 곱해진 값이므로 배경을 (1 - 알파)만큼 더하면 됨
+        let rest = (u8::MAX - pixel.a()) as u16;
+        let blend = |c: u8, bg: u8| (c as u16 + (bg as u16 * rest + 127) / 255).min(255) as u8;
+        *pixel = Color32::from_rgb(
+            blend(pixel.r(), background.r()),
+            blend(pixel.g(), background.g()),
+            blend(pixel.b(), background.b()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_default_level_and_full_scale() {
+        assert_eq!(Compression::parse("-1,100"), Ok(Compression { level: None, scale_percent: 100 }));
+    }
+
+    #[test]
+    fn parses_level_and_scale() {
+        assert_eq!(Compression::parse("9,50"), Ok(Compression { level: Some(9), scale_percent: 50 }));
+        assert_eq!(Compression::parse(" 0 , 1 "), Ok(Compression { level: Some(0), scale_percent: 1 }));
+    }
+
+    #[test]
+    fn missing_scale_defaults_to_100() {
+        assert_eq!(Compression::parse("6"), Ok(Compression { level: Some(6), scale_percent: 100 }));
+    }
+
+    #[test]
+    fn rejects_out_of_range_values() {
+        for value in ["-2,100", "10,100", "5,0", "5,101"] {
+            assert!(Compression::parse(value).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn rejects_junk() {
+        for value in ["", "abc", "5,", "5,x", ",50", "5.5,50", "5,50%"] {
+            assert!(Compression::parse(value).is_err(), "{:?}", value);
+        }
+    }
+}
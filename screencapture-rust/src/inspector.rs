@@ -0,0 +1,329 @@
+use crate::color_picker::{color_swatch, ColorPicker};
+use crate::config::Config;
+use crate::shapes::{self, ArrowStyle, DrawShape, NumberStyle};
+use crate::style::{DashPattern, LineCap, LineJoin, ShapeStyle};
+use egui::{DragValue, Pos2, Rect, Ui};
+
+/// 선택된 도형의 속성 패널
+pub struct Inspector {
+    stroke_picker: ColorPicker,
+    fill_picker: ColorPicker,
+
+    /// 마지막으로 도형을 바꾼 위젯 (같은 위젯을 계속 조작하면 실행 취소 한 번으로 묶음)
+    last_edit_widget: Option<egui::Id>,
+}
+
+/// 속성 패널에서 수정한 도형
+pub struct InspectorEdit {
+    pub shape: DrawShape,
+    /// 직전 수정과 같은 조작(슬라이더 드래그, 텍스트 입력)의 연속인지 여부
+    pub merge: bool,
+}
+
+impl Inspector {
+    pub fn new() -> Self {
+        Self {
+            stroke_picker: ColorPicker::new("inspector_stroke"),
+            fill_picker: ColorPicker::new("inspector_fill"),
+            last_edit_widget: None,
+        }
+    }
+
+    /// 속성 패널 UI (값이 바뀌면 수정된 도형 반환)
+    pub fn ui(&mut self, ui: &mut Ui, shape: &DrawShape, config: &mut Config) -> Option<InspectorEdit> {
+        // 드래그를 놓거나 입력 칸의 포커스가 빠지면 조작이 끝난 것이므로 다음 수정은 새 실행 취소 단계
+        if self.last_edit_widget.is_some() && active_widget(ui) != self.last_edit_widget {
+            self.last_edit_widget = None;
+        }
+
+        let mut shape = shape.clone();
+        ui.heading(shape.type_label());
+        ui.separator();
+
+        let mut changed = geometry_ui(ui, &mut shape);
+
+        ui.separator();
+        changed |= self.style_ui(ui, &mut shape, config);
+
+        if !changed {
+            return None;
+        }
+        let active = active_widget(ui);
+        let merge = active.is_some() && active == self.last_edit_widget;
+        self.last_edit_widget = active;
+        Some(InspectorEdit { shape, merge })
+    }
+
+    /// 선/채우기 스타일
+    fn style_ui(&mut self, ui: &mut Ui, shape: &mut DrawShape, config: &mut Config) -> bool {
+        let is_text = matches!(shape, DrawShape::Text { .. });
+        let has_corners = matches!(
+            shape,
+            DrawShape::Rectangle { .. }
+                | DrawShape::Callout { .. }
+                | DrawShape::Spotlight { .. }
+                | DrawShape::Magnifier { .. }
+                | DrawShape::Image { .. }
+        );
+        let style = shape_style_mut(shape);
+        let mut changed = false;
+
+        egui::Grid::new("inspector_style").num_columns(2).show(ui, |ui| {
+            ui.label(if is_text { "글자 색" } else { "선 색" });
+            ui.horizontal(|ui| {
+                color_swatch(ui, style.stroke_color, false, 18.0);
+                changed |= self.stroke_picker.ui(ui, &mut style.stroke_color, config);
+            });
+            ui.end_row();
+
+            if !is_text {
+                ui.label("두께");
+                changed |= ui.add(egui::Slider::new(&mut style.stroke_width, 0.0..=20.0).fixed_decimals(1)).changed();
+                ui.end_row();
+            }
+
+            ui.label(if is_text { "배경" } else { "채우기" });
+            ui.horizontal(|ui| {
+                let mut filled = style.fill_color.is_some();
+                if ui.checkbox(&mut filled, "").changed() {
+                    style.fill_color = filled.then_some(style.stroke_color);
+                    changed = true;
+                }
+                if let Some(fill) = &mut style.fill_color {
+                    color_swatch(ui, *fill, false, 18.0);
+                    changed |= self.fill_picker.ui(ui, fill, config);
+                }
+            });
+            ui.end_row();
+
+            ui.label("불투명도");
+            changed |= ui.add(egui::Slider::new(&mut style.opacity, 0.1..=1.0).fixed_decimals(2)).changed();
+            ui.end_row();
+
+            if !is_text {
+                ui.label("선 모양");
+                egui::ComboBox::from_id_salt("inspector_dash")
+                    .selected_text(style.dash.label())
+                    .show_ui(ui, |ui| {
+                        for dash in DashPattern::ALL {
+                            changed |= ui.selectable_value(&mut style.dash, dash, dash.label()).changed();
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("선 끝");
+                egui::ComboBox::from_id_salt("inspector_line_cap")
+                    .selected_text(style.line_cap.label())
+                    .show_ui(ui, |ui| {
+                        for cap in LineCap::ALL {
+                            changed |= ui.selectable_value(&mut style.line_cap, cap, cap.label()).changed();
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("꺾임");
+                egui::ComboBox::from_id_salt("inspector_line_join")
+                    .selected_text(style.line_join.label())
+                    .show_ui(ui, |ui| {
+                        for join in LineJoin::ALL {
+                            changed |= ui.selectable_value(&mut style.line_join, join, join.label()).changed();
+                        }
+                    });
+                ui.end_row();
+            }
+
+            if has_corners {
+                ui.label("모서리");
+                changed |= ui.add(egui::Slider::new(&mut style.corner_radius, 0.0..=30.0).fixed_decimals(0)).changed();
+                ui.end_row();
+            }
+        });
+
+        changed
+    }
+}
+
+impl Default for Inspector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 도형의 스타일
+fn shape_style_mut(shape: &mut DrawShape) -> &mut ShapeStyle {
+    match shape {
+        DrawShape::Rectangle { style, .. }
+        | DrawShape::Ellipse { style, .. }
+        | DrawShape::Arrow { style, .. }
+        | DrawShape::Line { style, .. }
+        | DrawShape::Curve { style, .. }
+        | DrawShape::Text { style, .. }
+        | DrawShape::Number { style, .. }
+        | DrawShape::Callout { style, .. }
+        | DrawShape::Spotlight { style, .. }
+        | DrawShape::Magnifier { style, .. }
+        | DrawShape::Image { style, .. } => style,
+    }
+}
+
+/// 위치, 크기, 텍스트 등 도형 종류별 속성
+fn geometry_ui(ui: &mut Ui, shape: &mut DrawShape) -> bool {
+    let mut changed = false;
+
+    egui::Grid::new("inspector_geometry").num_columns(2).show(ui, |ui| match shape {
+        DrawShape::Rectangle { rect, .. } => {
+            changed |= rect_rows(ui, "", rect);
+        }
+        DrawShape::Ellipse { center, radius_x, radius_y, .. } => {
+            changed |= pos_row(ui, "중심", center);
+            ui.label("반지름");
+            ui.horizontal(|ui| {
+                changed |= ui.add(DragValue::new(radius_x).range(0.0..=f32::MAX).prefix("x: ")).changed();
+                changed |= ui.add(DragValue::new(radius_y).range(0.0..=f32::MAX).prefix("y: ")).changed();
+            });
+            ui.end_row();
+        }
+        DrawShape::Arrow { start, end, control, kind, .. } => {
+            changed |= pos_row(ui, "시작", start);
+            changed |= pos_row(ui, "끝", end);
+            if let Some(control) = control {
+                changed |= pos_row(ui, "제어점", control);
+            }
+            ui.label("모양");
+            let before = *kind;
+            egui::ComboBox::from_id_salt("inspector_arrow")
+                .selected_text(kind.label())
+                .show_ui(ui, |ui| {
+                    for style in ArrowStyle::ALL {
+                        ui.selectable_value(kind, style, style.label());
+                    }
+                });
+            if *kind != before {
+                // 곡선 화살표만 제어점을 가짐
+                *control = (*kind == ArrowStyle::Curved).then(|| shapes::default_arrow_control(*start, *end));
+                changed = true;
+            }
+            ui.end_row();
+        }
+        DrawShape::Line { points, .. } => {
+            ui.label("점");
+            ui.label(points.len().to_string());
+            ui.end_row();
+        }
+        DrawShape::Curve { points, smoothing, .. } => {
+            ui.label("점");
+            ui.label(points.len().to_string());
+            ui.end_row();
+            ui.label("부드럽게");
+            changed |= ui.add(egui::Slider::new(smoothing, 0.0..=1.0).fixed_decimals(2)).changed();
+            ui.end_row();
+        }
+        DrawShape::Text { pos, text, font_size, .. } => {
+            changed |= pos_row(ui, "위치", pos);
+            changed |= text_rows(ui, text, font_size);
+        }
+        DrawShape::Number { center, number, radius, format, .. } => {
+            changed |= pos_row(ui, "중심", center);
+            ui.label("번호");
+            changed |= ui.add(DragValue::new(number).range(0..=9999)).changed();
+            ui.end_row();
+            ui.label("표기");
+            egui::ComboBox::from_id_salt("inspector_number_style")
+                .selected_text(format.label())
+                .show_ui(ui, |ui| {
+                    for style in NumberStyle::ALL {
+                        changed |= ui.selectable_value(format, style, style.label()).changed();
+                    }
+                });
+            ui.end_row();
+            ui.label("크기");
+            changed |= ui.add(egui::Slider::new(radius, 8.0..=40.0).fixed_decimals(0)).changed();
+            ui.end_row();
+        }
+        DrawShape::Callout { rect, target, text, font_size, .. } => {
+            // 크기는 텍스트에 맞춰 자동으로 조절되므로 위치만 편집
+            let mut min = rect.min;
+            if pos_row(ui, "위치", &mut min) {
+                *rect = Rect::from_min_size(min, rect.size());
+                changed = true;
+            }
+            changed |= pos_row(ui, "꼬리", target);
+            changed |= text_rows(ui, text, font_size);
+        }
+        DrawShape::Spotlight { holes, opacity, desaturate, .. } => {
+            ui.label("영역");
+            ui.label(holes.len().to_string());
+            ui.end_row();
+            ui.label("어둡게");
+            changed |= ui.add(egui::Slider::new(opacity, 0.0..=1.0).fixed_decimals(2)).changed();
+            ui.end_row();
+            ui.label("");
+            changed |= ui.checkbox(desaturate, "흑백").changed();
+            ui.end_row();
+        }
+        DrawShape::Magnifier { source, inset, circular, .. } => {
+            changed |= rect_rows(ui, "원본 ", source);
+            changed |= rect_rows(ui, "확대 ", inset);
+            ui.label("");
+            changed |= ui.checkbox(circular, "원형").changed();
+            ui.end_row();
+        }
+        DrawShape::Image { rect, image, .. } => {
+            changed |= rect_rows(ui, "", rect);
+            let [width, height] = image.pixels.size;
+            ui.label("원본");
+            ui.label(format!("{} × {}", width, height));
+            ui.end_row();
+        }
+    });
+
+    changed
+}
+
+/// x, y 좌표 한 줄
+fn pos_row(ui: &mut Ui, label: &str, pos: &mut Pos2) -> bool {
+    ui.label(label);
+    let changed = ui
+        .horizontal(|ui| {
+            ui.add(DragValue::new(&mut pos.x).prefix("x: ").fixed_decimals(0)).changed()
+                | ui.add(DragValue::new(&mut pos.y).prefix("y: ").fixed_decimals(0)).changed()
+        })
+        .inner;
+    ui.end_row();
+    changed
+}
+
+/// 사각형의 위치와 크기 두 줄
+fn rect_rows(ui: &mut Ui, prefix: &str, rect: &mut Rect) -> bool {
+    let mut min = rect.min;
+    let mut size = rect.size();
+
+    let mut changed = pos_row(ui, &format!("{}위치", prefix), &mut min);
+    ui.label(format!("{}크기", prefix));
+    ui.horizontal(|ui| {
+        changed |= ui.add(DragValue::new(&mut size.x).range(1.0..=f32::MAX).prefix("w: ").fixed_decimals(0)).changed();
+        changed |= ui.add(DragValue::new(&mut size.y).range(1.0..=f32::MAX).prefix("h: ").fixed_decimals(0)).changed();
+    });
+    ui.end_row();
+
+    if changed {
+        *rect = Rect::from_min_size(min, size);
+    }
+    changed
+}
+
+/// 텍스트 내용과 글자 크기
+fn text_rows(ui: &mut Ui, text: &mut String, font_size: &mut f32) -> bool {
+    ui.label("텍스트");
+    let mut changed = ui.add(egui::TextEdit::multiline(text).desired_rows(2).desired_width(180.0)).changed();
+    ui.end_row();
+    ui.label("글자 크기");
+    changed |= ui.add(egui::Slider::new(font_size, 8.0..=72.0).fixed_decimals(0)).changed();
+    ui.end_row();
+    changed
+}
+
+/// 지금 드래그 중이거나 키보드 포커스가 있는 위젯
+fn active_widget(ui: &Ui) -> Option<egui::Id> {
+    ui.ctx().dragged_id().or_else(|| ui.memory(|mem| mem.focused()))
+}
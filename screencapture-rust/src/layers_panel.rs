@@ -0,0 +1,113 @@
+use crate::canvas::Canvas;
+use egui::{Color32, Stroke, Ui};
+
+/// 레이어 목록 패널 (위 레이어가 위쪽에 표시됨)
+#[derive(Default)]
+pub struct LayersPanel {
+    /// 이름을 바꾸는 중인 레이어와 입력 중인 이름
+    renaming: Option<(usize, String)>,
+}
+
+/// 레이어 패널에서 발생한 액션
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayerAction {
+    None,
+    Select(usize),
+    SetVisible(usize, bool),
+    SetLocked(usize, bool),
+    Rename(usize, String),
+    /// 드래그로 순서 변경 (`to`는 이동 후 인덱스)
+    Move { from: usize, to: usize },
+}
+
+impl LayersPanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 레이어 패널 UI 렌더링
+    pub fn ui(&mut self, ui: &mut Ui, canvas: &Canvas) -> LayerAction {
+        let mut action = LayerAction::None;
+        if self.renaming.as_ref().is_some_and(|(index, _)| *index >= canvas.layers.len()) {
+            self.renaming = None;
+        }
+
+        ui.heading("레이어");
+        ui.separator();
+
+        if canvas.layers.is_empty() {
+            ui.label("도형이 없습니다");
+            return action;
+        }
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for index in (0..canvas.layers.len()).rev() {
+                let layer = &canvas.layers[index];
+                let row = ui.horizontal(|ui| {
+                    ui.dnd_drag_source(egui::Id::new(("layer_drag", index)), index, |ui| {
+                        ui.label("☰");
+                    })
+                    .response
+                    .on_hover_text("드래그해서 순서 변경");
+
+                    let visible = ui.selectable_label(layer.visible, "👁").on_hover_text("표시/숨기기");
+                    if visible.clicked() {
+                        action = LayerAction::SetVisible(index, !layer.visible);
+                    }
+                    let locked = ui.selectable_label(layer.locked, "🔒").on_hover_text("잠금 (캔버스에서 선택/편집 불가)");
+                    if locked.clicked() {
+                        action = LayerAction::SetLocked(index, !layer.locked);
+                    }
+
+                    ui.label(layer.shape.type_icon());
+
+                    match &mut self.renaming {
+                        Some((renaming, name)) if *renaming == index => {
+                            let response = ui.add(egui::TextEdit::singleline(name).desired_width(120.0));
+                            if !response.has_focus() && !response.lost_focus() {
+                                response.request_focus();
+                            }
+                            if response.lost_focus() {
+                                // ESC는 취소, 그 외(Enter, 다른 곳 클릭)는 적용
+                                if !ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                                    action = LayerAction::Rename(index, std::mem::take(name));
+                                }
+                                self.renaming = None;
+                            }
+                        }
+                        _ => {
+                            let mut text = egui::RichText::new(canvas.layer_name(index));
+                            if !layer.visible {
+                                text = text.weak();
+                            }
+                            let selected = canvas.selected_shape_index == Some(index);
+                            let name = ui.selectable_label(selected, text).on_hover_text("더블 클릭: 이름 바꾸기");
+                            if name.clicked() {
+                                action = LayerAction::Select(index);
+                            }
+                            if name.double_clicked() {
+                                self.renaming = Some((index, canvas.layer_name(index)));
+                            }
+                        }
+                    }
+                });
+
+                // 드래그 중인 레이어를 놓을 위치 표시 및 이동
+                let response = row.response;
+                if let Some(from) = response.dnd_hover_payload::<usize>() {
+                    if *from != index {
+                        // 목록은 위 레이어부터 표시되므로 위로 올리면 행의 위쪽에 표시
+                        let y = if *from < index { response.rect.top() } else { response.rect.bottom() };
+                        ui.painter()
+                            .hline(response.rect.x_range(), y, Stroke::new(2.0, Color32::from_rgb(0, 120, 215)));
+                    }
+                }
+                if let Some(from) = response.dnd_release_payload::<usize>() {
+                    action = LayerAction::Move { from: *from, to: index };
+                }
+            }
+        });
+
+        action
+    }
+}
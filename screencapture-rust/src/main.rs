@@ -1,46 +1,162 @@
+mod base64;
 mod canvas;
 mod capture;
+mod cli;
 mod clipboard;
+mod color_format;
+mod color_picker;
+mod config;
+mod effects;
+mod file_naming;
+mod gallery;
+mod history;
+mod image_format;
+mod inspector;
+mod layers_panel;
+mod pdf;
+mod pdf_font;
+mod pixel_info;
+mod png_optimize;
+mod recovery;
+mod render;
+mod session;
 mod shapes;
+mod stamps;
+mod style;
+mod svg;
 mod toolbar;
+mod transform;
 
 use canvas::Canvas;
 use capture::capture_screen;
-use clipboard::{copy_color_hex, copy_color_rgb, copy_image_to_clipboard, image_buffer_to_color_image, save_image_to_file};
-use egui::{CentralPanel, Color32, Key, Pos2, Vec2};
+use cli::CliArgs;
+use config::Config;
+use clipboard::{
+    copy_color_cmyk, copy_color_hex, copy_color_rgb, copy_image_to_clipboard, copy_text_to_clipboard,
+    image_buffer_to_color_image, load_image_from_file, paste_image_from_clipboard, save_image_to_file,
+};
+use color_format::{expand_template, ColorFormat};
+use egui::emath::TSTransform;
+use egui::{CentralPanel, Color32, Key, Pos2, Rect};
+use file_naming::{CaptureInfo, CollisionPolicy};
+use gallery::{Gallery, GalleryAction};
+use history::History;
+use image_format::{Compression, ExportOptions, ImageFormat};
+use inspector::Inspector;
+use layers_panel::{LayerAction, LayersPanel};
+use pdf::PdfPage;
+use pixel_info::PixelInfo;
+use session::{Session, SESSION_EXTENSION};
 use shapes::ToolType;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
 use toolbar::{Toolbar, ToolbarAction};
+use transform::ImageOp;
 
 fn main() -> Result<(), eframe::Error> {
+    // Qt 버전과 같이 잘못된 옵션은 종료 코드 10
+    let cli = match CliArgs::parse(std::env::args().skip(1)) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(10);
+        }
+    };
+
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1280.0, 720.0])
             .with_title("ScreenCapture Rust - 화면 캡처 및 주석 도구")
             .with_decorations(true)
-            .with_resizable(true),
+            .with_resizable(true)
+            .with_drag_and_drop(true),
         ..Default::default()
     };
 
     eframe::run_native(
         "ScreenCapture Rust",
         options,
-        Box::new(|_cc| Ok(Box::new(ScreenCaptureApp::new()))),
+        Box::new(|_cc| Ok(Box::new(ScreenCaptureApp::new(cli)))),
     )
 }
 
 struct ScreenCaptureApp {
     canvas: Canvas,
     toolbar: Toolbar,
+    inspector: Inspector,
+    layers_panel: LayersPanel,
+    config: Config,
+    /// 명령줄 `--comp` (저장 설정보다 우선)
+    compression: Option<Compression>,
+    /// 파일 이름 템플릿에 쓰는 현재 캡처의 정보
+    capture_info: CaptureInfo,
+    history: History,
+    /// 지금 편집 중인 캡처의 기록 항목 (기록에 없는 파일을 열었으면 None)
+    history_id: Option<String>,
+    gallery: Gallery,
+    /// 마지막으로 파일에 저장한 시점과 자동 저장한 시점의 캔버스 번호
+    saved_revision: u64,
+    autosaved_revision: u64,
+    last_autosave: Instant,
+    /// 이전 실행에서 저장하지 않고 남은 자동 저장 세션 (복원 여부를 묻는 중)
+    recovered: Option<Session>,
+    /// 저장하지 않은 변경 사항이 있어 닫기 전에 확인하는 중
+    show_close_prompt: bool,
+    /// 확인을 마쳐 창을 닫아도 됨
+    allow_close: bool,
+    /// 도구 모음의 닫기 버튼 (다음 프레임에 창 닫기 요청)
+    close_requested: bool,
     status_message: String,
     show_capture_button: bool,
 }
 
 impl ScreenCaptureApp {
-    fn new() -> Self {
+    fn new(cli: CliArgs) -> Self {
+        let (config, mut status_message) = match Config::load() {
+            Ok(config) => (config, "화면 캡처 버튼을 클릭하여 시작하세요".to_string()),
+            Err(e) => (Config::default(), format!("설정 불러오기 실패: {}", e)),
+        };
+        let recovered = match recovery::load() {
+            Ok(recovered) => recovered,
+            Err(e) => {
+                status_message = format!("자동 저장 파일 불러오기 실패: {}", e);
+                None
+            }
+        };
+        let history = match History::load() {
+            Ok(mut history) => {
+                if let Err(e) = history.apply_retention(&config.history, None) {
+                    status_message = format!("오래된 기록 삭제 실패: {}", e);
+                }
+                history
+            }
+            Err(e) => {
+                status_message = format!("캡처 기록 불러오기 실패: {}", e);
+                History::empty()
+            }
+        };
+        let mut toolbar = Toolbar::new();
+        toolbar.compression = cli.compression;
         Self {
             canvas: Canvas::new(),
-            toolbar: Toolbar::new(),
-            status_message: "화면 캡처 버튼을 클릭하여 시작하세요".to_string(),
+            toolbar,
+            inspector: Inspector::new(),
+            layers_panel: LayersPanel::new(),
+            config,
+            compression: cli.compression,
+            capture_info: CaptureInfo::default(),
+            history,
+            history_id: None,
+            gallery: Gallery::new(),
+            saved_revision: 0,
+            autosaved_revision: 0,
+            last_autosave: Instant::now(),
+            recovered,
+            show_close_prompt: false,
+            allow_close: false,
+            close_requested: false,
+            status_message,
             show_capture_button: true,
         }
     }
@@ -48,10 +164,17 @@ impl ScreenCaptureApp {
     fn capture_screen(&mut self) {
         match capture_screen() {
             Ok(result) => {
+                self.sync_history();
                 let color_image = image_buffer_to_color_image(&result.image);
                 self.canvas.set_background(color_image);
+                self.capture_info = CaptureInfo {
+                    captured_at: Some(chrono::Local::now()),
+                    window_title: result.window_title,
+                    monitor: result.monitor,
+                };
                 self.status_message = format!("화면 캡처 완료: {}x{}", result.width, result.height);
                 self.show_capture_button = false;
+                self.record_history();
             }
             Err(e) => {
                 self.status_message = format!("캡처 실패: {}", e);
@@ -59,7 +182,256 @@ impl ScreenCaptureApp {
         }
     }
 
+    /// 마지막 저장 뒤에 바뀐 내용이 있는지
+    fn has_unsaved_changes(&self) -> bool {
+        self.canvas.background_image.is_some() && self.canvas.revision() != self.saved_revision
+    }
+
+    /// 현재 내용을 저장한 것으로 표시하고 자동 저장 파일 삭제
+    fn mark_saved(&mut self) {
+        self.saved_revision = self.canvas.revision();
+        self.autosaved_revision = self.saved_revision;
+        if let Err(e) = recovery::clear() {
+            self.status_message = format!("자동 저장 파일 삭제 실패: {}", e);
+        }
+    }
+
+    /// 저장하지 않은 변경 사항을 일정 간격으로 복구 폴더에 저장
+    fn autosave(&mut self, ctx: &egui::Context) {
+        // 복원 여부를 묻는 동안은 이전 자동 저장 파일을 덮어쓰지 않음
+        if self.recovered.is_some() {
+            return;
+        }
+        let revision = self.canvas.revision();
+        if !self.has_unsaved_changes() || revision == self.autosaved_revision {
+            return;
+        }
+        let elapsed = self.last_autosave.elapsed();
+        if elapsed < recovery::AUTOSAVE_INTERVAL {
+            ctx.request_repaint_after(recovery::AUTOSAVE_INTERVAL - elapsed);
+            return;
+        }
+        let Some(session) = self.canvas.to_session() else {
+            return;
+        };
+        match recovery::save(&session) {
+            Ok(_) => self.autosaved_revision = revision,
+            Err(e) => self.status_message = format!("자동 저장 실패: {}", e),
+        }
+        self.last_autosave = Instant::now();
+    }
+
+    /// 이전 실행에서 남은 자동 저장 세션을 복원할지 묻는 창
+    fn recovery_prompt(&mut self, ctx: &egui::Context) {
+        let Some(session) = &self.recovered else {
+            return;
+        };
+        let saved_at = chrono::DateTime::parse_from_rfc3339(&session.saved_at)
+            .map(|time| time.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        let count = session.layers.len();
+
+        let mut restore = None;
+        egui::Window::new("편집 내용 복구")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("저장하지 않고 종료된 편집 내용이 있습니다. 복원할까요?");
+                ui.weak(format!("{} 자동 저장, 도형 {}개", saved_at, count));
+                ui.horizontal(|ui| {
+                    if ui.button("복원").clicked() {
+                        restore = Some(true);
+                    }
+                    if ui.button("버리기").clicked() {
+                        restore = Some(false);
+                    }
+                });
+            });
+
+        match restore {
+            Some(true) => {
+                let Some(session) = self.recovered.take() else {
+                    return;
+                };
+                self.sync_history();
+                self.load_session(session);
+                self.capture_info = CaptureInfo::default();
+                self.history_id = None;
+                self.show_capture_button = false;
+                // 복원한 내용은 아직 저장하지 않은 상태 (자동 저장 파일은 같은 내용)
+                self.saved_revision = self.canvas.revision();
+                self.canvas.mark_modified();
+                self.autosaved_revision = self.canvas.revision();
+                self.status_message = format!("편집 내용 복원 (도형 {}개)", count);
+            }
+            Some(false) => {
+                self.recovered = None;
+                self.mark_saved();
+            }
+            None => {}
+        }
+    }
+
+    /// 저장하지 않은 변경 사항이 있을 때 닫기 전에 확인하는 창
+    fn close_prompt(&mut self, ctx: &egui::Context) {
+        if !self.show_close_prompt {
+            return;
+        }
+        enum CloseChoice {
+            Save,
+            Discard,
+            Cancel,
+        }
+        let mut choice = None;
+        egui::Window::new("저장하지 않은 변경 사항")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label("편집한 내용을 저장하지 않았습니다. 닫기 전에 저장할까요?");
+                ui.horizontal(|ui| {
+                    if ui.button("💾 저장 후 닫기").clicked() {
+                        choice = Some(CloseChoice::Save);
+                    }
+                    if ui.button("저장하지 않고 닫기").clicked() {
+                        choice = Some(CloseChoice::Discard);
+                    }
+                    if ui.button("취소").clicked() {
+                        choice = Some(CloseChoice::Cancel);
+                    }
+                });
+            });
+
+        let Some(choice) = choice else {
+            return;
+        };
+        self.show_close_prompt = false;
+        match choice {
+            // 저장 대화상자에서 취소하면 닫지 않음
+            CloseChoice::Save => {
+                self.save_image();
+                if !self.has_unsaved_changes() {
+                    self.allow_close = true;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            }
+            CloseChoice::Discard => {
+                self.allow_close = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+            CloseChoice::Cancel => {}
+        }
+    }
+
+    /// 현재 캡처를 기록에 새 항목으로 추가하고 보관 개수/기간을 넘은 항목 삭제
+    fn record_history(&mut self) {
+        self.history_id = None;
+        if !self.config.history.enabled {
+            return;
+        }
+        let (Some(session), Some(image)) = (self.canvas.to_session(), self.canvas.render_to_image()) else {
+            return;
+        };
+        let result = self.history.add(&session, &image, &self.capture_info).and_then(|id| {
+            self.history.apply_retention(&self.config.history, Some(&id))?;
+            Ok(id)
+        });
+        match result {
+            Ok(id) => self.history_id = Some(id),
+            Err(e) => self.status_message = format!("캡처 기록 저장 실패: {}", e),
+        }
+    }
+
+    /// 편집 중인 캡처의 기록 항목을 현재 레이어와 썸네일로 갱신 (갱신했으면 true)
+    fn sync_history(&mut self) -> bool {
+        let Some(id) = self.history_id.clone() else {
+            return false;
+        };
+        let (Some(session), Some(image)) = (self.canvas.to_session(), self.canvas.render_to_image()) else {
+            return false;
+        };
+        match self.history.update(&id, &session, &image) {
+            Ok(()) => true,
+            Err(e) => {
+                self.status_message = format!("캡처 기록 저장 실패: {}", e);
+                false
+            }
+        }
+    }
+
+    /// 지금 편집 내용을 버려도 잃는 것이 없는지 확인 (파일 열기와 창 닫기에서 함께 사용)
+    ///
+    /// 기록에 있는 캡처는 먼저 기록에 반영하고, 반영했거나 저장하지 않은 변경 사항이 없으면 true.
+    fn edits_are_kept(&mut self) -> bool {
+        self.sync_history() || !self.has_unsaved_changes()
+    }
+
+    /// 세션을 캔버스로 불러오고 도구 모음의 번호 시작 값을 맞춤
+    fn load_session(&mut self, session: Session) {
+        self.canvas.load_session(session);
+        self.toolbar.number_start = self.canvas.number_start;
+    }
+
+    /// 기록 항목을 캔버스로 불러와 다시 편집
+    fn open_history(&mut self, id: &str) {
+        self.sync_history();
+        let Some(entry) = self.history.get(id) else {
+            return;
+        };
+        let info = entry.capture_info();
+        let label = entry.time_label();
+        match self.history.load_session(id) {
+            Ok(session) => {
+                self.load_session(session);
+                self.capture_info = info;
+                self.history_id = Some(id.to_string());
+                self.show_capture_button = false;
+                self.mark_saved();
+                self.status_message = format!("기록 열기: {}", label);
+            }
+            Err(e) => {
+                self.status_message = format!("기록 열기 실패: {}", e);
+            }
+        }
+    }
+
+    fn handle_gallery_action(&mut self, action: GalleryAction) {
+        match action {
+            GalleryAction::Open(id) => self.open_history(&id),
+            GalleryAction::SetTags(id, tags) => {
+                if let Err(e) = self.history.set_tags(&id, tags) {
+                    self.status_message = format!("태그 저장 실패: {}", e);
+                }
+            }
+            GalleryAction::Delete(ids) => {
+                let description = format!("선택한 캡처 기록 {}개를 삭제할까요?", ids.len());
+                if !clipboard::confirm("캡처 기록 삭제", &description) {
+                    return;
+                }
+                if self.history_id.as_ref().is_some_and(|id| ids.contains(id)) {
+                    self.history_id = None;
+                }
+                match self.history.remove(&ids) {
+                    Ok(_) => self.status_message = format!("캡처 기록 {}개 삭제", ids.len()),
+                    Err(e) => self.status_message = format!("캡처 기록 삭제 실패: {}", e),
+                }
+            }
+            GalleryAction::None => {}
+        }
+    }
+
     fn handle_keyboard(&mut self, ctx: &egui::Context) {
+        // 사용자 템플릿 단축키 (기본 단축키보다 먼저 처리)
+        let template = self.config.color_templates.iter().position(|template| {
+            template
+                .keyboard_shortcut()
+                .is_some_and(|shortcut| ctx.input_mut(|i| i.consume_shortcut(&shortcut)))
+        });
+        if let Some(index) = template {
+            self.copy_color_template(index);
+        }
+
         // Ctrl+Z: 실행 취소
         if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(Key::Z)) {
             self.canvas.undo();
@@ -72,9 +444,18 @@ impl ScreenCaptureApp {
             self.status_message = "다시 실행".to_string();
         }
 
-        // Ctrl+S: 저장
+        // Ctrl+S: 저장, Ctrl+Shift+S: 세션 저장
         if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(Key::S)) {
-            self.save_image();
+            if ctx.input(|i| i.modifiers.shift) {
+                self.save_session();
+            } else {
+                self.save_image();
+            }
+        }
+
+        // Ctrl+O: 파일 열기
+        if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(Key::O)) {
+            self.open_file_dialog();
         }
 
         // Ctrl+C: 클립보드 복사
@@ -84,7 +465,7 @@ impl ScreenCaptureApp {
 
         // Ctrl+R: RGB 복사
         if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(Key::R)) {
-            if let Err(e) = copy_color_rgb(self.canvas.current_color) {
+            if let Err(e) = copy_color_rgb(self.color_to_copy()) {
                 self.status_message = format!("RGB 복사 실패: {}", e);
             } else {
                 self.status_message = "RGB 색상이 클립보드에 복사되었습니다".to_string();
@@ -93,31 +474,157 @@ impl ScreenCaptureApp {
 
         // Ctrl+H: HEX 복사
         if ctx.input(|i| i.modifiers.ctrl && i.key_pressed(Key::H)) {
-            if let Err(e) = copy_color_hex(self.canvas.current_color) {
+            if let Err(e) = copy_color_hex(self.color_to_copy()) {
                 self.status_message = format!("HEX 복사 실패: {}", e);
             } else {
                 self.status_message = "HEX 색상이 클립보드에 복사되었습니다".to_string();
             }
         }
 
-        // Delete: 선택된 도형 삭제
-        if ctx.input(|i| i.key_pressed(Key::Delete)) {
+        // Ctrl+K: CMYK 복사 (텍스트 입력 중에는 제외)
+        if !ctx.wants_keyboard_input() && ctx.input(|i| i.modifiers.ctrl && i.key_pressed(Key::K)) {
+            if let Err(e) = copy_color_cmyk(self.color_to_copy()) {
+                self.status_message = format!("CMYK 복사 실패: {}", e);
+            } else {
+                self.status_message = "CMYK 색상이 클립보드에 복사되었습니다".to_string();
+            }
+        }
+
+        // Enter: 자르기 적용
+        if self.canvas.current_tool == ToolType::Crop
+            && !ctx.wants_keyboard_input()
+            && ctx.input(|i| i.key_pressed(Key::Enter))
+        {
+            if let Some(rect) = self.canvas.crop_rect {
+                self.transform_image(ImageOp::Crop(rect));
+            }
+        }
+
+        // Delete: 선택된 도형 삭제 (텍스트 입력 중에는 제외)
+        if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(Key::Delete)) {
             self.canvas.delete_selected();
             self.status_message = "선택된 도형 삭제".to_string();
         }
 
-        // ESC: 종료
-        if ctx.input(|i| i.key_pressed(Key::Escape)) {
+        // ESC: 종료 (텍스트 입력 중에는 입력 취소로 사용)
+        if !ctx.wants_keyboard_input() && ctx.input(|i| i.key_pressed(Key::Escape)) {
             ctx.send_viewport_cmd(egui::ViewportCommand::Close);
         }
     }
 
+    /// 색상 복사 대상
+    fn color_to_copy(&self) -> Color32 {
+        color_format::color_to_copy(self.canvas.sampled_color, self.canvas.current_color)
+    }
+
+    /// 픽셀 정보 돋보기가 보일 때 R/H/K/P 키로 값 복사
+    fn handle_pixel_info_keys(&mut self, ctx: &egui::Context, info: &PixelInfo) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        let key = ctx.input(|i| {
+            if i.modifiers.ctrl || i.modifiers.alt || i.modifiers.command {
+                return None;
+            }
+            [Key::R, Key::H, Key::K, Key::P].into_iter().find(|key| i.key_pressed(*key))
+        });
+        let (name, text) = match key {
+            Some(Key::R) => ("RGB", ColorFormat::Rgb.format(info.color)),
+            Some(Key::H) => ("HEX", ColorFormat::Hex.format(info.color)),
+            Some(Key::K) => ("CMYK", ColorFormat::Cmyk.format(info.color)),
+            Some(Key::P) => ("좌표", info.position_text()),
+            _ => return,
+        };
+        self.copy_text(name, &text);
+    }
+
+    /// 사용자 템플릿 형식으로 색상 복사
+    fn copy_color_template(&mut self, index: usize) {
+        if let Some(template) = self.config.color_templates.get(index) {
+            let name = template.name.clone();
+            let text = expand_template(&template.template, self.color_to_copy());
+            self.copy_text(&name, &text);
+        }
+    }
+
+    /// 텍스트를 복사하고 상태 표시줄에 결과 표시
+    fn copy_text(&mut self, name: &str, text: &str) {
+        match copy_text_to_clipboard(text) {
+            Ok(_) => self.status_message = format!("{} 복사: {}", name, text),
+            Err(e) => self.status_message = format!("{} 복사 실패: {}", name, e),
+        }
+    }
+
+    /// 명령줄 `--comp`를 적용한 저장 설정
+    fn export_options(&self) -> ExportOptions {
+        match self.compression {
+            Some(compression) => self.config.export.with_compression(compression),
+            None => self.config.export,
+        }
+    }
+
+    /// 저장 설정(폴더, 파일 이름 템플릿, 같은 이름 처리)에 따라 저장 경로 선택
+    ///
+    /// 취소하면 None. 경로를 정하면 {counter}를 다음 번호로 넘깁니다.
+    fn choose_save_path(&mut self, extension: &str, filters: &[(&str, &str)]) -> Option<PathBuf> {
+        let save = &self.config.save;
+        let directory = match save.ensure_directory() {
+            Ok(directory) => directory,
+            Err(e) => {
+                self.status_message = format!("저장 폴더를 만들 수 없습니다: {}", e);
+                return None;
+            }
+        };
+        let path = directory.join(save.file_name(&self.capture_info, extension));
+
+        let chosen = if save.show_dialog {
+            // 대화상자에서는 덮어쓰기를 대화상자가 확인하므로 번호 붙이기만 미리 적용
+            let suggested = match save.collision {
+                CollisionPolicy::Increment => file_naming::unique_path(&path),
+                _ => path,
+            };
+            let file_name = suggested.file_name().unwrap_or_default().to_string_lossy();
+            clipboard::show_save_dialog(&directory, &file_name, filters)
+        } else if !path.exists() {
+            Some(path)
+        } else {
+            match save.collision {
+                CollisionPolicy::Overwrite => Some(path),
+                CollisionPolicy::Increment => Some(file_naming::unique_path(&path)),
+                CollisionPolicy::Ask if clipboard::confirm_overwrite(&path) => Some(path),
+                CollisionPolicy::Ask => {
+                    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                    clipboard::show_save_dialog(&directory, &file_name, filters)
+                }
+            }
+        };
+
+        if chosen.is_some() && self.config.save.advance_counter() {
+            self.config.mark_changed();
+        }
+        chosen
+    }
+
     fn save_image(&mut self) {
+        self.sync_history();
         if let Some(image) = self.canvas.render_to_image() {
-            if let Some(path) = clipboard::show_save_dialog() {
-                match save_image_to_file(&image, &path) {
-                    Ok(_) => {
+            let options = self.export_options();
+            // 기본 형식을 맨 앞에 두어 대화상자에서 먼저 선택되게 함
+            let formats = std::iter::once(options.format).chain(ImageFormat::ALL.into_iter().filter(|f| *f != options.format));
+            let filters: Vec<(&str, &str)> = formats.map(|format| (format.label(), format.extension())).collect();
+            if let Some(path) = self.choose_save_path(options.format.extension(), &filters) {
+                // 저장한 PNG를 다시 열면 레이어를 편집할 수 있도록 세션도 함께 저장
+                let session = self.canvas.to_session();
+                match save_image_to_file(&image, &path, &options, session.as_ref()) {
+                    Ok(Some(report)) => {
+                        self.status_message = format!("저장 완료: {} ({})", path.display(), report);
+                        self.mark_saved();
+                        self.config.add_recent_file(&path);
+                    }
+                    Ok(None) => {
                         self.status_message = format!("저장 완료: {}", path.display());
+                        self.mark_saved();
+                        self.config.add_recent_file(&path);
                     }
                     Err(e) => {
                         self.status_message = format!("저장 실패: {}", e);
@@ -129,8 +636,149 @@ impl ScreenCaptureApp {
         }
     }
 
+    /// 원본 캡처와 레이어를 편집 가능한 세션 파일로 저장
+    fn save_session(&mut self) {
+        self.sync_history();
+        let Some(session) = self.canvas.to_session() else {
+            self.status_message = "저장할 이미지가 없습니다".to_string();
+            return;
+        };
+        if let Some(path) = self.choose_save_path(SESSION_EXTENSION, &[("주석 세션", SESSION_EXTENSION)]) {
+            match session.save(&path) {
+                Ok(_) => {
+                    self.status_message = format!("세션 저장 완료: {}", path.display());
+                    self.mark_saved();
+                    self.config.add_recent_file(&path);
+                }
+                Err(e) => self.status_message = format!("세션 저장 실패: {}", e),
+            }
+        }
+    }
+
+    /// 다른 파일을 열기 전에 편집 내용을 버려도 되는지 확인 (잃을 내용이 있을 때만 물어봄)
+    fn confirm_discard(&mut self) -> bool {
+        self.edits_are_kept()
+            || clipboard::confirm("저장하지 않은 변경 사항", "편집한 내용을 저장하지 않았습니다. 버리고 열까요?")
+    }
+
+    /// 파일 대화상자로 이미지나 세션 파일을 선택해 열기
+    fn open_file_dialog(&mut self) {
+        if let Some(path) = clipboard::show_open_file_dialog() {
+            self.open_file(&path);
+        }
+    }
+
+    /// 이미지나 세션 파일을 열어 편집 (세션과 세션이 들어 있는 PNG는 레이어까지 복원)
+    fn open_file(&mut self, path: &Path) {
+        if !self.confirm_discard() {
+            return;
+        }
+        match Session::open(path) {
+            Ok(session) => {
+                let count = session.layers.len();
+                self.load_session(session);
+                self.capture_info = CaptureInfo::default();
+                self.history_id = None;
+                self.show_capture_button = false;
+                self.mark_saved();
+                self.config.add_recent_file(path);
+                self.status_message = format!("열기: {} (도형 {}개)", path.display(), count);
+            }
+            Err(e) => {
+                // 지워지거나 옮겨진 파일은 최근 파일에서 제거
+                if !path.exists() {
+                    self.config.remove_recent_file(path);
+                }
+                self.status_message = format!("열기 실패: {}", e);
+            }
+        }
+    }
+
+    /// 클립보드의 이미지를 새 캡처처럼 열어 편집 (기록에도 추가)
+    fn open_clipboard_image(&mut self) {
+        let image = match paste_image_from_clipboard() {
+            Ok(image) => image,
+            Err(e) => {
+                self.status_message = format!("클립보드 이미지 열기 실패: {}", e);
+                return;
+            }
+        };
+        if !self.confirm_discard() {
+            return;
+        }
+        let [width, height] = image.size;
+        self.load_session(Session::new(Arc::new(image), Vec::new()));
+        self.capture_info = CaptureInfo {
+            captured_at: Some(chrono::Local::now()),
+            ..Default::default()
+        };
+        self.show_capture_button = false;
+        self.mark_saved();
+        self.record_history();
+        self.status_message = format!("클립보드 이미지 열기: {}x{}", width, height);
+    }
+
+    /// 파일을 창 위로 끌고 있을 때 놓으면 열린다는 표시
+    fn drop_overlay(&self, ctx: &egui::Context) {
+        if ctx.input(|i| i.raw.hovered_files.is_empty()) {
+            return;
+        }
+        let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("drop_overlay")));
+        let rect = ctx.screen_rect();
+        painter.rect_filled(rect, 0.0, Color32::from_black_alpha(160));
+        painter.text(
+            rect.center(),
+            egui::Align2::CENTER_CENTER,
+            "놓으면 파일을 엽니다",
+            egui::FontId::proportional(24.0),
+            Color32::WHITE,
+        );
+    }
+
+    /// 도형을 벡터 요소로 유지한 SVG로 내보내기
+    fn export_svg(&mut self) {
+        let Some(result) = self.canvas.export_svg() else {
+            self.status_message = "내보낼 이미지가 없습니다".to_string();
+            return;
+        };
+        let Some(path) = self.choose_save_path("svg", &[("SVG", "svg")]) else {
+            return;
+        };
+        match result.and_then(|svg| std::fs::write(&path, svg).map_err(|e| format!("Failed to write SVG: {}", e))) {
+            Ok(_) => self.status_message = format!("SVG 내보내기 완료: {}", path.display()),
+            Err(e) => self.status_message = format!("SVG 내보내기 실패: {}", e),
+        }
+    }
+
+    /// 페이지들을 PDF 파일로 내보내기
+    fn export_pdf(&mut self, pages: Vec<PdfPage>) {
+        let Some(path) = self.choose_save_path("pdf", &[("PDF", "pdf")]) else {
+            return;
+        };
+        let result = pdf::export_pdf(&pages, &self.config.pdf, &render::export_fonts())
+            .and_then(|bytes| std::fs::write(&path, bytes).map_err(|e| format!("Failed to write PDF: {}", e)));
+        match result {
+            Ok(_) => self.status_message = format!("PDF 내보내기 완료: {} ({}쪽)", path.display(), pages.len()),
+            Err(e) => self.status_message = format!("PDF 내보내기 실패: {}", e),
+        }
+    }
+
+    /// 선택한 세션/이미지 파일을 한 페이지씩 PDF로 합치기
+    fn export_pdf_files(&mut self) {
+        let Some(paths) = clipboard::show_open_pages_dialog() else {
+            return;
+        };
+        let pages: Result<Vec<PdfPage>, String> = paths.iter().map(|path| PdfPage::load(path)).collect();
+        match pages {
+            Ok(pages) => self.export_pdf(pages),
+            Err(e) => self.status_message = format!("PDF 내보내기 실패: {}", e),
+        }
+    }
+
     fn copy_to_clipboard(&mut self) {
         if let Some(image) = self.canvas.render_to_image() {
+            // Qt 버전과 같이 클립보드에는 크기 비율만 적용
+            let image = image_format::scale_image(&image, self.export_options().scale_percent);
             match copy_image_to_clipboard(&image) {
                 Ok(_) => {
                     self.status_message = "클립보드에 복사되었습니다".to_string();
@@ -144,6 +792,80 @@ impl ScreenCaptureApp {
         }
     }
 
+    fn paste_image_layer(&mut self) {
+        if self.canvas.background_image.is_none() {
+            self.status_message = "먼저 화면을 캡처하세요".to_string();
+            return;
+        }
+        match paste_image_from_clipboard() {
+            Ok(image) => {
+                self.canvas.insert_image(image);
+                self.status_message = "클립보드 이미지를 삽입했습니다".to_string();
+            }
+            Err(e) => {
+                self.status_message = format!("이미지 붙여넣기 실패: {}", e);
+            }
+        }
+    }
+
+    fn insert_image_file(&mut self) {
+        if self.canvas.background_image.is_none() {
+            self.status_message = "먼저 화면을 캡처하세요".to_string();
+            return;
+        }
+        if let Some(path) = clipboard::show_open_image_dialog() {
+            match load_image_from_file(&path) {
+                Ok(image) => {
+                    self.canvas.insert_image(image);
+                    self.status_message = format!("이미지 삽입: {}", path.display());
+                }
+                Err(e) => {
+                    self.status_message = format!("이미지 불러오기 실패: {}", e);
+                }
+            }
+        }
+    }
+
+    fn handle_layer_action(&mut self, action: LayerAction) {
+        match action {
+            LayerAction::Select(index) => {
+                self.canvas.select_layer(index);
+            }
+            LayerAction::SetVisible(index, visible) => {
+                self.canvas.set_layer_visible(index, visible);
+                self.status_message = format!("{} {}", self.canvas.layer_name(index), if visible { "표시" } else { "숨김" });
+            }
+            LayerAction::SetLocked(index, locked) => {
+                self.canvas.set_layer_locked(index, locked);
+                self.status_message = format!("{} {}", self.canvas.layer_name(index), if locked { "잠금" } else { "잠금 해제" });
+            }
+            LayerAction::Rename(index, name) => {
+                self.canvas.rename_layer(index, &name);
+            }
+            LayerAction::Move { from, to } => {
+                self.canvas.move_layer(from, to);
+                self.status_message = "레이어 순서를 변경했습니다".to_string();
+            }
+            LayerAction::None => {}
+        }
+    }
+
+    /// 이미지 자르기/크기 조절/회전/반전 (도형도 함께 변환)
+    fn transform_image(&mut self, op: ImageOp) {
+        match self.canvas.transform_image(op) {
+            Ok([width, height]) => {
+                // 자르기가 끝나면 선택 도구로 돌아감
+                if matches!(op, ImageOp::Crop(_)) {
+                    self.toolbar.current_tool = ToolType::Select;
+                }
+                self.status_message = format!("{}: {} × {}", op.label(), width, height);
+            }
+            Err(e) => {
+                self.status_message = format!("{} 실패: {}", op.label(), e);
+            }
+        }
+    }
+
     fn handle_toolbar_action(&mut self, action: ToolbarAction) {
         match action {
             ToolbarAction::Undo => {
@@ -160,22 +882,89 @@ impl ScreenCaptureApp {
             ToolbarAction::CopyToClipboard => {
                 self.copy_to_clipboard();
             }
+            ToolbarAction::SaveSession => {
+                self.save_session();
+            }
+            ToolbarAction::OpenFile => {
+                self.open_file_dialog();
+            }
+            ToolbarAction::OpenClipboardImage => {
+                self.open_clipboard_image();
+            }
+            ToolbarAction::OpenRecent(index) => {
+                if let Some(path) = self.config.recent_files.get(index).cloned() {
+                    self.open_file(&path);
+                }
+            }
+            ToolbarAction::ClearRecentFiles => {
+                self.config.recent_files.clear();
+                self.config.mark_changed();
+            }
+            ToolbarAction::ExportSvg => {
+                self.export_svg();
+            }
+            ToolbarAction::ExportPdf => {
+                match self.canvas.to_session() {
+                    Some(session) => self.export_pdf(vec![PdfPage::from_session(session)]),
+                    None => self.status_message = "내보낼 이미지가 없습니다".to_string(),
+                }
+            }
+            ToolbarAction::ChooseSaveFolder => {
+                let current = self.config.save.directory.clone().unwrap_or_else(file_naming::pictures_dir);
+                if let Some(directory) = clipboard::show_pick_folder_dialog(&current) {
+                    self.status_message = format!("저장 폴더: {}", directory.display());
+                    self.config.save.directory = Some(directory);
+                    self.config.mark_changed();
+                }
+            }
+            ToolbarAction::ExportPdfFiles => {
+                self.export_pdf_files();
+            }
+            ToolbarAction::PasteImageLayer => {
+                self.paste_image_layer();
+            }
+            ToolbarAction::InsertImageFile => {
+                self.insert_image_file();
+            }
             ToolbarAction::CopyColorRGB => {
-                if let Err(e) = copy_color_rgb(self.canvas.current_color) {
+                if let Err(e) = copy_color_rgb(self.color_to_copy()) {
                     self.status_message = format!("RGB 복사 실패: {}", e);
                 } else {
                     self.status_message = "RGB 색상이 클립보드에 복사되었습니다".to_string();
                 }
             }
             ToolbarAction::CopyColorHEX => {
-                if let Err(e) = copy_color_hex(self.canvas.current_color) {
+                if let Err(e) = copy_color_hex(self.color_to_copy()) {
                     self.status_message = format!("HEX 복사 실패: {}", e);
                 } else {
                     self.status_message = "HEX 색상이 클립보드에 복사되었습니다".to_string();
                 }
             }
+            ToolbarAction::CopyColorFormat(format) => {
+                self.copy_text(format.label(), &format.format(self.color_to_copy()));
+            }
+            ToolbarAction::CopyColorTemplate(index) => {
+                self.copy_color_template(index);
+            }
+            ToolbarAction::UseSampledColor => {
+                if let Some(color) = self.canvas.sampled_color {
+                    self.toolbar.current_color = color;
+                    self.config.add_recent_color(color);
+                    self.status_message = "추출한 색을 펜 색으로 설정했습니다".to_string();
+                }
+            }
+            ToolbarAction::RenumberBadges => {
+                self.canvas.renumber_badges_with_undo();
+                self.status_message = "번호를 다시 매겼습니다".to_string();
+            }
+            ToolbarAction::TransformImage(op) => {
+                self.transform_image(op);
+            }
+            ToolbarAction::ShowHistory => {
+                self.gallery.open = true;
+            }
             ToolbarAction::Close => {
-                std::process::exit(0);
+                self.close_requested = true;
             }
             ToolbarAction::None => {}
         }
@@ -187,14 +976,53 @@ impl eframe::App for ScreenCaptureApp {
         // 키보드 입력 처리
         self.handle_keyboard(ctx);
 
+        // 창을 닫기 전에 편집 내용을 기록에 반영하고, 잃을 내용이 있으면 확인
+        if std::mem::take(&mut self.close_requested) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+        if ctx.input(|i| i.viewport().close_requested()) {
+            if !self.edits_are_kept() && !self.allow_close {
+                ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                self.show_close_prompt = true;
+            } else if self.recovered.is_none() {
+                // 정상 종료이므로 자동 저장 파일은 필요 없음 (실패해도 다음 실행에서 물어봄)
+                // 복원 여부를 묻는 중이면 다음 실행에서 다시 묻도록 남겨 둠
+                let _ = recovery::clear();
+            }
+        }
+        self.autosave(ctx);
+        self.recovery_prompt(ctx);
+        self.close_prompt(ctx);
+
+        // 창에 끌어다 놓은 파일 열기 (여러 개면 첫 번째)
+        let dropped = ctx.input(|i| i.raw.dropped_files.iter().find_map(|file| file.path.clone()));
+        if let Some(path) = dropped {
+            self.open_file(&path);
+        }
+        self.drop_overlay(ctx);
+
         // 캔버스 텍스처 업데이트
         self.canvas.update_texture(ctx);
 
         // 상단 패널 (도구 모음)
         egui::TopBottomPanel::top("toolbar").show(ctx, |ui| {
-            let action = self.toolbar.ui(ui);
+            self.toolbar.sampled_color = self.canvas.sampled_color;
+            self.toolbar.image_size = self.canvas.background_image.as_ref().map(|image| image.size);
+            let crop_rect = self.canvas.crop_rect.or_else(|| self.canvas.image_bounds()).unwrap_or(Rect::NOTHING);
+            self.toolbar.crop_rect = crop_rect;
+            let action = self.toolbar.ui(ui, &mut self.config);
+            if self.toolbar.crop_rect != crop_rect {
+                self.canvas.crop_rect = Some(self.toolbar.crop_rect);
+            }
             self.handle_toolbar_action(action);
 
+            // 팔레트/최근 색 변경 사항 저장
+            if self.config.take_changed() {
+                if let Err(e) = self.config.save() {
+                    self.status_message = format!("설정 저장 실패: {}", e);
+                }
+            }
+
             // 캡처 버튼
             if self.show_capture_button {
                 ui.separator();
@@ -211,20 +1039,72 @@ impl eframe::App for ScreenCaptureApp {
             ui.horizontal(|ui| {
                 ui.label(&self.status_message);
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    ui.label(format!("도형: {}", self.canvas.shapes.len()));
-                    if self.canvas.undo_stack.len() > 0 {
+                    ui.label(format!("도형: {}", self.canvas.layers.len()));
+                    if !self.canvas.undo_stack.is_empty() {
                         ui.label(format!("실행 취소 가능: {}", self.canvas.undo_stack.len()));
                     }
                 });
             });
         });
 
+        // 캡처 기록 갤러리
+        if self.gallery.open {
+            let current = self.history_id.clone();
+            let action = self.gallery.ui(ctx, &self.history, current.as_deref(), &mut self.config);
+            self.handle_gallery_action(action);
+        }
+
+        // 왼쪽 패널 (레이어 목록)
+        if self.toolbar.show_layers {
+            let action = egui::SidePanel::left("layers")
+                .show(ctx, |ui| self.layers_panel.ui(ui, &self.canvas))
+                .inner;
+            self.handle_layer_action(action);
+        }
+
+        // 오른쪽 패널 (선택된 도형의 속성, 잠긴 레이어는 읽기 전용)
+        if let Some(layer) = self.canvas.selected_shape_index.and_then(|i| self.canvas.layers.get(i)) {
+            let edit = egui::SidePanel::right("inspector")
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.add_enabled_ui(!layer.locked, |ui| self.inspector.ui(ui, &layer.shape, &mut self.config))
+                        .inner
+                })
+                .inner;
+            if let Some(mut edit) = edit {
+                // 말풍선은 텍스트나 글자 크기가 바뀌면 크기를 다시 맞춤
+                ctx.fonts(|fonts| edit.shape.fit_to_text(fonts));
+                self.canvas.update_selected_shape(edit.shape, edit.merge);
+            }
+        }
+
         // 중앙 패널 (캔버스)
         CentralPanel::default().show(ctx, |ui| {
             // 도구 상태 동기화
             self.canvas.current_tool = self.toolbar.current_tool;
             self.canvas.current_color = self.toolbar.current_color;
             self.canvas.current_stroke_width = self.toolbar.current_stroke_width;
+            self.canvas.current_fill = self.toolbar.fill_color();
+            self.canvas.current_opacity = self.toolbar.current_opacity;
+            self.canvas.current_dash = self.toolbar.current_dash;
+            self.canvas.current_line_cap = self.toolbar.current_line_cap;
+            self.canvas.current_line_join = self.toolbar.current_line_join;
+            self.canvas.current_corner_radius = self.toolbar.current_corner_radius;
+            self.canvas.current_smoothing = self.toolbar.current_smoothing;
+            self.canvas.current_arrow_style = self.toolbar.current_arrow_style;
+            self.canvas.number_style = self.toolbar.number_style;
+            self.canvas.number_radius = self.toolbar.number_radius;
+            self.canvas.set_number_start(self.toolbar.number_start);
+            self.canvas.spotlight_ellipse = self.toolbar.spotlight_ellipse;
+            self.canvas.spotlight_opacity = self.toolbar.spotlight_opacity;
+            self.canvas.spotlight_desaturate = self.toolbar.spotlight_desaturate;
+            self.canvas.magnifier_zoom = self.toolbar.magnifier_zoom;
+            self.canvas.magnifier_circular = self.toolbar.magnifier_circular;
+            self.canvas.current_stamp = self.toolbar.current_stamp;
+            self.canvas.sample_size = self.toolbar.sample_size;
+            if self.canvas.current_tool != ToolType::Crop {
+                self.canvas.crop_rect = None;
+            }
 
             // 배경 이미지 렌더링
             if let Some(ref texture) = self.canvas.background_texture {
@@ -239,38 +1119,94 @@ impl eframe::App for ScreenCaptureApp {
                 let scaled_size = image_size * scale;
                 let (rect, response) = ui.allocate_exact_size(scaled_size, egui::Sense::click_and_drag());
 
+                // 도형은 이미지 좌표로 저장되므로 화면 좌표와 상호 변환
+                let to_screen = TSTransform::new(rect.min.to_vec2(), scale);
+                let to_image = to_screen.inverse();
+                self.canvas.view_scale = scale;
+                let painter = ui.painter_at(rect);
+
                 // 배경 이미지 그리기
-                ui.painter().image(
+                painter.image(
                     texture.id(),
                     rect,
                     egui::Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
                     Color32::WHITE,
                 );
 
-                // 그려진 도형들 렌더링
-                for shape in &self.canvas.shapes {
-                    for egui_shape in shape.to_egui_shape() {
-                        ui.painter().add(egui_shape);
+                // 그려진 도형들 렌더링 (현재 그리는 중인 도형 포함)
+                let background = texture.id();
+                let layer_textures = self.canvas.layer_texture_ids();
+                let egui_shapes = ui.fonts(|fonts| {
+                    let paint_ctx = shapes::PaintContext {
+                        fonts,
+                        background,
+                        background_size: image_size,
+                        layer_textures: &layer_textures,
+                    };
+                    self.canvas
+                        .visible_shapes()
+                        .chain(self.canvas.current_shape.as_ref())
+                        .flat_map(|shape| shape.to_egui_shape(&paint_ctx))
+                        .map(|mut shape| {
+                            shape.transform(to_screen);
+                            shape
+                        })
+                        .collect::<Vec<_>>()
+                });
+                painter.extend(egui_shapes);
+
+                // 선택된 도형의 핸들 표시
+                let selected = self.canvas.selected_shape_index.and_then(|i| self.canvas.layers.get(i));
+                if let Some(layer) = selected.filter(|layer| layer.is_editable()) {
+                    for handle in layer.shape.handles() {
+                        painter.circle(
+                            to_screen * handle,
+                            canvas::HANDLE_RADIUS * 0.7,
+                            Color32::WHITE,
+                            egui::Stroke::new(1.0, Color32::from_rgb(0, 120, 215)),
+                        );
                     }
                 }
 
-                // 현재 그리는 중인 도형 렌더링
-                if let Some(ref current_shape) = self.canvas.current_shape {
-                    for egui_shape in current_shape.to_egui_shape() {
-                        ui.painter().add(egui_shape);
+                // 자르기 영역 (바깥은 어둡게, 모서리/변에 핸들)
+                if self.canvas.current_tool == ToolType::Crop {
+                    let crop = self.canvas.crop_rect.map_or(rect, |crop| to_screen * crop);
+                    paint_crop_overlay(&painter, rect, crop);
+                }
+
+                // 커서 옆 픽셀 정보 돋보기
+                let show_pixel_info = self.toolbar.show_pixel_info || self.canvas.current_tool == ToolType::Eyedropper;
+                let image = self.canvas.background_image.clone();
+                if let (true, Some(pointer), Some(image)) = (show_pixel_info, response.hover_pos(), image) {
+                    if let Some(info) = PixelInfo::at(&image, to_image * pointer) {
+                        pixel_info::show(ui.ctx(), &image, &info, pointer);
+                        self.handle_pixel_info_keys(ui.ctx(), &info);
                     }
                 }
 
+                // 스포이트와 자르기는 십자 커서로 표시
+                if matches!(self.canvas.current_tool, ToolType::Eyedropper | ToolType::Crop) && response.hovered() {
+                    ui.ctx().set_cursor_icon(egui::CursorIcon::Crosshair);
+                }
+
                 // 마우스 입력 처리
+                // 드래그는 임계값을 넘은 뒤에 시작되므로 실제로 누른 위치에서 시작
+                if response.drag_started() {
+                    if let Some(pos) = ui.input(|i| i.pointer.press_origin()) {
+                        self.canvas.on_mouse_press(to_image * pos);
+                    }
+                }
+
                 if response.clicked() {
                     if let Some(pos) = response.interact_pointer_pos() {
-                        self.canvas.on_mouse_press(pos);
+                        self.canvas.on_mouse_press(to_image * pos);
+                        self.canvas.on_mouse_release();
                     }
                 }
 
                 if response.dragged() {
                     if let Some(pos) = response.interact_pointer_pos() {
-                        self.canvas.on_mouse_drag(pos);
+                        self.canvas.on_mouse_drag(to_image * pos);
                     }
                 }
 
@@ -278,13 +1214,20 @@ impl eframe::App for ScreenCaptureApp {
                     self.canvas.on_mouse_release();
                 }
 
+                // 더블 클릭으로 텍스트/말풍선 내용 편집
+                if response.double_clicked() && self.canvas.current_tool == ToolType::Select {
+                    if let Some(pos) = response.interact_pointer_pos() {
+                        self.canvas.begin_text_edit(to_image * pos);
+                    }
+                }
+
                 // 텍스트 입력 처리
                 if self.canvas.text_input_pos.is_some() {
                     ui.horizontal(|ui| {
                         ui.label("텍스트:");
                         let text_edit = ui.text_edit_singleline(&mut self.canvas.text_input);
                         if text_edit.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter)) {
-                            self.canvas.finish_text_input();
+                            ui.fonts(|fonts| self.canvas.finish_text_input(fonts));
                         }
                     });
                 }
@@ -296,3 +1239,28 @@ impl eframe::App for ScreenCaptureApp {
         });
     }
 }
+
+/// 자르기 영역 표시 (화면 좌표, 잘려 나갈 부분은 어둡게)
+fn paint_crop_overlay(painter: &egui::Painter, image: Rect, crop: Rect) {
+    let shade = Color32::from_black_alpha(140);
+    let outside = [
+        Rect::from_min_max(image.min, Pos2::new(image.max.x, crop.min.y)),
+        Rect::from_min_max(Pos2::new(image.min.x, crop.max.y), image.max),
+        Rect::from_min_max(Pos2::new(image.min.x, crop.min.y), Pos2::new(crop.min.x, crop.max.y)),
+        Rect::from_min_max(Pos2::new(crop.max.x, crop.min.y), Pos2::new(image.max.x, crop.max.y)),
+    ];
+    for rect in outside.into_iter().filter(|rect| rect.is_positive()) {
+        painter.rect_filled(rect, 0.0, shade);
+    }
+
+    let accent = Color32::from_rgb(0, 120, 215);
+    painter.rect_stroke(crop, 0.0, egui::Stroke::new(1.0, Color32::WHITE));
+    for handle in canvas::crop_handles(crop) {
+        painter.rect(
+            Rect::from_center_size(handle, egui::vec2(8.0, 8.0)),
+            0.0,
+            Color32::WHITE,
+            egui::Stroke::new(1.0, accent),
+        );
+    }
+}
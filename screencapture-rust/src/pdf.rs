@@ -0,0 +1,857 @@
+use crate::effects;
+use crate::pdf_font::PdfFonts;
+use crate::session::{png_chunks, Session};
+use crate::shapes::{self, ArrowStyle, DrawShape, CALLOUT_PADDING, CALLOUT_ROUNDING};
+use crate::style::{DashPattern, LineCap, LineJoin, ShapeStyle};
+use egui::epaint::text::{Fonts, Galley};
+use egui::{Color32, ColorImage, FontId, Pos2, Rect, Vec2};
+use image::ImageEncoder;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Arc;
+
+/// 화면 픽셀(96 DPI)을 PDF 포인트(1/72 인치)로 바꾸는 비율
+const POINTS_PER_PIXEL: f32 = 0.75;
+
+const POINTS_PER_MM: f32 = 72.0 / 25.4;
+
+/// 원호를 3차 베지어로 근사할 때 반지름 대비 제어점 거리
+const KAPPA: f32 = 0.552_284_8;
+
+/// 용지 크기
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PageSize {
+    FitImage, // 캡처 크기 + 여백
+    A4,
+    A3,
+    Letter,
+    Legal,
+}
+
+impl PageSize {
+    pub const ALL: [PageSize; 5] = [PageSize::FitImage, PageSize::A4, PageSize::A3, PageSize::Letter, PageSize::Legal];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PageSize::FitImage => "캡처 크기에 맞춤",
+            PageSize::A4 => "A4",
+            PageSize::A3 => "A3",
+            PageSize::Letter => "Letter",
+            PageSize::Legal => "Legal",
+        }
+    }
+
+    /// 세로 방향 [너비, 높이] (포인트, 캡처 크기에 맞추면 None)
+    fn points(self) -> Option<[f32; 2]> {
+        match self {
+            PageSize::FitImage => None,
+            PageSize::A4 => Some([595.28, 841.89]),
+            PageSize::A3 => Some([841.89, 1190.55]),
+            PageSize::Letter => Some([612.0, 792.0]),
+            PageSize::Legal => Some([612.0, 1008.0]),
+        }
+    }
+}
+
+/// 용지 방향
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PageOrientation {
+    Auto, // 캡처가 가로로 길면 가로 방향
+    Portrait,
+    Landscape,
+}
+
+impl PageOrientation {
+    pub const ALL: [PageOrientation; 3] = [PageOrientation::Auto, PageOrientation::Portrait, PageOrientation::Landscape];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PageOrientation::Auto => "자동",
+            PageOrientation::Portrait => "세로",
+            PageOrientation::Landscape => "가로",
+        }
+    }
+}
+
+/// PDF 내보내기 설정
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PdfOptions {
+    pub page_size: PageSize,
+    pub orientation: PageOrientation,
+    /// 용지 가장자리 여백 (mm)
+    pub margin_mm: f32,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            page_size: PageSize::A4,
+            orientation: PageOrientation::Auto,
+            margin_mm: 10.0,
+        }
+    }
+}
+
+impl PdfOptions {
+    /// 캡처 크기에 따른 용지 크기 (포인트)와 픽셀당 포인트 배율
+    ///
+    /// 작은 캡처는 원래 크기(96 DPI)로 두고, 여백 안에 들어가지 않는 캡처만 축소합니다.
+    fn layout(&self, image: Vec2) -> (Vec2, f32) {
+        let margin = self.margin_mm.max(0.0) * POINTS_PER_MM;
+        let Some([short, long]) = self.page_size.points() else {
+            return (image * POINTS_PER_PIXEL + Vec2::splat(margin * 2.0), POINTS_PER_PIXEL);
+        };
+        let landscape = match self.orientation {
+            PageOrientation::Auto => image.x > image.y,
+            PageOrientation::Portrait => false,
+            PageOrientation::Landscape => true,
+        };
+        let page = if landscape { Vec2::new(long, short) } else { Vec2::new(short, long) };
+        let available = (page - Vec2::splat(margin * 2.0)).max(Vec2::splat(1.0));
+        let scale = POINTS_PER_PIXEL.min(available.x / image.x).min(available.y / image.y);
+        (page, scale)
+    }
+}
+
+/// PDF 한 페이지에 들어갈 캡처와 도형
+pub struct PdfPage {
+    pub background: Arc<ColorImage>,
+    pub shapes: Vec<DrawShape>,
+}
+
+impl PdfPage {
+    /// 세션의 보이는 레이어로 페이지 생성
+    pub fn from_session(session: Session) -> Self {
+        Self {
+            background: session.background,
+            shapes: session.layers.into_iter().filter(|layer| layer.visible).map(|layer| layer.shape).collect(),
+        }
+    }
+
+    /// 세션 파일이나 세션이 들어 있는 PNG는 도형까지, 일반 이미지는 이미지만 불러옴
+    pub fn load(path: &Path) -> Result<Self, String> {
+        Session::open(path).map(Self::from_session)
+    }
+}
+
+/// 주석이 그려진 캡처들을 한 페이지씩 PDF 문서로 변환
+///
+/// 캡처는 이미지로 넣고 도형은 PDF 벡터 경로로, 텍스트는 쓴 글자만 남긴 TrueType 글꼴을 넣어
+/// 선택하고 검색할 수 있는 글자로 씁니다. 기본 글꼴에 없는 글자(한글 등)는 시스템 글꼴에서 찾습니다.
+pub fn export_pdf(pages: &[PdfPage], options: &PdfOptions, fonts: &Fonts) -> Result<Vec<u8>, String> {
+    if pages.is_empty() {
+        return Err("No pages to export".to_string());
+    }
+
+    let mut pdf = PdfWriter::default();
+    let catalog = pdf.reserve();
+    let page_tree = pdf.reserve();
+
+    let mut kids = Vec::with_capacity(pages.len());
+    for page in pages {
+        kids.push(write_page(&mut pdf, page, options, fonts, page_tree)?);
+    }
+    pdf.write_fonts()?;
+
+    let kids: Vec<String> = kids.iter().map(|id| format!("{} 0 R", id)).collect();
+    pdf.set(
+        page_tree,
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), kids.len()),
+    );
+    pdf.set(catalog, format!("<< /Type /Catalog /Pages {} 0 R >>", page_tree));
+    let info = pdf.add(format!(
+        "<< /Producer (ScreenCapture) /CreationDate (D:{}) >>",
+        chrono::Local::now().format("%Y%m%d%H%M%S")
+    ));
+    Ok(pdf.finish(catalog, info))
+}
+
+/// 페이지 하나를 쓰고 페이지 객체 번호 반환
+fn write_page(
+    pdf: &mut PdfWriter,
+    page: &PdfPage,
+    options: &PdfOptions,
+    fonts: &Fonts,
+    parent: usize,
+) -> Result<usize, String> {
+    // 스포트라이트처럼 픽셀을 바꾸는 배경 효과는 캡처 이미지에 미리 적용
+    let mut capture = ColorImage::clone(&page.background);
+    effects::apply_effects(&mut capture, page.shapes.iter());
+    let image_size = Vec2::new(capture.width() as f32, capture.height() as f32);
+    let (page_size, scale) = options.layout(image_size);
+    let margin = options.margin_mm.max(0.0) * POINTS_PER_MM;
+    let origin = Pos2::new((page_size.x - image_size.x * scale) * 0.5, page_size.y - margin);
+
+    let capture_id = pdf.image(&capture)?;
+    let mut content = Content {
+        pdf,
+        fonts,
+        out: String::new(),
+        images: Vec::new(),
+        alphas: Vec::new(),
+        capture: capture_id,
+        capture_size: image_size,
+    };
+
+    // 이후 좌표는 캡처 이미지 좌표 (왼쪽 위 원점, y 아래로)
+    // 큰 캡처에서 반올림 오차가 쌓이지 않도록 배율은 그대로 씀
+    content.line(&format!("q {} 0 0 {} {} {} cm", scale, -scale, num(origin.x), num(origin.y)));
+    content.line(&format!("0 0 {} {} re W n", num(image_size.x), num(image_size.y)));
+    content.image(capture_id, Rect::from_min_size(Pos2::ZERO, image_size));
+    for shape in &page.shapes {
+        content.shape(shape)?;
+    }
+    content.line("Q");
+
+    let Content { out, images, alphas, .. } = content;
+    let stream = pdf.add_stream("", out.as_bytes());
+    let images: Vec<String> = images.iter().enumerate().map(|(i, id)| format!("/Im{} {} 0 R", i, id)).collect();
+    let alphas: Vec<String> = alphas
+        .iter()
+        .map(|&alpha| format!("/A{} {} 0 R", alpha, pdf.alpha_state(alpha)))
+        .collect();
+    let fonts: Vec<String> = pdf.font_objects.iter().map(|(font, id)| format!("/F{} {} 0 R", font, id)).collect();
+    Ok(pdf.add(format!(
+        "<< /Type /Page /Parent {} 0 R /MediaBox [0 0 {} {}] /Resources << /Font << {} >> /XObject << {} >> /ExtGState << {} >> >> /Contents {} 0 R >>",
+        parent,
+        num(page_size.x),
+        num(page_size.y),
+        fonts.join(" "),
+        images.join(" "),
+        alphas.join(" "),
+        stream
+    )))
+}
+
+/// PDF 객체 목록 (객체 번호는 인덱스 + 1)
+#[derive(Default)]
+struct PdfWriter {
+    objects: Vec<Vec<u8>>,
+    /// 불투명도별 그래픽 상태 객체
+    alpha_states: HashMap<u8, usize>,
+    /// 여러 페이지에 같은 이미지 레이어가 있으면 한 번만 넣음
+    layer_images: HashMap<u64, usize>,
+    fonts: PdfFonts,
+    /// 쓴 글꼴별 Type0 글꼴 객체 (`/F{글꼴 번호}`, 모든 페이지를 쓴 뒤 채움)
+    font_objects: BTreeMap<usize, usize>,
+}
+
+impl PdfWriter {
+    /// 나중에 내용을 채울 객체 번호 예약
+    fn reserve(&mut self) -> usize {
+        self.objects.push(Vec::new());
+        self.objects.len()
+    }
+
+    fn set(&mut self, id: usize, body: String) {
+        self.objects[id - 1] = body.into_bytes();
+    }
+
+    fn add(&mut self, body: String) -> usize {
+        let id = self.reserve();
+        self.set(id, body);
+        id
+    }
+
+    /// 스트림 객체 추가 (`dict`는 /Length를 제외한 사전 항목)
+    fn add_stream(&mut self, dict: &str, data: &[u8]) -> usize {
+        let mut body = format!("<< {} /Length {} >>\nstream\n", dict, data.len()).into_bytes();
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\nendstream");
+        self.objects.push(body);
+        self.objects.len()
+    }
+
+    fn alpha_state(&mut self, alpha: u8) -> usize {
+        if let Some(&id) = self.alpha_states.get(&alpha) {
+            return id;
+        }
+        let value = num(alpha as f32 / 255.0);
+        let id = self.add(format!("<< /Type /ExtGState /CA {} /ca {} >>", value, value));
+        self.alpha_states.insert(alpha, id);
+        id
+    }
+
+    /// 이미지를 RGB 이미지 객체로 추가 (투명한 픽셀이 있으면 알파를 소프트 마스크로)
+    fn image(&mut self, image: &ColorImage) -> Result<usize, String> {
+        let mut rgb = Vec::with_capacity(image.pixels.len() * 3);
+        let mut alpha = Vec::with_capacity(image.pixels.len());
+        for pixel in &image.pixels {
+            let [r, g, b, a] = pixel.to_srgba_unmultiplied();
+            rgb.extend_from_slice(&[r, g, b]);
+            alpha.push(a);
+        }
+
+        let mask = if alpha.iter().any(|&a| a < u8::MAX) {
+            Some(self.flate_image(image.size, &alpha, false, None)?)
+        } else {
+            None
+        };
+        self.flate_image(image.size, &rgb, true, mask)
+    }
+
+    /// 픽셀 데이터를 PNG로 압축해 IDAT 데이터를 그대로 FlateDecode 스트림으로 사용
+    ///
+    /// PDF의 PNG 예측 필터(/Predictor 15)가 PNG의 줄 단위 필터와 같아서 다시 압축할 필요가 없습니다.
+    fn flate_image(&mut self, size: [usize; 2], data: &[u8], rgb: bool, mask: Option<usize>) -> Result<usize, String> {
+        let [width, height] = size;
+        let (color_type, color_space, colors) = if rgb {
+            (image::ExtendedColorType::Rgb8, "/DeviceRGB", 3)
+        } else {
+            (image::ExtendedColorType::L8, "/DeviceGray", 1)
+        };
+
+        let mut png = Vec::new();
+        image::codecs::png::PngEncoder::new(&mut png)
+            .write_image(data, width as u32, height as u32, color_type)
+            .map_err(|e| format!("Failed to encode PDF image: {}", e))?;
+        let idat: Vec<u8> = png_chunks(&png)?
+            .iter()
+            .filter(|chunk| chunk.kind == *b"IDAT")
+            .flat_map(|chunk| chunk.data.iter().copied())
+            .collect();
+
+        let mask = mask.map(|id| format!(" /SMask {} 0 R", id)).unwrap_or_default();
+        let dict = format!(
+            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace {} /BitsPerComponent 8 /Filter /FlateDecode /DecodeParms << /Predictor 15 /Colors {} /BitsPerComponent 8 /Columns {} >>{}",
+            width, height, color_space, colors, width, mask
+        );
+        Ok(self.add_stream(&dict, &idat))
+    }
+
+    /// 글꼴 객체 번호 (처음 쓰는 글꼴이면 예약)
+    fn font_object(&mut self, font: usize) -> usize {
+        if let Some(&id) = self.font_objects.get(&font) {
+            return id;
+        }
+        let id = self.reserve();
+        self.font_objects.insert(font, id);
+        id
+    }
+
+    /// 쓴 글꼴을 부분 집합 TrueType 글꼴(Type0/CIDFontType2, Identity-H)로 추가
+    ///
+    /// ToUnicode CMap으로 글리프를 글자로 되돌려 텍스트를 선택하고 복사할 수 있습니다.
+    fn write_fonts(&mut self) -> Result<(), String> {
+        let objects: Vec<(usize, usize)> = self.font_objects.iter().map(|(&font, &id)| (font, id)).collect();
+        for (font, id) in objects {
+            let subset = self.fonts.subset(font)?;
+            let file = self.add_stream(&format!("/Length1 {}", subset.data.len()), &subset.data);
+            let [x_min, y_min, x_max, y_max] = subset.bbox;
+            let descriptor = self.add(format!(
+                "<< /Type /FontDescriptor /FontName /{} /Flags 4 /FontBBox [{} {} {} {}] /ItalicAngle 0 /Ascent {} /Descent {} /CapHeight {} /StemV 80 /FontFile2 {} 0 R >>",
+                subset.name, x_min, y_min, x_max, y_max, subset.ascent, subset.descent, subset.cap_height, file
+            ));
+            let widths: Vec<String> = subset.widths.iter().map(|(glyph, width)| format!("{} [{}]", glyph, width)).collect();
+            let cid_font = self.add(format!(
+                "<< /Type /Font /Subtype /CIDFontType2 /BaseFont /{} /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >> /FontDescriptor {} 0 R /W [{}] /CIDToGIDMap /Identity >>",
+                subset.name,
+                descriptor,
+                widths.join(" ")
+            ));
+            let to_unicode = self.add_stream("", to_unicode_cmap(&subset.chars).as_bytes());
+            self.set(
+                id,
+                format!(
+                    "<< /Type /Font /Subtype /Type0 /BaseFont /{} /Encoding /Identity-H /DescendantFonts [{} 0 R] /ToUnicode {} 0 R >>",
+                    subset.name, cid_font, to_unicode
+                ),
+            );
+        }
+        Ok(())
+    }
+
+    /// 헤더, 객체, 교차 참조 표, 트레일러를 이어 붙여 파일 데이터 생성
+    fn finish(self, catalog: usize, info: usize) -> Vec<u8> {
+        let mut out = b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n".to_vec();
+        let mut offsets = Vec::with_capacity(self.objects.len());
+        for (i, body) in self.objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.extend_from_slice(format!("{} 0 obj\n", i + 1).as_bytes());
+            out.extend_from_slice(body);
+            out.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref = out.len();
+        out.extend_from_slice(format!("xref\n0 {}\n0000000000 65535 f \n", self.objects.len() + 1).as_bytes());
+        for offset in offsets {
+            out.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+        out.extend_from_slice(
+            format!(
+                "trailer\n<< /Size {} /Root {} 0 R /Info {} 0 R >>\nstartxref\n{}\n%%EOF\n",
+                self.objects.len() + 1,
+                catalog,
+                info,
+                xref
+            )
+            .as_bytes(),
+        );
+        out
+    }
+}
+
+/// 페이지 내용 스트림 작성 상태
+struct Content<'a> {
+    pdf: &'a mut PdfWriter,
+    fonts: &'a Fonts,
+    out: String,
+    /// 이 페이지에서 쓰는 이미지 객체 (`/Im{인덱스}`)
+    images: Vec<usize>,
+    /// 이 페이지에서 쓰는 불투명도 (`/A{값}`)
+    alphas: Vec<u8>,
+    /// 효과가 적용된 캡처 이미지 객체와 크기 (돋보기가 확대해 씀)
+    capture: usize,
+    capture_size: Vec2,
+}
+
+impl Content<'_> {
+    fn line(&mut self, text: &str) {
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn shape(&mut self, shape: &DrawShape) -> Result<(), String> {
+        // 도형마다 그래픽 상태를 저장/복원해 선 스타일이나 잘라내기가 다음 도형에 남지 않게 함
+        self.line("q");
+        match shape {
+            DrawShape::Rectangle { rect, style } => {
+                self.fill_and_stroke(style.fill(), Some(style), |c| c.rect_path(*rect, style.corner_radius));
+            }
+            DrawShape::Ellipse { center, radius_x, radius_y, style } => {
+                let rect = Rect::from_center_size(*center, Vec2::new(*radius_x, *radius_y) * 2.0);
+                self.fill_and_stroke(style.fill(), Some(style), |c| c.ellipse_path(rect));
+            }
+            DrawShape::Arrow { start, end, control, kind, style } => self.arrow(*start, *end, *control, *kind, style),
+            DrawShape::Line { points, style } => {
+                self.fill_and_stroke(None, Some(style), |c| c.polyline_path(points, false));
+            }
+            DrawShape::Curve { points, smoothing, style } => {
+                if points.len() >= 2 {
+                    match shapes::curve_bezier_segments(points, *smoothing) {
+                        Some(segments) => self.fill_and_stroke(None, Some(style), |c| {
+                            c.line(&format!("{} m", point(points[0])));
+                            for [c1, c2, end] in &segments {
+                                c.line(&format!("{} {} {} c", point(*c1), point(*c2), point(*end)));
+                            }
+                        }),
+                        None => self.fill_and_stroke(None, Some(style), |c| c.polyline_path(points, false)),
+                    }
+                }
+            }
+            DrawShape::Text { pos, text, font_size, style } => {
+                let color = style.stroke().color;
+                let galley = self.fonts.layout_no_wrap(text.clone(), FontId::proportional(*font_size), color);
+                let rect = Rect::from_min_size(*pos, galley.size());
+                self.fill_and_stroke(style.fill(), None, |c| c.rect_path(rect, style.corner_radius));
+                self.text(*pos, &galley, *font_size, color);
+            }
+            DrawShape::Number { center, number, radius, format, style } => {
+                let rect = Rect::from_center_size(*center, Vec2::splat(*radius * 2.0));
+                let badge = style.fill().unwrap_or(style.stroke().color);
+                let outline = style.fill_color.is_some().then_some(style);
+                self.fill_and_stroke(Some(badge), outline, |c| c.ellipse_path(rect));
+
+                // 대문자 높이(약 0.72em)의 절반만큼 기준선을 내려 세로 가운데 정렬
+                let label = format.format(*number);
+                let font_size = shapes::badge_font_size(self.fonts, &label, *radius);
+                let width = self.text_width(&label) * font_size;
+                let badge_color = style.fill_color.unwrap_or(style.stroke_color);
+                let text_color = shapes::contrasting_text_color(badge_color).gamma_multiply(style.opacity.clamp(0.0, 1.0));
+                let baseline = Pos2::new(center.x - width * 0.5, center.y + font_size * 0.36);
+                self.text_run(baseline, font_size, &label, text_color, None);
+            }
+            DrawShape::Callout { rect, target, text, font_size, style } => {
+                let fill = style.fill();
+                self.fill_and_stroke(fill, Some(style), |c| c.rect_path(*rect, CALLOUT_ROUNDING));
+
+                // 꼬리 밑변을 지나는 말풍선 외곽선은 꼬리 채우기로 덮음
+                if let Some(tail) = shapes::callout_tail(*rect, *target, *font_size) {
+                    self.fill_and_stroke(fill, None, |c| c.polyline_path(&tail, true));
+                    let exits = [shapes::rect_exit(*rect, tail[0], tail[1]), shapes::rect_exit(*rect, tail[2], tail[1])];
+                    self.fill_and_stroke(None, Some(style), |c| c.polyline_path(&[exits[0], tail[1], exits[1]], false));
+                }
+
+                let text_color = match style.fill_color {
+                    Some(color) => shapes::contrasting_text_color(color),
+                    None => style.stroke_color,
+                }
+                .gamma_multiply(style.opacity.clamp(0.0, 1.0));
+                let wrap_width = (rect.width() - CALLOUT_PADDING * 2.0).max(0.0);
+                let galley = self.fonts.layout(text.clone(), FontId::proportional(*font_size), text_color, wrap_width);
+                self.text(rect.min + Vec2::splat(CALLOUT_PADDING), &galley, *font_size, text_color);
+            }
+            // 어둡게 하는 효과는 캡처 이미지에 이미 적용되어 있으므로 구멍 테두리만 그림
+            DrawShape::Spotlight { holes, style, .. } => {
+                for hole in holes {
+                    if hole.ellipse {
+                        self.fill_and_stroke(None, Some(style), |c| c.ellipse_path(hole.rect));
+                    } else {
+                        self.fill_and_stroke(None, Some(style), |c| c.rect_path(hole.rect, style.corner_radius));
+                    }
+                }
+            }
+            DrawShape::Magnifier { source, inset, circular, style } => self.magnifier(*source, *inset, *circular, style),
+            DrawShape::Image { rect, image, style } => {
+                let id = match self.pdf.layer_images.get(&image.id) {
+                    Some(&id) => id,
+                    None => {
+                        let id = self.pdf.image(&image.pixels)?;
+                        self.pdf.layer_images.insert(image.id, id);
+                        id
+                    }
+                };
+                self.line("q");
+                if style.corner_radius > 0.0 {
+                    self.rect_path(*rect, style.corner_radius);
+                    self.line("W n");
+                }
+                self.alpha(opacity_alpha(style));
+                self.image(id, *rect);
+                self.line("Q");
+                self.fill_and_stroke(None, Some(style), |c| c.rect_path(*rect, style.corner_radius));
+            }
+        }
+        self.line("Q");
+        Ok(())
+    }
+
+    /// 화살표 (선은 머리 길이의 80%만큼 짧게, 머리는 채운 삼각형)
+    fn arrow(&mut self, start: Pos2, end: Pos2, control: Option<Pos2>, kind: ArrowStyle, style: &ShapeStyle) {
+        let length = start.distance(end);
+        if length < 1.0 {
+            return;
+        }
+        let head = shapes::arrow_head_size(style.stroke_width).min(length * 0.5);
+        let dir = (end - start) / length;
+        let color = style.stroke().color;
+
+        let mut tip_dir = dir;
+        match kind {
+            ArrowStyle::Filled => {
+                let line = [start, end - dir * head * 0.8];
+                self.fill_and_stroke(None, Some(style), |c| c.polyline_path(&line, false));
+            }
+            ArrowStyle::Double => {
+                let line = [start + dir * head * 0.8, end - dir * head * 0.8];
+                self.fill_and_stroke(None, Some(style), |c| c.polyline_path(&line, false));
+                let tail = shapes::arrow_head_points(start, -dir, head);
+                self.fill_and_stroke(Some(color), None, |c| c.polyline_path(&tail, true));
+            }
+            ArrowStyle::Pointer => {
+                // 시작점에서 뾰족하게 시작해 머리 쪽으로 갈수록 굵어지는 몸통
+                let perp = Vec2::new(-dir.y, dir.x);
+                let base = end - dir * head * 0.8;
+                let half_width = style.stroke_width.max(1.0);
+                let body = [start, base + perp * half_width, base - perp * half_width];
+                self.fill_and_stroke(Some(color), None, |c| c.polyline_path(&body, true));
+            }
+            ArrowStyle::Curved => {
+                // 2차 베지어를 같은 모양의 3차 베지어로 변환
+                let control = control.unwrap_or_else(|| shapes::default_arrow_control(start, end));
+                if control.distance(end) > f32::EPSILON {
+                    tip_dir = (end - control).normalized();
+                }
+                let tip = end - tip_dir * head * 0.8;
+                let c1 = start + (control - start) * (2.0 / 3.0);
+                let c2 = tip + (control - tip) * (2.0 / 3.0);
+                self.fill_and_stroke(None, Some(style), |c| {
+                    c.line(&format!("{} m {} {} {} c", point(start), point(c1), point(c2), point(tip)));
+                });
+            }
+        }
+        let head_points = shapes::arrow_head_points(end, tip_dir, head);
+        self.fill_and_stroke(Some(color), None, |c| c.polyline_path(&head_points, true));
+    }
+
+    /// 돋보기 (잘라내기 영역 안에서 캡처 이미지를 확대)
+    fn magnifier(&mut self, source: Rect, inset: Rect, circular: bool, style: &ShapeStyle) {
+        if source.width() < 1.0 || source.height() < 1.0 || inset.width() < 1.0 || inset.height() < 1.0 {
+            return;
+        }
+        let outline = |c: &mut Self, rect: Rect| {
+            if circular {
+                c.ellipse_path(rect);
+            } else {
+                c.rect_path(rect, style.corner_radius);
+            }
+        };
+
+        if !source.intersects(inset) {
+            let dir = (inset.center() - source.center()).normalized();
+            let line = [
+                shapes::boundary_point(source, circular, dir),
+                shapes::boundary_point(inset, circular, -dir),
+            ];
+            self.fill_and_stroke(None, Some(style), |c| c.polyline_path(&line, false));
+        }
+
+        // 원본 영역이 확대 영역에 맞도록 캡처 전체를 옮기고 늘려서 그림
+        let scale = inset.size() / source.size();
+        let capture = self.capture;
+        self.line("q");
+        outline(self, inset);
+        self.line("W n");
+        self.alpha(opacity_alpha(style));
+        let min = inset.min - source.min.to_vec2() * scale;
+        self.image(capture, Rect::from_min_size(min, self.capture_size * scale));
+        self.line("Q");
+
+        self.fill_and_stroke(None, Some(style), |c| outline(c, source));
+        self.fill_and_stroke(None, Some(style), |c| outline(c, inset));
+    }
+
+    /// `path`로 만든 경로를 채우고 외곽선을 그림 (외곽선 두께가 0이면 생략)
+    fn fill_and_stroke(&mut self, fill: Option<Color32>, stroke: Option<&ShapeStyle>, path: impl Fn(&mut Self)) {
+        if let Some(color) = fill {
+            let [r, g, b, a] = color.to_srgba_unmultiplied();
+            self.alpha(a);
+            self.line(&format!("{} {} {} rg", channel(r), channel(g), channel(b)));
+            path(self);
+            self.line("f");
+        }
+        let Some(style) = stroke else {
+            return;
+        };
+        let stroke = style.stroke();
+        if stroke.is_empty() {
+            return;
+        }
+        let [r, g, b, a] = stroke.color.to_srgba_unmultiplied();
+        self.alpha(a);
+        let cap = match style.line_cap {
+            LineCap::Butt => 0,
+            LineCap::Round => 1,
+            LineCap::Square => 2,
+        };
+        let join = match style.line_join {
+            LineJoin::Miter => 0,
+            LineJoin::Round => 1,
+            LineJoin::Bevel => 2,
+        };
+        let dash = if style.dash == DashPattern::Solid {
+            String::new()
+        } else {
+            style.dash.lengths(stroke.width).into_iter().map(num).collect::<Vec<_>>().join(" ")
+        };
+        self.line(&format!(
+            "{} {} {} RG {} w {} J {} j [{}] 0 d",
+            channel(r),
+            channel(g),
+            channel(b),
+            num(stroke.width),
+            cap,
+            join,
+            dash
+        ));
+        path(self);
+        self.line("S");
+    }
+
+    /// 이후 칠하기에 불투명도 적용
+    fn alpha(&mut self, alpha: u8) {
+        if !self.alphas.contains(&alpha) {
+            self.alphas.push(alpha);
+        }
+        self.line(&format!("/A{} gs", alpha));
+    }
+
+    /// 이미지 객체를 `rect`에 맞게 그림
+    fn image(&mut self, id: usize, rect: Rect) {
+        let index = match self.images.iter().position(|&image| image == id) {
+            Some(index) => index,
+            None => {
+                self.images.push(id);
+                self.images.len() - 1
+            }
+        };
+        // 이미지 단위 정사각형의 위쪽(v = 1)이 첫 줄이므로 뒤집힌 좌표계에서 다시 뒤집음
+        self.line(&format!(
+            "q {} 0 0 {} {} {} cm /Im{} Do Q",
+            num(rect.width()),
+            num(-rect.height()),
+            num(rect.min.x),
+            num(rect.max.y),
+            index
+        ));
+    }
+
+    fn polyline_path(&mut self, points: &[Pos2], closed: bool) {
+        let Some((first, rest)) = points.split_first() else {
+            return;
+        };
+        let mut path = format!("{} m", point(*first));
+        for p in rest {
+            path.push_str(&format!(" {} l", point(*p)));
+        }
+        if closed {
+            path.push_str(" h");
+        }
+        self.line(&path);
+    }
+
+    /// 둥근 모서리 사각형 경로 (모서리는 4분의 1 원호 베지어)
+    fn rect_path(&mut self, rect: Rect, radius: f32) {
+        let r = radius.min(rect.width() * 0.5).min(rect.height() * 0.5).max(0.0);
+        if r < 0.5 {
+            self.line(&format!(
+                "{} {} {} {} re",
+                num(rect.min.x),
+                num(rect.min.y),
+                num(rect.width()),
+                num(rect.height())
+            ));
+            return;
+        }
+        let k = r * (1.0 - KAPPA);
+        let (l, t, rt, b) = (rect.min.x, rect.min.y, rect.max.x, rect.max.y);
+        let segments = [
+            format!("{} m", point(Pos2::new(l + r, t))),
+            format!("{} l", point(Pos2::new(rt - r, t))),
+            corner(Pos2::new(rt - k, t), Pos2::new(rt, t + k), Pos2::new(rt, t + r)),
+            format!("{} l", point(Pos2::new(rt, b - r))),
+            corner(Pos2::new(rt, b - k), Pos2::new(rt - k, b), Pos2::new(rt - r, b)),
+            format!("{} l", point(Pos2::new(l + r, b))),
+            corner(Pos2::new(l + k, b), Pos2::new(l, b - k), Pos2::new(l, b - r)),
+            format!("{} l", point(Pos2::new(l, t + r))),
+            corner(Pos2::new(l, t + k), Pos2::new(l + k, t), Pos2::new(l + r, t)),
+            "h".to_string(),
+        ];
+        self.line(&segments.join(" "));
+    }
+
+    /// `rect`에 내접하는 타원 경로 (4개의 베지어)
+    fn ellipse_path(&mut self, rect: Rect) {
+        let c = rect.center();
+        let r = rect.size() * 0.5;
+        let k = r * KAPPA;
+        let segments = [
+            format!("{} m", point(Pos2::new(c.x + r.x, c.y))),
+            corner(Pos2::new(c.x + r.x, c.y + k.y), Pos2::new(c.x + k.x, c.y + r.y), Pos2::new(c.x, c.y + r.y)),
+            corner(Pos2::new(c.x - k.x, c.y + r.y), Pos2::new(c.x - r.x, c.y + k.y), Pos2::new(c.x - r.x, c.y)),
+            corner(Pos2::new(c.x - r.x, c.y - k.y), Pos2::new(c.x - k.x, c.y - r.y), Pos2::new(c.x, c.y - r.y)),
+            corner(Pos2::new(c.x + k.x, c.y - r.y), Pos2::new(c.x + r.x, c.y - k.y), Pos2::new(c.x + r.x, c.y)),
+            "h".to_string(),
+        ];
+        self.line(&segments.join(" "));
+    }
+
+    /// 레이아웃된 텍스트를 줄마다 출력 (`pos`는 텍스트 왼쪽 위)
+    fn text(&mut self, pos: Pos2, galley: &Galley, font_size: f32, color: Color32) {
+        for row in &galley.rows {
+            // 줄 끝 공백은 너비 맞추기에서 제외
+            let glyphs: Vec<_> = row.glyphs.iter().collect();
+            let Some(end) = glyphs.iter().rposition(|glyph| !glyph.chr.is_whitespace()) else {
+                continue;
+            };
+            let first = glyphs[0];
+            let width = glyphs[end].pos.x + glyphs[end].advance_width - first.pos.x;
+            let text: String = glyphs[..=end].iter().map(|glyph| glyph.chr).collect();
+            self.text_run(pos + first.pos.to_vec2(), font_size, &text, color, Some(width));
+        }
+    }
+
+    /// 글꼴 크기 1 기준 텍스트 너비
+    fn text_width(&mut self, text: &str) -> f32 {
+        self.pdf.fonts.glyphs(text).iter().map(|glyph| glyph.advance).sum()
+    }
+
+    /// 기준선 왼쪽 끝 `baseline`에서 시작하는 한 줄 텍스트
+    ///
+    /// `width`가 있으면 화면의 글꼴과 너비가 같도록 가로 배율을 조절합니다.
+    fn text_run(&mut self, baseline: Pos2, font_size: f32, text: &str, color: Color32, width: Option<f32>) {
+        let glyphs = self.pdf.fonts.glyphs(text);
+        if glyphs.is_empty() {
+            return;
+        }
+        let natural = glyphs.iter().map(|glyph| glyph.advance).sum::<f32>() * font_size;
+        let scaling = match width {
+            Some(width) if natural > 0.0 => (width / natural * 100.0).clamp(50.0, 200.0),
+            _ => 100.0,
+        };
+        let [r, g, b, a] = color.to_srgba_unmultiplied();
+        self.alpha(a);
+
+        // 이미지 좌표계가 위아래로 뒤집혀 있으므로 글자는 다시 뒤집어서 씀
+        let mut ops = format!(
+            "BT {} {} {} rg {} Tz 1 0 0 -1 {} {} Tm",
+            channel(r),
+            channel(g),
+            channel(b),
+            num(scaling),
+            num(baseline.x),
+            num(baseline.y)
+        );
+        // 글꼴이 바뀌는 곳마다 나눠서 씀 (Identity-H이므로 2바이트 글리프 ID)
+        for run in glyphs.chunk_by(|a, b| a.font == b.font) {
+            self.pdf.font_object(run[0].font);
+            let ids: String = run.iter().map(|glyph| format!("{:04X}", glyph.id)).collect();
+            ops.push_str(&format!(" /F{} {} Tf <{}> Tj", run[0].font, num(font_size), ids));
+        }
+        ops.push_str(" ET");
+
+        // 글꼴에 없는 글자(.notdef)는 ToUnicode로 되돌릴 수 없으므로 원래 문자열을 함께 넣음
+        if glyphs.iter().any(|glyph| glyph.id == 0) {
+            ops = format!("/Span << /ActualText <FEFF{}> >> BDC {} EMC", utf16_hex(text), ops);
+        }
+        self.line(&ops);
+    }
+}
+
+fn opacity_alpha(style: &ShapeStyle) -> u8 {
+    (style.opacity.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// 4분의 1 원호를 근사하는 3차 베지어 구간
+fn corner(c1: Pos2, c2: Pos2, end: Pos2) -> String {
+    format!("{} {} {} c", point(c1), point(c2), point(end))
+}
+
+fn point(p: Pos2) -> String {
+    format!("{} {}", num(p.x), num(p.y))
+}
+
+/// 색상 채널 (0~1)
+fn channel(value: u8) -> String {
+    num(value as f32 / 255.0)
+}
+
+/// 소수점 셋째 자리까지, 불필요한 0 제거
+fn num(value: f32) -> String {
+    let text = format!("{:.3}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// UTF-16BE 16진 문자열
+fn utf16_hex(text: &str) -> String {
+    text.encode_utf16()
+        .map(|unit| format!("{:04X}", unit))
+        .collect()
+}
+
+/// 글리프 ID를 글자로 되돌리는 ToUnicode CMap
+fn to_unicode_cmap(chars: &[(u16, char)]) -> String {
+    let mut cmap = String::from(
+        "/CIDInit /ProcSet findresource begin\n12 dict begin\nbegincmap\n\
+         /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+         /CMapName /Adobe-Identity-UCS def\n/CMapType 2 def\n\
+         1 begincodespacerange\n<0000> <FFFF>\nendcodespacerange\n",
+    );
+    // 한 구역에 최대 100개
+    for block in chars.chunks(100) {
+        cmap.push_str(&format!("{} beginbfchar\n", block.len()));
+        for (glyph, chr) in block {
+            cmap.push_str(&format!("<{:04X}> <{}>\n", glyph, utf16_hex(&chr.to_string())));
+        }
+        cmap.push_str("endbfchar\n");
+    }
+    cmap.push_str("endcmap\nCMapName currentdict /CMap defineresource pop\nend\nend\n");
+    cmap
+}
@@ -0,0 +1,391 @@
+use egui::epaint::text::FontDefinitions;
+use egui::FontFamily;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use ttf_parser::{Face, GlyphId, Tag};
+
+/// 기본 글꼴에 없는 글자(한글 등)를 찾아볼 시스템 글꼴 (앞에서부터 사용)
+///
+/// PDF에 넣으려면 TrueType 윤곽선(glyf 표)이 있어야 하므로 CFF 기반 글꼴은 건너뜁니다.
+const SYSTEM_FONTS: &[&str] = &[
+    "/usr/share/fonts/truetype/nanum/NanumGothic.ttf",
+    "/usr/share/fonts/nanum/NanumGothic.ttf",
+    "/usr/share/fonts/truetype/unfonts-core/UnDotum.ttf",
+    "/usr/share/fonts/truetype/baekmuk/gulim.ttf",
+    "/System/Library/Fonts/Supplemental/AppleGothic.ttf",
+    "/Library/Fonts/Arial Unicode.ttf",
+    "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+    "/usr/share/fonts/TTF/DejaVuSans.ttf",
+    "/usr/share/fonts/dejavu/DejaVuSans.ttf",
+];
+
+/// Windows 글꼴 폴더(`%WINDIR%\Fonts`)에서 찾아볼 글꼴
+const WINDOWS_FONTS: &[&str] = &["malgun.ttf", "gulim.ttc", "arial.ttf", "seguisym.ttf"];
+
+/// PDF 글꼴 단위 (1 em = 1000)
+const UNITS: f32 = 1000.0;
+
+/// 복합 글리프 구성 요소 플래그
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+/// 글꼴 파일에서 PDF에 넣을 표 (태그 순서)
+const SUBSET_TABLES: [&[u8; 4]; 9] = [b"cvt ", b"fpgm", b"glyf", b"head", b"hhea", b"hmtx", b"loca", b"maxp", b"prep"];
+
+/// PDF 텍스트에 쓸 글리프
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Glyph {
+    /// `PdfFonts` 안의 글꼴 번호
+    pub font: usize,
+    /// 글리프 ID (Identity-H 인코딩이므로 그대로 CID로 씀, 글꼴에 없는 글자는 0)
+    pub id: u16,
+    /// 글꼴 크기 1 기준 전진 폭
+    pub advance: f32,
+}
+
+/// PDF에 넣을 부분 집합 글꼴
+pub struct SubsetFont {
+    /// 부분 집합 태그를 붙인 PostScript 이름 ("ABCDEF+Ubuntu-Light")
+    pub name: String,
+    /// 쓰지 않는 글리프의 윤곽선을 비운 TrueType 파일
+    pub data: Vec<u8>,
+    /// 1000 단위 글꼴 상자 [x_min, y_min, x_max, y_max]
+    pub bbox: [i32; 4],
+    pub ascent: i32,
+    pub descent: i32,
+    pub cap_height: i32,
+    /// (글리프 ID, 1000 단위 너비)
+    pub widths: Vec<(u16, i32)>,
+    /// (글리프 ID, 글자) 텍스트 추출용
+    pub chars: Vec<(u16, char)>,
+}
+
+/// 글꼴 파일 하나와 지금까지 쓴 글리프
+struct FontFile {
+    data: Cow<'static, [u8]>,
+    index: u32,
+    /// 쓴 글리프 ID와 그 글리프가 나타내는 글자
+    used: BTreeMap<u16, char>,
+}
+
+impl FontFile {
+    /// TrueType 윤곽선이 있는 글꼴만 사용
+    fn new(data: Cow<'static, [u8]>, index: u32) -> Option<Self> {
+        let face = Face::parse(&data, index).ok()?;
+        face.raw_face().table(Tag::from_bytes(b"glyf"))?;
+        face.raw_face().table(Tag::from_bytes(b"loca"))?;
+        Some(Self {
+            data,
+            index,
+            used: BTreeMap::new(),
+        })
+    }
+
+    fn face(&self) -> Face<'_> {
+        // new()에서 읽어 봤으므로 실패하지 않음
+        Face::parse(&self.data, self.index).expect("font was parsed when loaded")
+    }
+
+    /// 글자의 글리프 ID와 전진 폭 (글꼴에 없으면 None)
+    fn glyph(&self, chr: char) -> Option<(u16, f32)> {
+        let face = self.face();
+        let id = face.glyph_index(chr)?;
+        Some((id.0, advance(&face, id)))
+    }
+}
+
+/// 글리프의 전진 폭 (글꼴 크기 1 기준)
+fn advance(face: &Face, id: GlyphId) -> f32 {
+    face.glyph_hor_advance(id).unwrap_or(0) as f32 / face.units_per_em() as f32
+}
+
+/// PDF 문서에 넣을 글꼴 목록
+///
+/// 화면과 같은 egui 기본 글꼴로 시작하고, 그 글꼴에 없는 글자가 나오면 시스템 글꼴을 한 번 찾아 뒤에 붙입니다.
+pub struct PdfFonts {
+    fonts: Vec<FontFile>,
+    system_loaded: bool,
+}
+
+impl Default for PdfFonts {
+    fn default() -> Self {
+        let definitions = FontDefinitions::default();
+        let fonts = definitions
+            .families
+            .get(&FontFamily::Proportional)
+            .into_iter()
+            .flatten()
+            .filter_map(|name| definitions.font_data.get(name))
+            .filter_map(|data| FontFile::new(data.font.clone(), data.index))
+            .collect();
+        Self {
+            fonts,
+            system_loaded: false,
+        }
+    }
+}
+
+impl PdfFonts {
+    /// 글자마다 그 글자가 있는 첫 번째 글꼴의 글리프 (쓴 글리프로 기록)
+    ///
+    /// 어느 글꼴에도 없는 글자는 첫 글꼴의 .notdef 글리프(화면의 빈 상자와 같음)로 씁니다.
+    pub fn glyphs(&mut self, text: &str) -> Vec<Glyph> {
+        let mut glyphs = Vec::new();
+        for chr in text.chars().filter(|c| !c.is_control()) {
+            let (font, id, advance) = match self.find(chr) {
+                Some(found) => found,
+                None if !self.fonts.is_empty() => {
+                    let face = self.fonts[0].face();
+                    (0, 0, advance(&face, GlyphId(0)))
+                }
+                None => continue,
+            };
+            self.fonts[font].used.entry(id).or_insert(chr);
+            glyphs.push(Glyph { font, id, advance });
+        }
+        glyphs
+    }
+
+    fn find(&mut self, chr: char) -> Option<(usize, u16, f32)> {
+        if let Some(found) = self.lookup(chr) {
+            return Some(found);
+        }
+        if self.system_loaded {
+            return None;
+        }
+        self.system_loaded = true;
+        self.fonts.extend(system_fonts());
+        self.lookup(chr)
+    }
+
+    fn lookup(&self, chr: char) -> Option<(usize, u16, f32)> {
+        self.fonts
+            .iter()
+            .enumerate()
+            .find_map(|(i, font)| font.glyph(chr).map(|(id, advance)| (i, id, advance)))
+    }
+
+    /// 쓴 글리프만 남긴 글꼴
+    pub fn subset(&self, font: usize) -> Result<SubsetFont, String> {
+        let file = self.fonts.get(font).ok_or("Unknown PDF font")?;
+        let face = file.face();
+        let scale = UNITS / face.units_per_em() as f32;
+        let units = |value: f32| (value * scale).round() as i32;
+
+        let bbox = face.global_bounding_box();
+        let ascent = units(face.ascender() as f32);
+        let name = postscript_name(&face).unwrap_or_else(|| format!("Font{}", font));
+        Ok(SubsetFont {
+            name: format!("{}+{}", subset_tag(font, &file.used), name),
+            data: subset_font(&face, file.used.keys().copied())?,
+            bbox: [
+                units(bbox.x_min as f32),
+                units(bbox.y_min as f32),
+                units(bbox.x_max as f32),
+                units(bbox.y_max as f32),
+            ],
+            ascent,
+            descent: units(face.descender() as f32),
+            cap_height: face.capital_height().map(|h| units(h as f32)).unwrap_or(ascent),
+            widths: file
+                .used
+                .keys()
+                .map(|&id| (id, (advance(&face, GlyphId(id)) * UNITS).round() as i32))
+                .collect(),
+            chars: file.used.iter().filter(|(&id, _)| id != 0).map(|(&id, &chr)| (id, chr)).collect(),
+        })
+    }
+}
+
+/// 설치된 시스템 글꼴 중 TrueType 윤곽선이 있는 것
+fn system_fonts() -> Vec<FontFile> {
+    let windows: Vec<PathBuf> = std::env::var_os("WINDIR")
+        .map(|dir| {
+            let dir = PathBuf::from(dir).join("Fonts");
+            WINDOWS_FONTS.iter().map(|name| dir.join(name)).collect()
+        })
+        .unwrap_or_default();
+    windows
+        .into_iter()
+        .chain(SYSTEM_FONTS.iter().map(PathBuf::from))
+        .filter_map(|path| std::fs::read(path).ok())
+        .filter_map(|data| FontFile::new(Cow::Owned(data), 0))
+        .collect()
+}
+
+/// PDF 이름에 쓸 수 있는 PostScript 이름
+fn postscript_name(face: &Face) -> Option<String> {
+    let name = face
+        .names()
+        .into_iter()
+        .filter(|name| name.name_id == ttf_parser::name_id::POST_SCRIPT_NAME)
+        .find_map(|name| name.to_string())?;
+    let name: String = name.chars().filter(|c| c.is_ascii_alphanumeric() || *c == '-').collect();
+    (!name.is_empty()).then_some(name)
+}
+
+/// 부분 집합 글꼴 이름 앞에 붙이는 대문자 6개 (글꼴과 쓴 글리프마다 다름)
+fn subset_tag(font: usize, used: &BTreeMap<u16, char>) -> String {
+    // FNV-1a
+    let mut hash = 0x811C_9DC5u32 ^ font as u32;
+    for id in used.keys() {
+        for byte in id.to_be_bytes() {
+            hash = (hash ^ byte as u32).wrapping_mul(0x0100_0193);
+        }
+    }
+    (0..6)
+        .map(|_| {
+            let letter = (b'A' + (hash % 26) as u8) as char;
+            hash /= 26;
+            letter
+        })
+        .collect()
+}
+
+/// 쓰지 않는 글리프의 윤곽선을 비운 TrueType 파일
+///
+/// 글리프 ID를 그대로 두어야 PDF의 CID(= 글리프 ID)가 맞으므로 글리프를 지우지 않고 비웁니다.
+/// 복합 글리프가 참조하는 글리프는 함께 남깁니다.
+fn subset_font(face: &Face, used: impl Iterator<Item = u16>) -> Result<Vec<u8>, String> {
+    let raw = face.raw_face();
+    let table = |tag: &[u8; 4]| raw.table(Tag::from_bytes(tag));
+    let head = table(b"head").ok_or("Font has no head table")?;
+    let loca = table(b"loca").ok_or("Font has no loca table")?;
+    let glyf = table(b"glyf").ok_or("Font has no glyf table")?;
+    let long_offsets = head.get(50..52).ok_or("Invalid head table")? == [0, 1];
+    let glyph_count = face.number_of_glyphs() as usize;
+
+    let glyph_data = |id: usize| -> &[u8] {
+        let offset = |i: usize| -> Option<usize> {
+            if long_offsets {
+                let bytes = loca.get(i * 4..i * 4 + 4)?;
+                Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+            } else {
+                let bytes = loca.get(i * 2..i * 2 + 2)?;
+                Some(u16::from_be_bytes([bytes[0], bytes[1]]) as usize * 2)
+            }
+        };
+        match (offset(id), offset(id + 1)) {
+            (Some(start), Some(end)) if start < end => glyf.get(start..end).unwrap_or(&[]),
+            _ => &[],
+        }
+    };
+
+    let mut keep: BTreeSet<u16> = used.collect();
+    keep.insert(0);
+    let mut pending: Vec<u16> = keep.iter().copied().collect();
+    while let Some(id) = pending.pop() {
+        for component in glyph_components(glyph_data(id as usize)) {
+            if keep.insert(component) {
+                pending.push(component);
+            }
+        }
+    }
+
+    let mut new_glyf = Vec::new();
+    let mut new_loca = Vec::with_capacity((glyph_count + 1) * 4);
+    for id in 0..glyph_count {
+        new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+        if keep.contains(&(id as u16)) {
+            new_glyf.extend_from_slice(glyph_data(id));
+            new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+        }
+    }
+    new_loca.extend_from_slice(&(new_glyf.len() as u32).to_be_bytes());
+
+    // loca는 항상 32비트 오프셋으로 쓰고 체크섬 조정 값은 파일을 다 만든 뒤 계산
+    let mut new_head = head.to_vec();
+    new_head[50..52].copy_from_slice(&[0, 1]);
+    new_head[8..12].fill(0);
+
+    let tables: Vec<(&[u8; 4], Cow<[u8]>)> = SUBSET_TABLES
+        .iter()
+        .filter_map(|&tag| {
+            let data = match tag {
+                b"glyf" => Cow::Owned(std::mem::take(&mut new_glyf)),
+                b"loca" => Cow::Owned(std::mem::take(&mut new_loca)),
+                b"head" => Cow::Owned(std::mem::take(&mut new_head)),
+                _ => Cow::Borrowed(table(tag)?),
+            };
+            Some((tag, data))
+        })
+        .collect();
+    Ok(write_sfnt(&tables))
+}
+
+/// 복합 글리프가 참조하는 글리프 ID
+fn glyph_components(glyph: &[u8]) -> Vec<u16> {
+    let mut components = Vec::new();
+    // 윤곽선 개수가 음수이면 복합 글리프
+    if glyph.len() < 10 || i16::from_be_bytes([glyph[0], glyph[1]]) >= 0 {
+        return components;
+    }
+    let mut pos = 10;
+    while let Some(header) = glyph.get(pos..pos + 4) {
+        let flags = u16::from_be_bytes([header[0], header[1]]);
+        components.push(u16::from_be_bytes([header[2], header[3]]));
+        pos += 4 + if flags & ARG_1_AND_2_ARE_WORDS != 0 { 4 } else { 2 };
+        pos += if flags & WE_HAVE_A_SCALE != 0 {
+            2
+        } else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+            4
+        } else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+            8
+        } else {
+            0
+        };
+        if flags & MORE_COMPONENTS == 0 {
+            break;
+        }
+    }
+    components
+}
+
+/// 표들을 TrueType 파일로 합침 (표는 태그 순서여야 함)
+fn write_sfnt(tables: &[(&[u8; 4], Cow<[u8]>)]) -> Vec<u8> {
+    let count = tables.len() as u16;
+    let entry_selector = 15 - count.leading_zeros() as u16;
+    let search_range = (1u16 << entry_selector) * 16;
+
+    let mut out = Vec::new();
+    out.extend_from_slice(&0x0001_0000u32.to_be_bytes());
+    for value in [count, search_range, entry_selector, count * 16 - search_range] {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+
+    let mut offset = 12 + 16 * tables.len();
+    let mut head_offset = None;
+    for (tag, data) in tables {
+        if **tag == *b"head" {
+            head_offset = Some(offset);
+        }
+        out.extend_from_slice(*tag);
+        out.extend_from_slice(&checksum(data).to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in tables {
+        out.extend_from_slice(data);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+
+    if let Some(head) = head_offset {
+        let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&out));
+        out[head + 8..head + 12].copy_from_slice(&adjustment.to_be_bytes());
+    }
+    out
+}
+
+/// TrueType 표 체크섬 (4바이트 빅엔디언 정수의 합)
+fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |sum, chunk| {
+        let mut word = [0u8; 4];
+        word[..chunk.len()].copy_from_slice(chunk);
+        sum.wrapping_add(u32::from_be_bytes(word))
+    })
+}
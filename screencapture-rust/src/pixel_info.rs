@@ -0,0 +1,133 @@
+use crate::color_format::ColorFormat;
+use egui::{Color32, ColorImage, FontId, Pos2, Rect, Stroke};
+
+/// 돋보기에 보이는 픽셀 수 (가로/세로, 가운데 픽셀이 있도록 홀수)
+const LOUPE_PIXELS: usize = 15;
+
+/// 픽셀 하나를 그리는 크기 (화면 픽셀)
+const LOUPE_ZOOM: f32 = 8.0;
+
+/// 커서와 정보 창 사이 간격
+const CURSOR_OFFSET: f32 = 20.0;
+
+/// 정보 창 안쪽 여백
+const PADDING: f32 = 6.0;
+
+/// 커서 아래 픽셀의 좌표와 색상
+pub struct PixelInfo {
+    /// 이미지 좌표 (픽셀)
+    pub x: usize,
+    pub y: usize,
+    pub color: Color32,
+}
+
+impl PixelInfo {
+    /// 배경 이미지에서 `pos` 위치의 픽셀 정보 (이미지 밖이면 None)
+    pub fn at(image: &ColorImage, pos: Pos2) -> Option<Self> {
+        let [width, height] = image.size;
+        if pos.x < 0.0 || pos.y < 0.0 || pos.x >= width as f32 || pos.y >= height as f32 {
+            return None;
+        }
+        let (x, y) = (pos.x as usize, pos.y as usize);
+        Some(Self {
+            x,
+            y,
+            color: image.pixels[y * width + x],
+        })
+    }
+
+    /// "x, y" 형식 좌표
+    pub fn position_text(&self) -> String {
+        format!("{}, {}", self.x, self.y)
+    }
+}
+
+/// 커서 옆에 확대된 픽셀과 좌표/색상 정보를 표시
+///
+/// 확대된 픽셀도 `info`와 같은 `image`(원본 캡처)에서 읽어 표시하는 색과 값이 일치합니다.
+pub fn show(ctx: &egui::Context, image: &ColorImage, info: &PixelInfo, pointer: Pos2) {
+    let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Tooltip, egui::Id::new("pixel_info")));
+
+    let lines = [
+        format!("X: {}  Y: {}", info.x, info.y),
+        ColorFormat::Rgb.format(info.color),
+        ColorFormat::Hex.format(info.color),
+        ColorFormat::Cmyk.format(info.color),
+        "R/H/K: 색 복사  P: 좌표 복사".to_string(),
+    ];
+    let font = FontId::monospace(12.0);
+    let galleys: Vec<_> = lines
+        .into_iter()
+        .map(|line| painter.layout_no_wrap(line, font.clone(), Color32::WHITE))
+        .collect();
+
+    let loupe_side = LOUPE_PIXELS as f32 * LOUPE_ZOOM;
+    // RGB 줄 앞에는 색 견본이 붙음
+    let text_width = galleys
+        .iter()
+        .enumerate()
+        .map(|(i, g)| if i == 1 { g.size().x + g.size().y + 2.0 } else { g.size().x })
+        .fold(0.0, f32::max);
+    let text_height: f32 = galleys.iter().map(|g| g.size().y).sum();
+    let size = egui::vec2(loupe_side.max(text_width), loupe_side + PADDING + text_height) + egui::vec2(PADDING, PADDING) * 2.0;
+
+    // 화면 밖으로 나가면 커서 반대쪽에 표시
+    let screen = ctx.screen_rect();
+    let mut min = pointer + egui::vec2(CURSOR_OFFSET, CURSOR_OFFSET);
+    if min.x + size.x > screen.max.x {
+        min.x = pointer.x - CURSOR_OFFSET - size.x;
+    }
+    if min.y + size.y > screen.max.y {
+        min.y = pointer.y - CURSOR_OFFSET - size.y;
+    }
+    let panel = Rect::from_min_size(min, size);
+    painter.rect_filled(panel, 4.0, Color32::from_black_alpha(220));
+
+    // 확대된 픽셀 (이미지 밖은 어두운 배경 그대로)
+    let loupe = Rect::from_min_size(panel.min + egui::vec2((size.x - loupe_side) * 0.5, PADDING), egui::vec2(loupe_side, loupe_side));
+    painter.rect_filled(loupe, 0.0, Color32::from_gray(32));
+
+    let half = (LOUPE_PIXELS / 2) as isize;
+    let [width, height] = image.size;
+    let x0 = (info.x as isize - half).max(0) as usize;
+    let y0 = (info.y as isize - half).max(0) as usize;
+    let x1 = (info.x + half as usize + 1).min(width);
+    let y1 = (info.y + half as usize + 1).min(height);
+    let to_loupe = |x: usize, y: usize| {
+        loupe.min
+            + egui::vec2(
+                (x as isize - info.x as isize + half) as f32 * LOUPE_ZOOM,
+                (y as isize - info.y as isize + half) as f32 * LOUPE_ZOOM,
+            )
+    };
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let pixel = Rect::from_min_size(to_loupe(x, y), egui::vec2(LOUPE_ZOOM, LOUPE_ZOOM));
+            painter.rect_filled(pixel, 0.0, image.pixels[y * width + x]);
+        }
+    }
+
+    // 십자선과 가운데 픽셀 테두리
+    let center = Rect::from_min_size(to_loupe(info.x, info.y), egui::vec2(LOUPE_ZOOM, LOUPE_ZOOM));
+    let guide = Stroke::new(1.0, Color32::from_white_alpha(90));
+    painter.line_segment([Pos2::new(center.center().x, loupe.min.y), Pos2::new(center.center().x, center.min.y)], guide);
+    painter.line_segment([Pos2::new(center.center().x, center.max.y), Pos2::new(center.center().x, loupe.max.y)], guide);
+    painter.line_segment([Pos2::new(loupe.min.x, center.center().y), Pos2::new(center.min.x, center.center().y)], guide);
+    painter.line_segment([Pos2::new(center.max.x, center.center().y), Pos2::new(loupe.max.x, center.center().y)], guide);
+    painter.rect_stroke(center, 0.0, Stroke::new(1.0, Color32::WHITE));
+    painter.rect_stroke(center.expand(1.0), 0.0, Stroke::new(1.0, Color32::BLACK));
+    painter.rect_stroke(loupe, 0.0, Stroke::new(1.0, Color32::from_gray(120)));
+
+    // 색 견본과 텍스트
+    let mut y = loupe.max.y + PADDING;
+    for (i, galley) in galleys.into_iter().enumerate() {
+        let height = galley.size().y;
+        let mut x = panel.min.x + PADDING;
+        if i == 1 {
+            painter.rect_filled(Rect::from_min_size(Pos2::new(x, y + 1.0), egui::vec2(height - 2.0, height - 2.0)), 2.0, info.color);
+            x += height + 2.0;
+        }
+        painter.galley(Pos2::new(x, y), galley, Color32::WHITE);
+        y += height;
+    }
+}
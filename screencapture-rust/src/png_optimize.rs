@@ -0,0 +1,331 @@
+use crate::clipboard::color_image_to_image_buffer;
+use egui::ColorImage;
+use image::imageops::{self, ColorMap};
+use image::RgbaImage;
+use png::{BitDepth, ColorType, DeflateCompression, Filter};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// 팔레트 PNG의 최대 색 수
+const MAX_PALETTE_COLORS: usize = 256;
+
+/// NeuQuant 학습에 사용할 픽셀 간격 (1 = 모든 픽셀, 클수록 빠르지만 덜 정확)
+const QUANTIZE_SAMPLE_FACTOR: i32 = 10;
+
+/// 크기를 비교해 볼 행 필터 전략
+const FILTERS: [Filter; 6] = [
+    Filter::NoFilter,
+    Filter::Sub,
+    Filter::Up,
+    Filter::Paeth,
+    Filter::Adaptive,
+    Filter::MinEntropy,
+];
+
+/// PNG 최적화 설정
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PngOptimize {
+    /// 색 형식과 비트 깊이를 줄이고 여러 필터 중 가장 작은 결과 선택 (무손실)
+    pub enabled: bool,
+    /// 256색 팔레트로 줄이기 (손실)
+    pub quantize: bool,
+    /// 팔레트로 줄일 때 오차 확산 디더링
+    pub dither: bool,
+}
+
+impl Default for PngOptimize {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            quantize: false,
+            dither: true,
+        }
+    }
+}
+
+/// 최적화 전후 크기
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SizeReport {
+    pub before: usize,
+    pub after: usize,
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let saved = self.before.saturating_sub(self.after);
+        let percent = if self.before > 0 { saved as f32 / self.before as f32 * 100.0 } else { 0.0 };
+        write!(
+            f,
+            "{} → {} ({:.0}% 감소)",
+            format_bytes(self.before),
+            format_bytes(self.after),
+            percent
+        )
+    }
+}
+
+/// 사람이 읽기 쉬운 파일 크기 (B, KB, MB)
+pub fn format_bytes(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f32 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f32 / 1_048_576.0),
+    }
+}
+
+/// 이미지를 가장 작은 PNG로 압축
+///
+/// 기본 인코더로 만든 `baseline`보다 작아지지 않으면 `baseline`을 그대로 반환합니다.
+/// `level`은 압축 단계 (None이면 최대).
+pub fn optimize_png(
+    image: &ColorImage,
+    options: &PngOptimize,
+    level: Option<u8>,
+    baseline: Vec<u8>,
+) -> Result<(Vec<u8>, SizeReport), String> {
+    let mut buffer = color_image_to_image_buffer(image);
+    if options.quantize && palette_colors(&buffer).is_none() {
+        quantize(&mut buffer, options.dither);
+    }
+
+    let reduced = Reduced::from_image(&buffer);
+    let compression = match level.unwrap_or(9) {
+        0 => DeflateCompression::NoCompression,
+        level => DeflateCompression::Level(level.min(9)),
+    };
+
+    let mut best = baseline;
+    let before = best.len();
+    for filter in FILTERS {
+        let bytes = reduced.encode(buffer.width(), buffer.height(), filter, compression)?;
+        if bytes.len() < best.len() {
+            best = bytes;
+        }
+    }
+    let report = SizeReport { before, after: best.len() };
+    Ok((best, report))
+}
+
+/// 이미지에 쓰인 서로 다른 색 (팔레트에 담을 수 있는 수를 넘으면 바로 멈추고 None)
+fn palette_colors(buffer: &RgbaImage) -> Option<HashSet<[u8; 4]>> {
+    let mut colors = HashSet::new();
+    for pixel in buffer.pixels() {
+        if colors.insert(pixel.0) && colors.len() > MAX_PALETTE_COLORS {
+            return None;
+        }
+    }
+    Some(colors)
+}
+
+/// NeuQuant로 256색 팔레트를 만들어 각 픽셀을 가장 가까운 색으로 바꿈
+fn quantize(buffer: &mut RgbaImage, dither: bool) {
+    let quant = color_quant::NeuQuant::new(QUANTIZE_SAMPLE_FACTOR, MAX_PALETTE_COLORS, buffer.as_raw());
+    if dither {
+        imageops::dither(buffer, &quant);
+    } else {
+        for pixel in buffer.pixels_mut() {
+            quant.map_color(pixel);
+        }
+    }
+}
+
+/// 손실 없이 줄인 PNG 색 형식과 행 데이터 (필터 적용 전)
+struct Reduced {
+    color: ColorType,
+    depth: BitDepth,
+    data: Vec<u8>,
+    /// RGB 팔레트 (팔레트 형식일 때)
+    palette: Option<Vec<u8>>,
+    /// 팔레트 항목별 알파 (투명한 항목이 있을 때)
+    trns: Option<Vec<u8>>,
+}
+
+impl Reduced {
+    /// 이미지를 표현할 수 있는 가장 작은 색 형식 선택
+    ///
+    /// 불투명한 회색조 → 회색조 (1/2/4/8비트), 256색 이하 → 팔레트 (1/2/4/8비트),
+    /// 그 외에는 회색조+알파, RGB, RGBA 순서로 시도합니다.
+    fn from_image(buffer: &RgbaImage) -> Self {
+        let width = buffer.width() as usize;
+        let opaque = buffer.pixels().all(|pixel| pixel[3] == u8::MAX);
+        let gray = buffer.pixels().all(|pixel| pixel[0] == pixel[1] && pixel[1] == pixel[2]);
+
+        if gray && opaque {
+            let depth = [1u8, 2, 4]
+                .into_iter()
+                .find(|&bits| {
+                    let step = 255 / ((1u16 << bits) - 1) as u8;
+                    buffer.pixels().all(|pixel| pixel[0] % step == 0)
+                })
+                .unwrap_or(8);
+            let step = 255 / ((1u16 << depth) - 1) as u8;
+            let values: Vec<u8> = buffer.pixels().map(|pixel| pixel[0] / step).collect();
+            return Self {
+                color: ColorType::Grayscale,
+                depth: bit_depth(depth),
+                data: pack_rows(&values, width, depth),
+                palette: None,
+                trns: None,
+            };
+        }
+
+        if let Some(colors) = palette_colors(buffer) {
+            // 투명한 색을 앞에 두어 tRNS 청크를 짧게 만듦
+            let mut colors: Vec<[u8; 4]> = colors.into_iter().collect();
+            colors.sort_unstable_by_key(|color| (color[3] == u8::MAX, *color));
+            let index: HashMap<[u8; 4], u8> = colors.iter().enumerate().map(|(i, color)| (*color, i as u8)).collect();
+
+            let depth = match colors.len() {
+                0..=2 => 1,
+                3..=4 => 2,
+                5..=16 => 4,
+                _ => 8,
+            };
+            let values: Vec<u8> = buffer.pixels().map(|pixel| index[&pixel.0]).collect();
+            let translucent = colors.iter().take_while(|color| color[3] < u8::MAX).count();
+            return Self {
+                color: ColorType::Indexed,
+                depth: bit_depth(depth),
+                data: pack_rows(&values, width, depth),
+                palette: Some(colors.iter().flat_map(|color| [color[0], color[1], color[2]]).collect()),
+                trns: (translucent > 0).then(|| colors[..translucent].iter().map(|color| color[3]).collect()),
+            };
+        }
+
+        let (color, data) = if gray {
+            let data = buffer.pixels().flat_map(|pixel| [pixel[0], pixel[3]]).collect();
+            (ColorType::GrayscaleAlpha, data)
+        } else if opaque {
+            let data = buffer.pixels().flat_map(|pixel| [pixel[0], pixel[1], pixel[2]]).collect();
+            (ColorType::Rgb, data)
+        } else {
+            (ColorType::Rgba, buffer.as_raw().clone())
+        };
+        Self {
+            color,
+            depth: BitDepth::Eight,
+            data,
+            palette: None,
+            trns: None,
+        }
+    }
+
+    fn encode(&self, width: u32, height: u32, filter: Filter, compression: DeflateCompression) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(self.color);
+        encoder.set_depth(self.depth);
+        if let Some(palette) = &self.palette {
+            encoder.set_palette(palette.as_slice());
+        }
+        if let Some(trns) = &self.trns {
+            encoder.set_trns(trns.as_slice());
+        }
+        encoder.set_deflate_compression(compression);
+        encoder.set_filter(filter);
+
+        let mut writer = encoder.write_header().map_err(|e| format!("Failed to encode PNG: {}", e))?;
+        writer
+            .write_image_data(&self.data)
+            .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+        writer.finish().map_err(|e| format!("Failed to encode PNG: {}", e))?;
+        Ok(bytes)
+    }
+}
+
+fn bit_depth(bits: u8) -> BitDepth {
+    match bits {
+        1 => BitDepth::One,
+        2 => BitDepth::Two,
+        4 => BitDepth::Four,
+        _ => BitDepth::Eight,
+    }
+}
+
+/// 픽셀 값을 행마다 `bits` 비트씩 앞쪽 비트부터 채움 (각 행은 바이트 경계에서 시작)
+fn pack_rows(values: &[u8], width: usize, bits: u8) -> Vec<u8> {
+    if bits == 8 {
+        return values.to_vec();
+    }
+    let per_byte = (8 / bits) as usize;
+    let mut data = Vec::with_capacity(values.len() / per_byte + values.len() / width.max(1) + 1);
+    for row in values.chunks(width.max(1)) {
+        for group in row.chunks(per_byte) {
+            let byte = group
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, &value)| byte | value << (8 - bits as usize * (i + 1)));
+            data.push(byte);
+        }
+    }
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clipboard::decode_png;
+    use egui::Color32;
+
+    /// 최적화한 PNG를 다시 읽어 픽셀이 그대로인지 확인하고 (비트 깊이, 색 형식)을 반환
+    fn round_trip(image: &ColorImage) -> (u8, u8) {
+        let options = PngOptimize { enabled: true, ..Default::default() };
+        // 기본 인코더 결과보다 항상 작도록 큰 기준 데이터를 넘김
+        let (png, _) = optimize_png(image, &options, None, vec![0; 1 << 20]).unwrap();
+        let decoded = decode_png(&png).unwrap();
+        assert_eq!(decoded.size, image.size);
+        assert_eq!(decoded.pixels, image.pixels);
+        (png[24], png[25])
+    }
+
+    /// 5x3 이미지 (행 끝이 바이트 경계에 맞지 않도록 홀수 너비)
+    fn image(pixel: impl Fn(usize) -> Color32) -> ColorImage {
+        ColorImage {
+            size: [5, 3],
+            pixels: (0..15).map(pixel).collect(),
+        }
+    }
+
+    #[test]
+    fn one_bit_grayscale() {
+        let image = image(|i| if i % 3 == 0 { Color32::WHITE } else { Color32::BLACK });
+        assert_eq!(round_trip(&image), (1, 0));
+    }
+
+    #[test]
+    fn two_bit_grayscale() {
+        let image = image(|i| Color32::from_gray([0, 85, 170, 255][i % 4]));
+        assert_eq!(round_trip(&image), (2, 0));
+    }
+
+    #[test]
+    fn palette_with_transparency() {
+        let colors = [
+            Color32::TRANSPARENT,
+            Color32::from_rgba_unmultiplied(200, 100, 50, 128),
+            Color32::from_rgba_unmultiplied(10, 20, 250, 64),
+            Color32::RED,
+            Color32::GREEN,
+            Color32::BLUE,
+            Color32::YELLOW,
+            Color32::from_rgb(1, 2, 3),
+            Color32::from_rgb(90, 80, 70),
+            Color32::from_rgb(255, 0, 255),
+        ];
+        let image = image(|i| colors[i * 7 % colors.len()]);
+        assert_eq!(round_trip(&image), (4, 3));
+    }
+
+    #[test]
+    fn rgba_with_many_colors() {
+        let image = ColorImage {
+            size: [20, 20],
+            pixels: (0..400)
+                .map(|i| Color32::from_rgba_unmultiplied((i * 7) as u8, (i * 13) as u8, (i / 2) as u8, 255 - (i % 3) as u8 * 60))
+                .collect(),
+        };
+        assert_eq!(round_trip(&image), (8, 6));
+    }
+}
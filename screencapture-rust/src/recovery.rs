@@ -0,0 +1,49 @@
+use crate::config::config_dir;
+use crate::session::{Session, SESSION_EXTENSION};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// 편집 중인 내용을 자동 저장하는 간격
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 자동 저장 파일 이름 (확장자 제외)
+const AUTOSAVE_NAME: &str = "autosave";
+
+/// 자동 저장 파일이 있는 폴더
+pub fn recovery_dir() -> PathBuf {
+    config_dir().join("recovery")
+}
+
+fn autosave_path() -> PathBuf {
+    recovery_dir().join(format!("{}.{}", AUTOSAVE_NAME, SESSION_EXTENSION))
+}
+
+/// 편집 중인 세션을 복구 폴더에 저장
+///
+/// 임시 파일에 쓴 뒤 이름을 바꾸므로 저장 도중에 종료되어도 이전 자동 저장 파일은 남습니다.
+pub fn save(session: &Session) -> Result<(), String> {
+    let dir = recovery_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = autosave_path();
+    let temp = path.with_extension("tmp");
+    session.save(&temp)?;
+    std::fs::rename(&temp, &path).map_err(|e| format!("Failed to write autosave: {}", e))
+}
+
+/// 이전 실행에서 남은 자동 저장 세션 (없으면 None)
+pub fn load() -> Result<Option<Session>, String> {
+    let path = autosave_path();
+    if !path.exists() {
+        return Ok(None);
+    }
+    Session::load(&path).map(Some)
+}
+
+/// 자동 저장 파일 삭제 (정상적으로 저장하거나 종료했을 때)
+pub fn clear() -> Result<(), String> {
+    match std::fs::remove_file(autosave_path()) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove autosave: {}", e)),
+    }
+}
@@ -0,0 +1,194 @@
+use egui::epaint::text::{FontDefinitions, Fonts};
+use egui::epaint::{Mesh, TessellationOptions, Tessellator};
+use egui::{Color32, ColorImage, Pos2, Shape, TextureId};
+
+/// 내보내기 시 배경 이미지를 가리키는 텍스처 ID
+pub const EXPORT_BACKGROUND_TEXTURE: TextureId = TextureId::User(0);
+
+/// 내보내기용 폰트 텍스처 최대 크기
+const MAX_FONT_TEXTURE_SIDE: usize = 8 * 1024;
+
+/// 오프스크린 렌더링용 폰트 (1 포인트 = 1 픽셀)
+pub fn export_fonts() -> Fonts {
+    Fonts::new(1.0, MAX_FONT_TEXTURE_SIDE, FontDefinitions::default())
+}
+
+/// egui Shape들을 이미지 위에 직접 그리기 (CPU 래스터화)
+///
+/// 화면에 그릴 때와 같은 tessellator로 삼각형 메시를 만든 뒤
+/// 픽셀 단위로 채우므로 안티앨리어싱과 텍스트가 화면과 동일하게 나옵니다.
+/// 폰트 이외의 텍스처를 쓰는 메시는 `textures`에서 찾아 원본 해상도로 샘플링합니다.
+pub fn rasterize_shapes(
+    target: &mut ColorImage,
+    shapes: Vec<Shape>,
+    fonts: &Fonts,
+    textures: &[(TextureId, &ColorImage)],
+) {
+    // 텍스트 레이아웃이 끝난 뒤의 폰트 아틀라스를 사용해야 함
+    let font_image = fonts.image();
+    let font_pixels: Vec<Color32> = font_image.srgba_pixels(None).collect();
+    let font_texture = Texture {
+        size: font_image.size,
+        pixels: &font_pixels,
+        bilinear: false,
+    };
+
+    let mut tessellator = Tessellator::new(1.0, TessellationOptions::default(), font_image.size, vec![]);
+
+    for shape in shapes {
+        let mut mesh = Mesh::default();
+        tessellator.tessellate_shape(shape, &mut mesh);
+        if mesh.texture_id == TextureId::default() {
+            draw_mesh(target, &mesh, &font_texture);
+        } else if let Some((_, image)) = textures.iter().find(|(id, _)| *id == mesh.texture_id) {
+            // 배경은 돋보기 확대용이라 픽셀 그대로, 이미지 레이어는 부드럽게 샘플링
+            let texture = Texture {
+                size: image.size,
+                pixels: &image.pixels,
+                bilinear: mesh.texture_id != EXPORT_BACKGROUND_TEXTURE,
+            };
+            draw_mesh(target, &mesh, &texture);
+        }
+    }
+}
+
+/// 메시가 참조하는 텍스처
+struct Texture<'a> {
+    size: [usize; 2],
+    pixels: &'a [Color32],
+    bilinear: bool,
+}
+
+impl Texture<'_> {
+    fn sample(&self, uv: Pos2) -> Color32 {
+        if self.bilinear {
+            self.sample_bilinear(uv)
+        } else {
+            self.sample_nearest(uv)
+        }
+    }
+
+    fn pixel(&self, x: isize, y: isize) -> Color32 {
+        let x = x.clamp(0, self.size[0] as isize - 1) as usize;
+        let y = y.clamp(0, self.size[1] as isize - 1) as usize;
+        self.pixels[y * self.size[0] + x]
+    }
+
+    fn sample_nearest(&self, uv: Pos2) -> Color32 {
+        self.pixel((uv.x * self.size[0] as f32) as isize, (uv.y * self.size[1] as f32) as isize)
+    }
+
+    fn sample_bilinear(&self, uv: Pos2) -> Color32 {
+        let x = uv.x * self.size[0] as f32 - 0.5;
+        let y = uv.y * self.size[1] as f32 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as isize, y0 as isize);
+
+        let corners = [
+            (self.pixel(x0, y0), (1.0 - fx) * (1.0 - fy)),
+            (self.pixel(x0 + 1, y0), fx * (1.0 - fy)),
+            (self.pixel(x0, y0 + 1), (1.0 - fx) * fy),
+            (self.pixel(x0 + 1, y0 + 1), fx * fy),
+        ];
+        let channel = |i: usize| {
+            let value: f32 = corners.iter().map(|(c, w)| c.to_array()[i] as f32 * w).sum();
+            value.round().clamp(0.0, 255.0) as u8
+        };
+        Color32::from_rgba_premultiplied(channel(0), channel(1), channel(2), channel(3))
+    }
+}
+
+/// 삼각형 메시를 이미지에 합성
+fn draw_mesh(target: &mut ColorImage, mesh: &Mesh, texture: &Texture) {
+    for triangle in mesh.indices.chunks_exact(3) {
+        let a = &mesh.vertices[triangle[0] as usize];
+        let b = &mesh.vertices[triangle[1] as usize];
+        let c = &mesh.vertices[triangle[2] as usize];
+        draw_triangle(target, [a, b, c], texture);
+    }
+}
+
+/// 세 점이 이루는 평행사변형의 부호 있는 넓이
+///
+/// 이웃한 삼각형이 공유하는 변에서 값이 정확히 부호만 반대가 되도록
+/// 항상 같은 순서의 끝점을 기준으로 계산합니다 (틈 방지).
+fn edge(a: Pos2, b: Pos2, p: Pos2) -> f32 {
+    if (a.y, a.x) > (b.y, b.x) {
+        return -edge(b, a, p);
+    }
+    (b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// 공유 변의 픽셀이 두 번 칠해지지 않도록 하는 top-left 규칙
+fn is_top_left(a: Pos2, b: Pos2) -> bool {
+    (a.y == b.y && b.x < a.x) || b.y < a.y
+}
+
+fn draw_triangle(target: &mut ColorImage, vertices: [&egui::epaint::Vertex; 3], texture: &Texture) {
+    let [mut v0, mut v1, v2] = vertices;
+    let mut area = edge(v0.pos, v1.pos, v2.pos);
+    if area.abs() < f32::EPSILON {
+        return;
+    }
+    // 항상 같은 방향(시계 방향, y축 아래)으로 정렬
+    if area < 0.0 {
+        std::mem::swap(&mut v0, &mut v1);
+        area = -area;
+    }
+
+    let [width, height] = target.size;
+    let min_x = v0.pos.x.min(v1.pos.x).min(v2.pos.x).floor().max(0.0) as usize;
+    let min_y = v0.pos.y.min(v1.pos.y).min(v2.pos.y).floor().max(0.0) as usize;
+    let max_x = (v0.pos.x.max(v1.pos.x).max(v2.pos.x).ceil().max(0.0) as usize).min(width);
+    let max_y = (v0.pos.y.max(v1.pos.y).max(v2.pos.y).ceil().max(0.0) as usize).min(height);
+
+    let top_left = [
+        is_top_left(v1.pos, v2.pos),
+        is_top_left(v2.pos, v0.pos),
+        is_top_left(v0.pos, v1.pos),
+    ];
+
+    for y in min_y..max_y {
+        for x in min_x..max_x {
+            let p = Pos2::new(x as f32 + 0.5, y as f32 + 0.5);
+            let w = [edge(v1.pos, v2.pos, p), edge(v2.pos, v0.pos, p), edge(v0.pos, v1.pos, p)];
+            let inside = w.iter().zip(top_left).all(|(&w, tl)| w > 0.0 || (w == 0.0 && tl));
+            if !inside {
+                continue;
+            }
+
+            let l = [w[0] / area, w[1] / area, w[2] / area];
+            let uv = Pos2::new(
+                v0.uv.x * l[0] + v1.uv.x * l[1] + v2.uv.x * l[2],
+                v0.uv.y * l[0] + v1.uv.y * l[1] + v2.uv.y * l[2],
+            );
+            let color = interpolate_color([v0.color, v1.color, v2.color], l);
+            let src = multiply(color, texture.sample(uv));
+
+            let dst = &mut target.pixels[y * width + x];
+            *dst = blend_over(src, *dst);
+        }
+    }
+}
+
+fn interpolate_color(colors: [Color32; 3], weights: [f32; 3]) -> Color32 {
+    let channel = |i: usize| {
+        let value: f32 = colors.iter().zip(weights).map(|(c, w)| c.to_array()[i] as f32 * w).sum();
+        value.round().clamp(0.0, 255.0) as u8
+    };
+    Color32::from_rgba_premultiplied(channel(0), channel(1), channel(2), channel(3))
+}
+
+/// 정점 색상과 텍스처 색상 곱하기 (둘 다 premultiplied)
+fn multiply(a: Color32, b: Color32) -> Color32 {
+    let mul = |x: u8, y: u8| ((x as u16 * y as u16 + 127) / 255) as u8;
+    Color32::from_rgba_premultiplied(mul(a.r(), b.r()), mul(a.g(), b.g()), mul(a.b(), b.b()), mul(a.a(), b.a()))
+}
+
+/// premultiplied alpha "over" 합성
+pub fn blend_over(src: Color32, dst: Color32) -> Color32 {
+    let inv = 255 - src.a() as u16;
+    let mix = |s: u8, d: u8| (s as u16 + (d as u16 * inv + 127) / 255).min(255) as u8;
+    Color32::from_rgba_premultiplied(mix(src.r(), dst.r()), mix(src.g(), dst.g()), mix(src.b(), dst.b()), mix(src.a(), dst.a()))
+}
@@ -0,0 +1,277 @@
+use crate::clipboard::{decode_png, encode_png, load_image_from_file};
+use crate::shapes::Layer;
+use egui::ColorImage;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::Arc;
+
+/// 세션 파일 확장자
+pub const SESSION_EXTENSION: &str = "scap";
+
+/// 현재 세션 형식 버전
+///
+/// 형식을 바꿀 때는 버전을 올리고 이전 버전을 변환하는 함수를 `MIGRATIONS`에 추가합니다.
+pub const SESSION_VERSION: u32 = 1;
+
+/// PNG에 세션을 넣는 비공개 청크 종류
+///
+/// 대소문자가 청크 속성을 나타냅니다: 보조(s, 다른 뷰어는 무시), 비공개(c), 예약(A),
+/// 복사 불가(P, 다른 프로그램이 이미지를 수정하면 맞지 않는 세션이 남지 않도록 버려짐).
+const PNG_CHUNK_TYPE: [u8; 4] = *b"scAP";
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// 이전 버전 세션 변환 함수 (`MIGRATIONS[i]`는 버전 i + 1 → i + 2)
+const MIGRATIONS: &[fn(&mut Value)] = &[];
+
+/// 다시 열어서 편집할 수 있는 주석 세션 (원본 캡처 + 레이어)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Session {
+    pub version: u32,
+    /// 저장한 시각 (RFC 3339)
+    #[serde(default)]
+    pub saved_at: String,
+    /// 도형을 그리기 전의 원본 캡처 (PNG)
+    #[serde(with = "png_data")]
+    pub background: Arc<ColorImage>,
+    /// 아래 레이어부터 순서대로
+    pub layers: Vec<Layer>,
+    /// 번호 배지 시작 값 (없으면 현재 설정 유지)
+    #[serde(default)]
+    pub number_start: Option<u32>,
+    /// 다음에 붙일 번호 배지 값 (없으면 배지 번호로 계산)
+    #[serde(default)]
+    pub next_number: Option<u32>,
+}
+
+impl Session {
+    pub fn new(background: Arc<ColorImage>, layers: Vec<Layer>) -> Self {
+        Self {
+            version: SESSION_VERSION,
+            saved_at: chrono::Local::now().to_rfc3339(),
+            background,
+            layers,
+            number_start: None,
+            next_number: None,
+        }
+    }
+
+    /// 세션을 JSON 바이트로 변환
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String> {
+        serde_json::to_vec(self).map_err(|e| format!("Failed to serialize session: {}", e))
+    }
+
+    /// JSON 바이트에서 세션 읽기 (이전 버전은 현재 버전으로 변환)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        let mut value: Value = serde_json::from_slice(bytes).map_err(|e| format!("Invalid session: {}", e))?;
+        migrate(&mut value)?;
+        serde_json::from_value(value).map_err(|e| format!("Invalid session: {}", e))
+    }
+
+    /// 세션 파일 저장
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        std::fs::write(path, self.to_bytes()?).map_err(|e| format!("Failed to write session: {}", e))
+    }
+
+    /// 세션 파일 불러오기 (세션이 들어 있는 PNG도 가능)
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|e| format!("Failed to read session {}: {}", path.display(), e))?;
+        if bytes.starts_with(&PNG_SIGNATURE) {
+            return Self::from_png(&bytes)?.ok_or_else(|| "PNG has no embedded annotation session".to_string());
+        }
+        Self::from_bytes(&bytes)
+    }
+
+    /// 편집할 파일 열기
+    ///
+    /// 세션 파일과 세션이 들어 있는 PNG는 도형까지 복원하고, 그 외의 이미지는 도형 없는 새 세션으로 엽니다.
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_ascii_lowercase());
+        if extension.as_deref() == Some(SESSION_EXTENSION) {
+            return Self::load(path);
+        }
+        if extension.as_deref() == Some("png") {
+            let bytes = std::fs::read(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            if let Ok(Some(session)) = Self::from_png(&bytes) {
+                return Ok(session);
+            }
+        }
+        Ok(Self::new(Arc::new(load_image_from_file(path)?), Vec::new()))
+    }
+
+    /// PNG 파일 데이터의 IEND 앞에 세션 청크를 추가
+    ///
+    /// 다른 뷰어는 모르는 보조 청크를 무시하므로 합성된 이미지만 보입니다.
+    pub fn embed_in_png(&self, png: &[u8]) -> Result<Vec<u8>, String> {
+        let data = self.to_bytes()?;
+        let length = u32::try_from(data.len()).map_err(|_| "Session is too large to embed in PNG".to_string())?;
+        let iend = png_chunks(png)?
+            .into_iter()
+            .find(|chunk| chunk.kind == *b"IEND")
+            .ok_or("PNG has no IEND chunk")?;
+
+        let mut result = Vec::with_capacity(png.len() + data.len() + 12);
+        result.extend_from_slice(&png[..iend.offset]);
+        result.extend_from_slice(&length.to_be_bytes());
+        result.extend_from_slice(&PNG_CHUNK_TYPE);
+        result.extend_from_slice(&data);
+        result.extend_from_slice(&crc32(&[&PNG_CHUNK_TYPE, &data]).to_be_bytes());
+        result.extend_from_slice(&png[iend.offset..]);
+        Ok(result)
+    }
+
+    /// PNG 파일 데이터에서 세션 청크 읽기 (청크가 없으면 None)
+    pub fn from_png(png: &[u8]) -> Result<Option<Self>, String> {
+        let Some(chunk) = png_chunks(png)?.into_iter().find(|chunk| chunk.kind == PNG_CHUNK_TYPE) else {
+            return Ok(None);
+        };
+        if crc32(&[&chunk.kind, chunk.data]) != chunk.crc {
+            return Err("Embedded annotation session is corrupted (CRC mismatch)".to_string());
+        }
+        Self::from_bytes(chunk.data).map(Some)
+    }
+}
+
+/// PNG 청크 (`offset`은 길이 필드의 위치)
+pub struct PngChunk<'a> {
+    pub offset: usize,
+    pub kind: [u8; 4],
+    pub data: &'a [u8],
+    pub crc: u32,
+}
+
+/// PNG 파일 데이터를 청크 단위로 나눔
+pub fn png_chunks(png: &[u8]) -> Result<Vec<PngChunk<'_>>, String> {
+    if !png.starts_with(&PNG_SIGNATURE) {
+        return Err("Not a PNG file".to_string());
+    }
+    let mut chunks = Vec::new();
+    let mut offset = PNG_SIGNATURE.len();
+    while offset < png.len() {
+        let header = png.get(offset..offset + 8).ok_or("Truncated PNG chunk")?;
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let data_start = offset + 8;
+        let data = png.get(data_start..data_start + length).ok_or("Truncated PNG chunk")?;
+        let crc = png.get(data_start + length..data_start + length + 4).ok_or("Truncated PNG chunk")?;
+        chunks.push(PngChunk {
+            offset,
+            kind: [header[4], header[5], header[6], header[7]],
+            data,
+            crc: u32::from_be_bytes([crc[0], crc[1], crc[2], crc[3]]),
+        });
+        offset = data_start + length + 4;
+    }
+    Ok(chunks)
+}
+
+/// PNG 청크 CRC-32 (ISO 3309, 다항식 0xEDB88320)
+fn crc32(parts: &[&[u8]]) -> u32 {
+    const TABLE: [u32; 256] = {
+        let mut table = [0u32; 256];
+        let mut n = 0;
+        while n < 256 {
+            let mut c = n as u32;
+            let mut k = 0;
+            while k < 8 {
+                c = if c & 1 != 0 { 0xEDB8_8320 ^ (c >> 1) } else { c >> 1 };
+                k += 1;
+            }
+            table[n] = c;
+            n += 1;
+        }
+        table
+    };
+
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in parts.iter().flat_map(|part| part.iter()) {
+        crc = TABLE[((crc ^ byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    crc ^ 0xFFFF_FFFF
+}
+
+/// 세션 JSON을 현재 버전 형식으로 변환
+fn migrate(value: &mut Value) -> Result<(), String> {
+    debug_assert_eq!(MIGRATIONS.len(), SESSION_VERSION as usize - 1);
+    apply_migrations(value, MIGRATIONS, SESSION_VERSION)
+}
+
+/// 세션 JSON의 버전부터 `current`까지 변환 함수를 차례로 적용
+fn apply_migrations(value: &mut Value, migrations: &[fn(&mut Value)], current: u32) -> Result<(), String> {
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or("Session has no version")? as u32;
+    if version == 0 || version > current {
+        return Err(format!(
+            "Unsupported session version {} (supported: 1~{})",
+            version, current
+        ));
+    }
+
+    let steps = migrations
+        .get(version as usize - 1..current as usize - 1)
+        .ok_or_else(|| format!("No migration from session version {}", version))?;
+    for migration in steps {
+        migration(value);
+    }
+    value["version"] = current.into();
+    Ok(())
+}
+
+/// 이미지를 PNG로 압축해 base64 문자열로 저장
+pub mod png_data {
+    use super::{decode_png, encode_png};
+    use crate::base64;
+    use egui::ColorImage;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::sync::Arc;
+
+    pub fn serialize<S: Serializer>(image: &Arc<ColorImage>, serializer: S) -> Result<S::Ok, S::Error> {
+        let png = encode_png(image).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&base64::encode(&png))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Arc<ColorImage>, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        let png = base64::decode(&text).ok_or_else(|| serde::de::Error::custom("invalid base64 image data"))?;
+        decode_png(&png).map(Arc::new).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rename_shapes(value: &mut Value) {
+        let shapes = value.as_object_mut().and_then(|object| object.remove("shapes"));
+        value["layers"] = shapes.unwrap_or_default();
+    }
+
+    #[test]
+    fn migrations_cover_every_version() {
+        assert_eq!(MIGRATIONS.len(), SESSION_VERSION as usize - 1);
+    }
+
+    #[test]
+    fn applies_migrations_up_to_current_version() {
+        let mut value = json!({ "version": 1, "shapes": [] });
+        apply_migrations(&mut value, &[rename_shapes], 2).unwrap();
+        assert_eq!(value, json!({ "version": 2, "layers": [] }));
+    }
+
+    #[test]
+    fn missing_migration_is_an_error() {
+        let mut value = json!({ "version": 1 });
+        assert!(apply_migrations(&mut value, &[], 2).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        for version in [json!(0), json!(3), json!("1")] {
+            let mut value = json!({ "version": version });
+            assert!(apply_migrations(&mut value, &[rename_shapes], 2).is_err());
+        }
+    }
+}
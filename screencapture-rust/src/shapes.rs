@@ -1,4 +1,12 @@
-use egui::{Color32, Pos2, Rect, Shape, Stroke};
+use crate::style::{self, ShapeStyle};
+use crate::transform::{reorient, GeometryTransform};
+use egui::epaint::text::{Fonts, Galley};
+use egui::epaint::{Mesh, RectShape, Vertex};
+use egui::{Color32, ColorImage, FontId, Pos2, Rect, Shape, Stroke, TextureId, Vec2};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 
 /// 그리기 도구 종류
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -15,116 +23,413 @@ pub enum ToolType {
     Mosaic,      // 모자이크
     Eraser,      // 지우개
     Number,      // 번호 매기기
+    Callout,     // 말풍선
+    Spotlight,   // 스포트라이트
+    Magnifier,   // 돋보기
+    Stamp,       // 스탬프
+    Eyedropper,  // 스포이트 (캡처 화면 색 추출)
+    Crop,        // 자르기
 }
 
-/// 그리기 객체
-#[derive(Clone)]
+/// 화살표 모양
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ArrowStyle {
+    Filled,  // 채워진 삼각형 머리
+    Double,  // 양쪽 머리
+    Pointer, // 끝으로 갈수록 굵어지는 포인터
+    Curved,  // 제어점으로 휘는 곡선 화살표
+}
+
+impl ArrowStyle {
+    pub const ALL: [ArrowStyle; 4] = [
+        ArrowStyle::Filled,
+        ArrowStyle::Double,
+        ArrowStyle::Pointer,
+        ArrowStyle::Curved,
+    ];
+
+    /// 도구 모음에 표시할 이름
+    pub fn label(self) -> &'static str {
+        match self {
+            ArrowStyle::Filled => "기본",
+            ArrowStyle::Double => "양방향",
+            ArrowStyle::Pointer => "포인터",
+            ArrowStyle::Curved => "곡선",
+        }
+    }
+}
+
+/// 번호 배지 표기 방식
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NumberStyle {
+    Numeric,    // 1, 2, 3
+    Alphabetic, // A, B, C
+    Roman,      // I, II, III
+}
+
+impl NumberStyle {
+    pub const ALL: [NumberStyle; 3] = [NumberStyle::Numeric, NumberStyle::Alphabetic, NumberStyle::Roman];
+
+    /// 도구 모음에 표시할 이름
+    pub fn label(self) -> &'static str {
+        match self {
+            NumberStyle::Numeric => "123",
+            NumberStyle::Alphabetic => "ABC",
+            NumberStyle::Roman => "Ⅰ Ⅱ Ⅲ",
+        }
+    }
+
+    /// 번호를 표기 방식에 맞는 문자열로 변환
+    pub fn format(self, number: u32) -> String {
+        match self {
+            NumberStyle::Numeric => number.to_string(),
+            NumberStyle::Alphabetic if number > 0 => {
+                // 1 → A, 26 → Z, 27 → AA
+                let mut n = number;
+                let mut letters = Vec::new();
+                while n > 0 {
+                    n -= 1;
+                    letters.push((b'A' + (n % 26) as u8) as char);
+                    n /= 26;
+                }
+                letters.iter().rev().collect()
+            }
+            NumberStyle::Roman if (1..4000).contains(&number) => {
+                const NUMERALS: [(u32, &str); 13] = [
+                    (1000, "M"), (900, "CM"), (500, "D"), (400, "CD"),
+                    (100, "C"), (90, "XC"), (50, "L"), (40, "XL"),
+                    (10, "X"), (9, "IX"), (5, "V"), (4, "IV"), (1, "I"),
+                ];
+                let mut n = number;
+                let mut text = String::new();
+                for (value, numeral) in NUMERALS {
+                    while n >= value {
+                        text.push_str(numeral);
+                        n -= value;
+                    }
+                }
+                text
+            }
+            // 표기할 수 없는 값은 숫자로 표시
+            _ => number.to_string(),
+        }
+    }
+}
+
+/// 스포트라이트에서 밝게 남길 영역
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SpotlightHole {
+    pub rect: Rect,
+    /// true면 rect에 내접하는 타원
+    pub ellipse: bool,
+}
+
+/// 도형을 egui Shape으로 변환할 때 필요한 자원
+pub struct PaintContext<'a> {
+    pub fonts: &'a Fonts,
+    /// 배경 이미지 텍스처 (돋보기가 원본 영역을 확대해 그릴 때 사용)
+    pub background: TextureId,
+    pub background_size: Vec2,
+    /// 이미지 레이어 ID별 텍스처
+    pub layer_textures: &'a HashMap<u64, TextureId>,
+}
+
+/// 이미지 레이어의 픽셀 (실행 취소 상태끼리 복사 없이 공유)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LayerImage {
+    /// 텍스처 캐시에 사용하는 고유 ID (세션에는 저장하지 않고 불러올 때 새로 발급)
+    #[serde(skip, default = "next_image_id")]
+    pub id: u64,
+    #[serde(with = "crate::session::png_data")]
+    pub pixels: Arc<ColorImage>,
+}
+
+/// 새 이미지 레이어 ID
+fn next_image_id() -> u64 {
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    NEXT_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+impl LayerImage {
+    pub fn new(pixels: ColorImage) -> Self {
+        Self {
+            id: next_image_id(),
+            pixels: Arc::new(pixels),
+        }
+    }
+
+    /// 가로/세로 비율
+    pub fn aspect(&self) -> f32 {
+        let [width, height] = self.pixels.size;
+        width.max(1) as f32 / height.max(1) as f32
+    }
+}
+
+/// 레이어 (도형과 레이어 패널에서 설정하는 이름, 표시/잠금 상태)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Layer {
+    pub shape: DrawShape,
+    /// 사용자가 지정한 이름 (None이면 자동 이름)
+    pub name: Option<String>,
+    /// 같은 종류의 도형 중 몇 번째로 추가됐는지 (자동 이름에 사용)
+    pub ordinal: u32,
+    /// 숨긴 레이어는 화면과 내보내기에서 제외
+    pub visible: bool,
+    /// 잠긴 레이어는 캔버스에서 선택/이동/편집할 수 없음
+    pub locked: bool,
+}
+
+impl Layer {
+    pub fn new(shape: DrawShape) -> Self {
+        Self {
+            shape,
+            name: None,
+            ordinal: 1,
+            visible: true,
+            locked: false,
+        }
+    }
+
+    /// 캔버스에서 선택하거나 편집할 수 있는지 여부
+    pub fn is_editable(&self) -> bool {
+        self.visible && !self.locked
+    }
+}
+
+/// 그리기 객체 (세션 파일에는 "type" 필드로 종류를 구분해 저장)
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
 pub enum DrawShape {
     Rectangle {
         rect: Rect,
-        stroke: Stroke,
-        filled: bool,
+        style: ShapeStyle,
     },
     Ellipse {
         center: Pos2,
         radius_x: f32,
         radius_y: f32,
-        stroke: Stroke,
-        filled: bool,
+        style: ShapeStyle,
     },
     Arrow {
         start: Pos2,
         end: Pos2,
-        stroke: Stroke,
+        /// 곡선 화살표의 2차 베지어 제어점
+        control: Option<Pos2>,
+        kind: ArrowStyle,
+        style: ShapeStyle,
     },
     Line {
         points: Vec<Pos2>,
-        stroke: Stroke,
+        style: ShapeStyle,
     },
     Curve {
         points: Vec<Pos2>,
-        stroke: Stroke,
+        /// 부드럽게 하기 강도 (0.0 = 원본 꺾은선, 1.0 = 최대)
+        smoothing: f32,
+        style: ShapeStyle,
     },
+    /// 글자색은 선 색, 채우기는 배경
     Text {
         pos: Pos2,
         text: String,
-        color: Color32,
         font_size: f32,
+        style: ShapeStyle,
     },
+    /// 배지 색은 채우기 색 (없으면 선 색)
     Number {
         center: Pos2,
         number: u32,
-        color: Color32,
         radius: f32,
+        format: NumberStyle,
+        style: ShapeStyle,
+    },
+    /// 말풍선 색은 채우기 색 (없으면 외곽선만)
+    Callout {
+        /// 말풍선 본체 (텍스트에 맞춰 자동으로 크기 조절)
+        rect: Rect,
+        /// 꼬리가 가리키는 위치
+        target: Pos2,
+        text: String,
+        font_size: f32,
+        style: ShapeStyle,
+    },
+    /// 구멍 바깥을 어둡게 하는 배경 효과 (다른 도형보다 먼저 배경에 적용됨)
+    Spotlight {
+        holes: Vec<SpotlightHole>,
+        /// 어둡게 하는 정도 (0.0 ~ 1.0)
+        opacity: f32,
+        /// 구멍 바깥을 흑백으로 표시
+        desaturate: bool,
+        /// 구멍 테두리
+        style: ShapeStyle,
+    },
+    /// 배경의 원본 영역을 다른 곳에 확대해서 보여주는 돋보기
+    Magnifier {
+        source: Rect,
+        inset: Rect,
+        /// true면 원본/확대 영역을 원형(타원)으로 표시
+        circular: bool,
+        style: ShapeStyle,
+    },
+    /// 붙여넣거나 불러온 이미지, 스탬프
+    Image {
+        rect: Rect,
+        image: LayerImage,
+        /// 테두리, 불투명도, 모서리 둥글기
+        style: ShapeStyle,
     },
 }
 
 impl DrawShape {
+    /// 도형 종류 이름
+    pub fn type_label(&self) -> &'static str {
+        match self {
+            DrawShape::Rectangle { .. } => "사각형",
+            DrawShape::Ellipse { .. } => "타원",
+            DrawShape::Arrow { .. } => "화살표",
+            DrawShape::Line { .. } => "직선",
+            DrawShape::Curve { .. } => "곡선",
+            DrawShape::Text { .. } => "텍스트",
+            DrawShape::Number { .. } => "번호",
+            DrawShape::Callout { .. } => "말풍선",
+            DrawShape::Spotlight { .. } => "스포트라이트",
+            DrawShape::Magnifier { .. } => "돋보기",
+            DrawShape::Image { .. } => "이미지",
+        }
+    }
+
+    /// 레이어 목록에 표시할 아이콘 (도구 모음과 같은 기호)
+    pub fn type_icon(&self) -> &'static str {
+        match self {
+            DrawShape::Rectangle { .. } => "□",
+            DrawShape::Ellipse { .. } => "○",
+            DrawShape::Arrow { .. } => "→",
+            DrawShape::Line { .. } => "—",
+            DrawShape::Curve { .. } => "~",
+            DrawShape::Text { .. } => "T",
+            DrawShape::Number { .. } => "#",
+            DrawShape::Callout { .. } => "💬",
+            DrawShape::Spotlight { .. } => "🔦",
+            DrawShape::Magnifier { .. } => "🔍",
+            DrawShape::Image { .. } => "🖼",
+        }
+    }
+
     /// egui Shape으로 변환
-    pub fn to_egui_shape(&self) -> Vec<Shape> {
+    pub fn to_egui_shape(&self, ctx: &PaintContext) -> Vec<Shape> {
+        let fonts = ctx.fonts;
         match self {
-            DrawShape::Rectangle { rect, stroke, filled } => {
-                if *filled {
-                    vec![Shape::rect_filled(*rect, 0.0, stroke.color)]
-                } else {
-                    vec![Shape::rect_stroke(*rect, 0.0, *stroke)]
+            DrawShape::Rectangle { rect, style } => {
+                let mut shapes = Vec::new();
+                if let Some(fill) = style.fill() {
+                    shapes.push(Shape::rect_filled(*rect, style.corner_radius, fill));
                 }
+                shapes.extend(style::stroke_path(&style::rounded_rect_points(*rect, style.corner_radius), true, style));
+                shapes
             }
             DrawShape::Ellipse {
                 center,
                 radius_x,
                 radius_y,
-                stroke,
-                filled,
+                style,
             } => {
-                let rect = Rect::from_center_size(
-                    *center,
-                    egui::vec2(*radius_x * 2.0, *radius_y * 2.0),
-                );
-                if *filled {
-                    vec![Shape::ellipse_filled(*center, egui::vec2(*radius_x, *radius_y), stroke.color)]
-                } else {
-                    vec![Shape::ellipse_stroke(*center, egui::vec2(*radius_x, *radius_y), *stroke)]
+                let radius = egui::vec2(*radius_x, *radius_y);
+                let mut shapes = Vec::new();
+                if let Some(fill) = style.fill() {
+                    shapes.push(Shape::ellipse_filled(*center, radius, fill));
                 }
-            }
-            DrawShape::Arrow { start, end, stroke } => {
-                let mut shapes = vec![Shape::line_segment([*start, *end], *stroke)];
-
-                // 화살표 머리 그리기
-                let dir = (*end - *start).normalized();
-                let perp = egui::vec2(-dir.y, dir.x);
-                let arrow_size = 10.0;
-
-                let arrow_point1 = *end - dir * arrow_size + perp * arrow_size * 0.5;
-                let arrow_point2 = *end - dir * arrow_size - perp * arrow_size * 0.5;
-
-                shapes.push(Shape::line_segment([*end, arrow_point1], *stroke));
-                shapes.push(Shape::line_segment([*end, arrow_point2], *stroke));
+                shapes.extend(style::stroke_path(&style::ellipse_points(*center, radius), true, style));
                 shapes
             }
-            DrawShape::Line { points, stroke } => {
+            DrawShape::Arrow { start, end, control, kind, style } => {
+                arrow_shapes(*start, *end, *control, *kind, style)
+            }
+            DrawShape::Line { points, style } => style::stroke_path(points, false, style),
+            DrawShape::Curve { points, smoothing, style } => {
                 if points.len() < 2 {
                     return vec![];
                 }
-                vec![Shape::line(points.clone(), *stroke)]
+                // 단순화된 제어점을 Catmull-Rom 스플라인으로 보간
+                style::stroke_path(&smooth_curve(points, *smoothing), false, style)
             }
-            DrawShape::Curve { points, stroke } => {
-                if points.len() < 2 {
-                    return vec![];
+            DrawShape::Text { pos, text, font_size, style } => {
+                // 배경(채우기)은 글자 영역 크기로, 글자는 선 색으로 그림 (SVG/PDF 내보내기와 같음)
+                let color = style.stroke().color;
+                let galley = fonts.layout_no_wrap(text.clone(), FontId::proportional(*font_size), color);
+                let mut shapes = Vec::new();
+                if let Some(fill) = style.fill() {
+                    shapes.push(Shape::rect_filled(Rect::from_min_size(*pos, galley.size()), style.corner_radius, fill));
+                }
+                shapes.push(Shape::galley(*pos, galley, color));
+                shapes
+            }
+            DrawShape::Number { center, number, radius, format, style } => {
+                let label = format.format(*number);
+                let badge_color = style.fill_color.unwrap_or(style.stroke_color);
+                let text_color = contrasting_text_color(badge_color).gamma_multiply(style.opacity.clamp(0.0, 1.0));
+
+                let galley = badge_galley(fonts, label, *radius, text_color);
+                let text_pos = *center - galley.size() * 0.5;
+                let mut shapes = vec![Shape::circle_filled(*center, *radius, style.fill().unwrap_or(style.stroke().color))];
+                if style.fill_color.is_some() {
+                    let outline = style::ellipse_points(*center, egui::vec2(*radius, *radius));
+                    shapes.extend(style::stroke_path(&outline, true, style));
                 }
-                // 곡선은 여러 작은 선분으로 근사
-                vec![Shape::line(points.clone(), *stroke)]
+                shapes.push(Shape::galley(text_pos, galley, text_color));
+                shapes
             }
-            DrawShape::Text { pos, text, color, font_size } => {
-                // Note: Text rendering requires galley which needs fonts context
-                // For now, we'll use a simple rect to indicate text position
-                // In practice, this should be rendered via painter.text() in the UI code
-                let rect = Rect::from_min_size(*pos, egui::vec2(text.len() as f32 * font_size * 0.6, *font_size));
-                vec![Shape::rect_stroke(rect, 0.0, Stroke::new(1.0, *color))]
+            DrawShape::Callout { rect, target, text, font_size, style } => {
+                let mut shapes = Vec::new();
+                let tail = callout_tail(*rect, *target, *font_size);
+                let fill = style.fill();
+
+                if let Some(fill) = fill {
+                    shapes.push(Shape::rect_filled(*rect, CALLOUT_ROUNDING, fill));
+                }
+                shapes.extend(style::stroke_path(&style::rounded_rect_points(*rect, CALLOUT_ROUNDING), true, style));
+
+                // 꼬리를 나중에 그려 꼬리 밑변을 지나는 말풍선 외곽선을 덮음
+                if let Some(tail) = tail {
+                    if let Some(fill) = fill {
+                        shapes.push(Shape::convex_polygon(tail.to_vec(), fill, Stroke::NONE));
+                    }
+                    let exits = [rect_exit(*rect, tail[0], tail[1]), rect_exit(*rect, tail[2], tail[1])];
+                    shapes.extend(style::stroke_path(&[exits[0], tail[1], exits[1]], false, style));
+                }
+
+                let text_color = match style.fill_color {
+                    Some(color) => contrasting_text_color(color),
+                    None => style.stroke_color,
+                }
+                .gamma_multiply(style.opacity.clamp(0.0, 1.0));
+                let wrap_width = (rect.width() - CALLOUT_PADDING * 2.0).max(0.0);
+                let galley = fonts.layout(text.clone(), FontId::proportional(*font_size), text_color, wrap_width);
+                shapes.push(Shape::galley(rect.min + egui::vec2(CALLOUT_PADDING, CALLOUT_PADDING), galley, text_color));
+                shapes
             }
-            DrawShape::Number { center, number, color, radius } => {
-                vec![Shape::circle_filled(*center, *radius, *color)]
+            // 어둡게 하는 효과는 배경에 적용되므로 (effects::apply_effects) 구멍 테두리만 그림
+            DrawShape::Spotlight { holes, style, .. } => holes
+                .iter()
+                .flat_map(|hole| {
+                    let outline = if hole.ellipse {
+                        style::ellipse_points(hole.rect.center(), hole.rect.size() * 0.5)
+                    } else {
+                        style::rounded_rect_points(hole.rect, style.corner_radius)
+                    };
+                    style::stroke_path(&outline, true, style)
+                })
+                .collect(),
+            DrawShape::Magnifier { source, inset, circular, style } => {
+                magnifier_shapes(ctx, *source, *inset, *circular, style)
             }
+            DrawShape::Image { rect, image, style } => match ctx.layer_textures.get(&image.id) {
+                Some(texture) => {
+                    let mut shapes = vec![textured_rect(*rect, style.corner_radius, *texture, unit_uv(), style.tint())];
+                    shapes.extend(style::stroke_path(&style::rounded_rect_points(*rect, style.corner_radius), true, style));
+                    shapes
+                }
+                None => vec![],
+            },
         }
     }
 
@@ -135,8 +440,12 @@ impl DrawShape {
             DrawShape::Ellipse { center, radius_x, radius_y, .. } => {
                 Rect::from_center_size(*center, egui::vec2(*radius_x * 2.0, *radius_y * 2.0))
             }
-            DrawShape::Arrow { start, end, .. } => {
-                Rect::from_two_pos(*start, *end)
+            DrawShape::Arrow { start, end, control, style, .. } => {
+                let mut rect = Rect::from_two_pos(*start, *end);
+                if let Some(control) = control {
+                    rect.extend_with(*control);
+                }
+                rect.expand(arrow_head_size(style.stroke_width) * 0.5)
             }
             DrawShape::Line { points, .. } | DrawShape::Curve { points, .. } => {
                 if points.is_empty() {
@@ -156,13 +465,616 @@ impl DrawShape {
             DrawShape::Number { center, radius, .. } => {
                 Rect::from_center_size(*center, egui::vec2(*radius * 2.0, *radius * 2.0))
             }
+            DrawShape::Callout { rect, target, .. } => rect.union(Rect::from_two_pos(*target, *target)),
+            DrawShape::Spotlight { holes, .. } => {
+                holes.iter().fold(Rect::NOTHING, |acc, hole| acc.union(hole.rect))
+            }
+            DrawShape::Magnifier { source, inset, .. } => source.union(*inset),
+            DrawShape::Image { rect, .. } => *rect,
         }
     }
 
     /// 점이 도형 내부에 있는지 확인
     pub fn contains_point(&self, point: Pos2) -> bool {
-        self.bounding_rect().contains(point)
+        match self {
+            // 스포트라이트는 어두운 영역 전체가 아니라 구멍을 눌렀을 때만 선택
+            DrawShape::Spotlight { holes, .. } => holes.iter().any(|hole| hole.rect.contains(point)),
+            DrawShape::Magnifier { source, inset, .. } => source.contains(point) || inset.contains(point),
+            _ => self.bounding_rect().contains(point),
+        }
+    }
+
+    /// 선택했을 때 드래그할 수 있는 핸들 위치
+    pub fn handles(&self) -> Vec<Pos2> {
+        match self {
+            DrawShape::Arrow { start, end, control, .. } => {
+                let mut handles = vec![*start, *end];
+                handles.extend(control);
+                handles
+            }
+            DrawShape::Callout { target, .. } => vec![*target],
+            // 구멍마다 두 모서리
+            DrawShape::Spotlight { holes, .. } => holes.iter().flat_map(|h| [h.rect.min, h.rect.max]).collect(),
+            // 원본/확대 영역의 두 모서리와 중심 (중심은 영역 이동)
+            DrawShape::Magnifier { source, inset, .. } => {
+                vec![source.min, source.max, inset.min, inset.max, source.center(), inset.center()]
+            }
+            DrawShape::Image { rect, .. } => vec![rect.min, rect.max],
+            _ => vec![],
+        }
     }
+
+    /// 핸들을 새 위치로 이동
+    pub fn move_handle(&mut self, index: usize, pos: Pos2) {
+        match self {
+            DrawShape::Arrow { start, end, control, .. } => match (index, control) {
+                (0, _) => *start = pos,
+                (1, _) => *end = pos,
+                (2, Some(control)) => *control = pos,
+                _ => {}
+            },
+            DrawShape::Callout { target, .. } if index == 0 => *target = pos,
+            DrawShape::Spotlight { holes, .. } => {
+                if let Some(hole) = holes.get_mut(index / 2) {
+                    let other = if index.is_multiple_of(2) { hole.rect.max } else { hole.rect.min };
+                    hole.rect = Rect::from_two_pos(pos, other);
+                }
+            }
+            DrawShape::Magnifier { source, inset, .. } => match index {
+                0 => *source = Rect::from_two_pos(pos, source.max),
+                1 => *source = Rect::from_two_pos(source.min, pos),
+                2 => *inset = Rect::from_two_pos(pos, inset.max),
+                3 => *inset = Rect::from_two_pos(inset.min, pos),
+                4 => *source = Rect::from_center_size(pos, source.size()),
+                5 => *inset = Rect::from_center_size(pos, inset.size()),
+                _ => {}
+            },
+            DrawShape::Image { rect, image, .. } => {
+                // 반대쪽 모서리를 고정하고 원본 비율을 유지하며 크기 조절
+                let anchor = if index == 0 { rect.max } else { rect.min };
+                let aspect = image.aspect();
+                let delta = pos - anchor;
+                let width = delta.x.abs().max(delta.y.abs() * aspect).max(MIN_IMAGE_SIZE);
+                let size = egui::vec2(width * delta.x.signum(), width / aspect * delta.y.signum());
+                *rect = Rect::from_two_pos(anchor, anchor + size);
+            }
+            _ => {}
+        }
+    }
+
+    /// 도형 전체를 이동
+    pub fn translate(&mut self, delta: egui::Vec2) {
+        match self {
+            DrawShape::Rectangle { rect, .. } => *rect = rect.translate(delta),
+            DrawShape::Ellipse { center, .. } => *center += delta,
+            DrawShape::Arrow { start, end, control, .. } => {
+                *start += delta;
+                *end += delta;
+                if let Some(control) = control {
+                    *control += delta;
+                }
+            }
+            DrawShape::Line { points, .. } | DrawShape::Curve { points, .. } => {
+                for p in points {
+                    *p += delta;
+                }
+            }
+            DrawShape::Text { pos, .. } => *pos += delta,
+            DrawShape::Number { center, .. } => *center += delta,
+            DrawShape::Callout { rect, target, .. } => {
+                *rect = rect.translate(delta);
+                *target += delta;
+            }
+            DrawShape::Spotlight { holes, .. } => {
+                for hole in holes {
+                    hole.rect = hole.rect.translate(delta);
+                }
+            }
+            DrawShape::Magnifier { source, inset, .. } => {
+                *source = source.translate(delta);
+                *inset = inset.translate(delta);
+            }
+            DrawShape::Image { rect, .. } => *rect = rect.translate(delta),
+        }
+    }
+
+    /// 배경 이미지를 자르거나 회전/반전/크기 조절할 때 도형 좌표도 같이 변환
+    ///
+    /// 텍스트, 번호, 말풍선은 글자가 눕지 않도록 위치(중심)만 옮기고,
+    /// 이미지 레이어는 픽셀도 같은 방향으로 회전/반전합니다.
+    pub fn transform(&mut self, transform: &GeometryTransform) {
+        let scale = transform.length_scale();
+        match self {
+            DrawShape::Rectangle { rect, style } => {
+                *rect = transform.apply_rect(*rect);
+                *style = style.scaled(scale);
+            }
+            DrawShape::Ellipse { center, radius_x, radius_y, style } => {
+                *center = transform.apply(*center);
+                let radius = transform.apply_size(egui::vec2(*radius_x, *radius_y));
+                (*radius_x, *radius_y) = (radius.x, radius.y);
+                *style = style.scaled(scale);
+            }
+            DrawShape::Arrow { start, end, control, style, .. } => {
+                *start = transform.apply(*start);
+                *end = transform.apply(*end);
+                if let Some(control) = control {
+                    *control = transform.apply(*control);
+                }
+                *style = style.scaled(scale);
+            }
+            DrawShape::Line { points, style } | DrawShape::Curve { points, style, .. } => {
+                for p in points {
+                    *p = transform.apply(*p);
+                }
+                *style = style.scaled(scale);
+            }
+            DrawShape::Text { pos, text, font_size, style } => {
+                let center = transform.apply(text_box(*pos, text, *font_size).center());
+                *font_size *= scale;
+                *pos = center - text_box(Pos2::ZERO, text, *font_size).size() * 0.5;
+                *style = style.scaled(scale);
+            }
+            DrawShape::Number { center, radius, style, .. } => {
+                *center = transform.apply(*center);
+                *radius *= scale;
+                *style = style.scaled(scale);
+            }
+            DrawShape::Callout { rect, target, font_size, style, .. } => {
+                *rect = Rect::from_center_size(transform.apply(rect.center()), rect.size() * scale);
+                *target = transform.apply(*target);
+                *font_size *= scale;
+                *style = style.scaled(scale);
+            }
+            DrawShape::Spotlight { holes, style, .. } => {
+                for hole in holes {
+                    hole.rect = transform.apply_rect(hole.rect);
+                }
+                *style = style.scaled(scale);
+            }
+            DrawShape::Magnifier { source, inset, style, .. } => {
+                *source = transform.apply_rect(*source);
+                *inset = transform.apply_rect(*inset);
+                *style = style.scaled(scale);
+            }
+            DrawShape::Image { rect, image, style } => {
+                *rect = Rect::from_center_size(transform.apply(rect.center()), transform.apply_size(rect.size()));
+                if transform.changes_orientation() {
+                    *image = LayerImage::new(reorient(&image.pixels, transform));
+                }
+                *style = style.scaled(scale);
+            }
+        }
+    }
+
+    /// 편집 가능한 텍스트 (텍스트와 말풍선만 해당)
+    pub fn text_mut(&mut self) -> Option<&mut String> {
+        match self {
+            DrawShape::Text { text, .. } | DrawShape::Callout { text, .. } => Some(text),
+            _ => None,
+        }
+    }
+
+    /// 말풍선 크기를 텍스트에 맞춤 (왼쪽 위 모서리 고정)
+    pub fn fit_to_text(&mut self, fonts: &Fonts) {
+        if let DrawShape::Callout { rect, text, font_size, .. } = self {
+            let galley = fonts.layout(text.clone(), FontId::proportional(*font_size), Color32::WHITE, CALLOUT_MAX_TEXT_WIDTH);
+            let padding = egui::vec2(CALLOUT_PADDING, CALLOUT_PADDING) * 2.0;
+            *rect = Rect::from_min_size(rect.min, galley.size().ceil() + padding);
+        }
+    }
+}
+
+/// 텍스트 도형이 차지하는 영역 (글자 수와 크기로 추정)
+fn text_box(pos: Pos2, text: &str, font_size: f32) -> Rect {
+    Rect::from_min_size(pos, egui::vec2(text.len() as f32 * font_size * 0.6, font_size))
+}
+
+/// 이미지 레이어의 최소 너비
+const MIN_IMAGE_SIZE: f32 = 4.0;
+
+/// 원형 돋보기를 근사할 다각형 꼭짓점 수
+const MAGNIFIER_SEGMENTS: usize = 64;
+
+/// 돋보기 egui Shape 생성
+fn magnifier_shapes(ctx: &PaintContext, source: Rect, inset: Rect, circular: bool, style: &ShapeStyle) -> Vec<Shape> {
+    if source.width() < 1.0 || source.height() < 1.0 || inset.width() < 1.0 || inset.height() < 1.0 {
+        return vec![];
+    }
+
+    // 원본 영역을 배경 텍스처 좌표로 변환
+    let size = ctx.background_size;
+    let uv = Rect::from_min_max(
+        Pos2::new(source.min.x / size.x, source.min.y / size.y),
+        Pos2::new(source.max.x / size.x, source.max.y / size.y),
+    );
+
+    let mut shapes = vec![];
+
+    // 원본과 확대 영역을 잇는 연결선
+    let (from, to) = (source.center(), inset.center());
+    if !source.intersects(inset) {
+        let dir = (to - from).normalized();
+        let start = boundary_point(source, circular, dir);
+        let end = boundary_point(inset, circular, -dir);
+        shapes.extend(style::stroke_path(&[start, end], false, style));
+    }
+
+    if circular {
+        // 타원을 삼각형 부채꼴 메시로 만들어 텍스처 좌표를 매핑
+        let mut mesh = Mesh::with_texture(ctx.background);
+        let vertex = |p: Vec2| Vertex {
+            pos: inset.min + p * inset.size(),
+            uv: uv.min + p * uv.size(),
+            color: style.tint(),
+        };
+        mesh.vertices.push(vertex(egui::vec2(0.5, 0.5)));
+        for i in 0..MAGNIFIER_SEGMENTS {
+            let angle = i as f32 / MAGNIFIER_SEGMENTS as f32 * std::f32::consts::TAU;
+            mesh.vertices.push(vertex(egui::vec2(0.5 + 0.5 * angle.cos(), 0.5 + 0.5 * angle.sin())));
+        }
+        for i in 0..MAGNIFIER_SEGMENTS as u32 {
+            mesh.add_triangle(0, i + 1, (i + 1) % MAGNIFIER_SEGMENTS as u32 + 1);
+        }
+        shapes.push(Shape::mesh(mesh));
+        shapes.extend(style::stroke_path(&style::ellipse_points(source.center(), source.size() * 0.5), true, style));
+        shapes.extend(style::stroke_path(&style::ellipse_points(inset.center(), inset.size() * 0.5), true, style));
+    } else {
+        shapes.push(textured_rect(inset, style.corner_radius, ctx.background, uv, style.tint()));
+        shapes.extend(style::stroke_path(&style::rounded_rect_points(source, style.corner_radius), true, style));
+        shapes.extend(style::stroke_path(&style::rounded_rect_points(inset, style.corner_radius), true, style));
+    }
+
+    shapes
+}
+
+/// 텍스처 전체를 가리키는 UV 좌표
+fn unit_uv() -> Rect {
+    Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0))
+}
+
+/// 모서리가 둥근 텍스처 사각형
+fn textured_rect(rect: Rect, rounding: f32, texture: TextureId, uv: Rect, tint: Color32) -> Shape {
+    let mut shape = RectShape::filled(rect, rounding, tint);
+    shape.fill_texture_id = texture;
+    shape.uv = uv;
+    Shape::Rect(shape)
+}
+
+/// 사각형 안의 점 `inside`에서 바깥 점 `outside`로 가는 선분이 사각형 경계를 지나는 점
+pub fn rect_exit(rect: Rect, inside: Pos2, outside: Pos2) -> Pos2 {
+    let d = outside - inside;
+    let mut t: f32 = 1.0;
+    if d.x > f32::EPSILON {
+        t = t.min((rect.max.x - inside.x) / d.x);
+    } else if d.x < -f32::EPSILON {
+        t = t.min((rect.min.x - inside.x) / d.x);
+    }
+    if d.y > f32::EPSILON {
+        t = t.min((rect.max.y - inside.y) / d.y);
+    } else if d.y < -f32::EPSILON {
+        t = t.min((rect.min.y - inside.y) / d.y);
+    }
+    inside + d * t.clamp(0.0, 1.0)
+}
+
+/// 영역 중심에서 `dir` 방향으로 나간 경계 위의 점
+pub fn boundary_point(rect: Rect, circular: bool, dir: Vec2) -> Pos2 {
+    let half = rect.size() * 0.5;
+    if circular {
+        return rect.center() + dir * half;
+    }
+    let scale_x = if dir.x.abs() > f32::EPSILON { half.x / dir.x.abs() } else { f32::INFINITY };
+    let scale_y = if dir.y.abs() > f32::EPSILON { half.y / dir.y.abs() } else { f32::INFINITY };
+    rect.center() + dir * scale_x.min(scale_y)
+}
+
+/// 원본 영역 옆에 확대 영역을 배치 (가능하면 이미지 안쪽으로)
+pub fn default_magnifier_inset(source: Rect, zoom: f32, bounds: Rect) -> Rect {
+    let size = source.size() * zoom;
+    let gap = 20.0;
+    let right = Rect::from_min_size(Pos2::new(source.max.x + gap, source.min.y), size);
+    let left = Rect::from_min_size(Pos2::new(source.min.x - gap - size.x, source.min.y), size);
+    let mut inset = if bounds.contains_rect(right) || !bounds.contains_rect(left) { right } else { left };
+
+    // 세로 방향은 이미지 안으로 밀어 넣음
+    if inset.max.y > bounds.max.y {
+        inset = inset.translate(egui::vec2(0.0, bounds.max.y - inset.max.y));
+    }
+    if inset.min.y < bounds.min.y {
+        inset = inset.translate(egui::vec2(0.0, bounds.min.y - inset.min.y));
+    }
+    inset
+}
+
+/// 말풍선 모서리 둥글기
+pub const CALLOUT_ROUNDING: f32 = 8.0;
+
+/// 말풍선 안쪽 여백
+pub const CALLOUT_PADDING: f32 = 8.0;
+
+/// 말풍선 텍스트 자동 줄바꿈 너비
+const CALLOUT_MAX_TEXT_WIDTH: f32 = 280.0;
+
+/// 말풍선 꼬리 삼각형 (대상이 말풍선 안에 있으면 None)
+pub fn callout_tail(rect: Rect, target: Pos2, font_size: f32) -> Option<[Pos2; 3]> {
+    if rect.contains(target) {
+        return None;
+    }
+
+    // 꼬리 밑변이 둥근 모서리가 아닌 평평한 변에 놓이도록 안쪽 사각형 기준으로 계산
+    let inner = rect.shrink(CALLOUT_ROUNDING);
+    let center = rect.center();
+    let dir = target - center;
+    let half = (inner.size() * 0.5).max(egui::Vec2::ZERO);
+    let scale_x = if dir.x.abs() > f32::EPSILON { half.x / dir.x.abs() } else { f32::INFINITY };
+    let scale_y = if dir.y.abs() > f32::EPSILON { half.y / dir.y.abs() } else { f32::INFINITY };
+    let base = center + dir * scale_x.min(scale_y).min(1.0);
+
+    let perp = egui::vec2(-dir.y, dir.x).normalized();
+    let half_width = (font_size * 0.6).min(half.min_elem().max(2.0));
+    Some([base + perp * half_width, target, base - perp * half_width])
+}
+
+/// 번호 배지 글자 (배지 안에 들어가도록 글자가 길면 크기를 줄임)
+pub fn badge_galley(fonts: &Fonts, label: String, radius: f32, color: Color32) -> Arc<Galley> {
+    let font_size = badge_font_size(fonts, &label, radius);
+    fonts.layout_no_wrap(label, FontId::proportional(font_size), color)
+}
+
+/// 번호 배지 글자 크기
+pub fn badge_font_size(fonts: &Fonts, label: &str, radius: f32) -> f32 {
+    let max_width = radius * 1.6;
+    let width = fonts.layout_no_wrap(label.to_string(), FontId::proportional(radius * 1.2), Color32::WHITE).size().x;
+    if width <= max_width {
+        radius * 1.2
+    } else {
+        radius * 1.2 * max_width / width
+    }
+}
+
+/// 배경색 위에서 잘 보이는 글자색 (흰색 또는 검정)
+pub fn contrasting_text_color(background: Color32) -> Color32 {
+    // ITU-R BT.601 가중치로 밝기 계산
+    let luma = 0.299 * background.r() as f32 + 0.587 * background.g() as f32 + 0.114 * background.b() as f32;
+    if luma > 150.0 {
+        Color32::BLACK
+    } else {
+        Color32::WHITE
+    }
+}
+
+/// 선 두께에 비례하는 화살표 머리 길이
+pub fn arrow_head_size(stroke_width: f32) -> f32 {
+    (stroke_width * 4.0).max(10.0)
+}
+
+/// 시작점과 끝점 사이로 휘는 곡선 화살표의 기본 제어점
+pub fn default_arrow_control(start: Pos2, end: Pos2) -> Pos2 {
+    let delta = end - start;
+    let perp = egui::vec2(-delta.y, delta.x);
+    start + delta * 0.5 + perp * 0.25
+}
+
+/// 2차 베지어 곡선을 꺾은선으로 근사
+fn flatten_quadratic(p0: Pos2, p1: Pos2, p2: Pos2) -> Vec<Pos2> {
+    let length = p0.distance(p1) + p1.distance(p2);
+    let steps = ((length / SPLINE_SEGMENT_LENGTH).ceil() as usize).clamp(2, 64);
+    (0..=steps)
+        .map(|i| {
+            let t = i as f32 / steps as f32;
+            let u = 1.0 - t;
+            (p0.to_vec2() * (u * u) + p1.to_vec2() * (2.0 * u * t) + p2.to_vec2() * (t * t)).to_pos2()
+        })
+        .collect()
+}
+
+/// 꺾은선 끝에서 `length`만큼 잘라냄 (화살표 머리와 겹치지 않도록)
+fn trim_polyline_end(points: &mut Vec<Pos2>, mut length: f32) {
+    while points.len() >= 2 {
+        let last = points[points.len() - 1];
+        let prev = points[points.len() - 2];
+        let segment = last.distance(prev);
+        if segment > length {
+            *points.last_mut().unwrap() = last + (prev - last) * (length / segment);
+            return;
+        }
+        length -= segment;
+        points.pop();
+    }
+}
+
+/// 끝점 `tip`에서 `dir` 방향을 가리키는 채워진 삼각형 머리
+fn arrow_head(tip: Pos2, dir: egui::Vec2, size: f32, color: Color32) -> Shape {
+    Shape::convex_polygon(arrow_head_points(tip, dir, size).to_vec(), color, Stroke::NONE)
+}
+
+/// 화살표 머리 삼각형의 꼭짓점 (끝점, 밑변 양 끝)
+pub fn arrow_head_points(tip: Pos2, dir: egui::Vec2, size: f32) -> [Pos2; 3] {
+    let perp = egui::vec2(-dir.y, dir.x);
+    let base = tip - dir * size;
+    [tip, base + perp * size * 0.5, base - perp * size * 0.5]
+}
+
+/// 화살표 모양별 egui Shape 생성
+fn arrow_shapes(start: Pos2, end: Pos2, control: Option<Pos2>, kind: ArrowStyle, style: &ShapeStyle) -> Vec<Shape> {
+    let length = start.distance(end);
+    if length < 1.0 {
+        return vec![];
+    }
+
+    // 머리가 화살표 길이보다 커지지 않도록 제한
+    let head = arrow_head_size(style.stroke_width).min(length * 0.5);
+    let dir = (end - start) / length;
+    let color = style.stroke().color;
+
+    let mut shapes = match kind {
+        ArrowStyle::Filled => style::stroke_path(&[start, end - dir * head * 0.8], false, style),
+        ArrowStyle::Double => style::stroke_path(&[start + dir * head * 0.8, end - dir * head * 0.8], false, style),
+        ArrowStyle::Pointer => {
+            // 시작점에서 뾰족하게 시작해 머리 쪽으로 갈수록 굵어지는 몸통
+            let perp = egui::vec2(-dir.y, dir.x);
+            let base = end - dir * head * 0.8;
+            let half_width = style.stroke_width.max(1.0);
+            vec![Shape::convex_polygon(
+                vec![start, base + perp * half_width, base - perp * half_width],
+                color,
+                Stroke::NONE,
+            )]
+        }
+        ArrowStyle::Curved => {
+            let control = control.unwrap_or_else(|| default_arrow_control(start, end));
+            let mut points = flatten_quadratic(start, control, end);
+            trim_polyline_end(&mut points, head * 0.8);
+            style::stroke_path(&points, false, style)
+        }
+    };
+
+    let tip_dir = match (kind, control) {
+        // 곡선 화살표 머리 방향은 곡선 끝의 접선 방향
+        (ArrowStyle::Curved, control) => {
+            let control = control.unwrap_or_else(|| default_arrow_control(start, end));
+            if control.distance(end) > f32::EPSILON { (end - control).normalized() } else { dir }
+        }
+        _ => dir,
+    };
+    shapes.push(arrow_head(end, tip_dir, head, color));
+    if kind == ArrowStyle::Double {
+        shapes.push(arrow_head(start, -dir, head, color));
+    }
+    shapes
+}
+
+/// 곡선 단순화 허용 오차 (smoothing 1.0 기준, 픽셀)
+const MAX_SIMPLIFY_EPSILON: f32 = 3.0;
+
+/// 스플라인 보간 시 제어점 사이의 최대 선분 길이 (픽셀)
+const SPLINE_SEGMENT_LENGTH: f32 = 4.0;
+
+/// 부드럽게 하기 강도에 따른 단순화 허용 오차
+pub fn simplify_epsilon(smoothing: f32) -> f32 {
+    smoothing.clamp(0.0, 1.0) * MAX_SIMPLIFY_EPSILON
+}
+
+/// Ramer–Douglas–Peucker 알고리즘으로 점 개수 줄이기
+pub fn simplify_points(points: &[Pos2], epsilon: f32) -> Vec<Pos2> {
+    if points.len() < 3 || epsilon <= 0.0 {
+        return points.to_vec();
+    }
+
+    let mut keep = vec![false; points.len()];
+    keep[0] = true;
+    keep[points.len() - 1] = true;
+
+    // 재귀 대신 명시적 스택 사용 (긴 드래그에서도 안전)
+    let mut stack = vec![(0, points.len() - 1)];
+    while let Some((first, last)) = stack.pop() {
+        if last <= first + 1 {
+            continue;
+        }
+
+        let mut max_dist = 0.0;
+        let mut index = first;
+        for i in (first + 1)..last {
+            let dist = distance_to_segment(points[i], points[first], points[last]);
+            if dist > max_dist {
+                max_dist = dist;
+                index = i;
+            }
+        }
+
+        if max_dist > epsilon {
+            keep[index] = true;
+            stack.push((first, index));
+            stack.push((index, last));
+        }
+    }
+
+    points
+        .iter()
+        .zip(keep)
+        .filter_map(|(p, k)| k.then_some(*p))
+        .collect()
+}
+
+/// 점과 선분 사이의 거리
+pub fn distance_to_segment(p: Pos2, a: Pos2, b: Pos2) -> f32 {
+    let ab = b - a;
+    let len_sq = ab.length_sq();
+    if len_sq <= f32::EPSILON {
+        return p.distance(a);
+    }
+    let t = ((p - a).dot(ab) / len_sq).clamp(0.0, 1.0);
+    p.distance(a + ab * t)
+}
+
+/// 제어점을 지나는 Catmull-Rom 스플라인 생성
+///
+/// `smoothing`이 0이면 원래 꺾은선을 그대로 반환합니다.
+/// 값이 클수록 제어점 사이의 접선이 길어져 더 둥근 곡선이 됩니다.
+pub fn smooth_curve(points: &[Pos2], smoothing: f32) -> Vec<Pos2> {
+    let smoothing = smoothing.clamp(0.0, 1.0);
+    if points.len() < 3 || smoothing <= 0.0 {
+        return points.to_vec();
+    }
+
+    // 표준 Catmull-Rom 접선(0.5)에 강도를 곱함
+    let tension = 0.5 * smoothing;
+    let last = points.len() - 1;
+    let mut result = Vec::with_capacity(points.len() * 4);
+    result.push(points[0]);
+
+    for i in 0..last {
+        let p0 = points[i.saturating_sub(1)];
+        let p1 = points[i];
+        let p2 = points[i + 1];
+        let p3 = points[(i + 2).min(last)];
+
+        let m1 = (p2 - p0) * tension;
+        let m2 = (p3 - p1) * tension;
+
+        let steps = ((p1.distance(p2) / SPLINE_SEGMENT_LENGTH).ceil() as usize).clamp(1, 32);
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            let t2 = t * t;
+            let t3 = t2 * t;
+
+            // 에르미트 기저 함수
+            let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+            let h10 = t3 - 2.0 * t2 + t;
+            let h01 = -2.0 * t3 + 3.0 * t2;
+            let h11 = t3 - t2;
+
+            let p = p1.to_vec2() * h00 + m1 * h10 + p2.to_vec2() * h01 + m2 * h11;
+            result.push(p.to_pos2());
+        }
+    }
+
+    result
+}
+
+/// `smooth_curve`와 같은 스플라인을 3차 베지어 구간 (제어점 1, 제어점 2, 끝점)으로 변환
+///
+/// 벡터 형식으로 내보낼 때 사용합니다. 부드럽게 하지 않는 곡선이면 None (꺾은선으로 그림).
+pub fn curve_bezier_segments(points: &[Pos2], smoothing: f32) -> Option<Vec<[Pos2; 3]>> {
+    let smoothing = smoothing.clamp(0.0, 1.0);
+    if points.len() < 3 || smoothing <= 0.0 {
+        return None;
+    }
+
+    // 에르미트 접선 m에 대해 베지어 제어점은 p ± m / 3
+    let tension = 0.5 * smoothing;
+    let last = points.len() - 1;
+    let segments = (0..last)
+        .map(|i| {
+            let p0 = points[i.saturating_sub(1)];
+            let p1 = points[i];
+            let p2 = points[i + 1];
+            let p3 = points[(i + 2).min(last)];
+            [p1 + (p2 - p0) * tension / 3.0, p2 - (p3 - p1) * tension / 3.0, p2]
+        })
+        .collect();
+    Some(segments)
 }
 
 /// 색상 팔레트
@@ -179,3 +1091,18 @@ pub const COLORS: [Color32; 8] = [
 
 /// 선 두께 옵션
 pub const STROKE_WIDTHS: [f32; 5] = [1.0, 2.0, 3.0, 5.0, 8.0];
+
+/// 번호 배지 기본 반지름
+pub const DEFAULT_BADGE_RADIUS: f32 = 15.0;
+
+/// 스포트라이트 기본 어둡기
+pub const DEFAULT_SPOTLIGHT_OPACITY: f32 = 0.6;
+
+/// 돋보기 기본 확대 배율
+pub const DEFAULT_MAGNIFICATION: f32 = 2.0;
+
+/// 스포이트 평균 영역 크기 옵션 (N×N 픽셀)
+pub const SAMPLE_SIZES: [usize; 3] = [1, 3, 5];
+
+/// 곡선 부드럽게 하기 기본 강도
+pub const DEFAULT_SMOOTHING: f32 = 0.5;
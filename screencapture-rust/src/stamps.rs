@@ -0,0 +1,87 @@
+use crate::render;
+use egui::{Color32, ColorImage, FontId, Pos2, Shape, Stroke};
+
+/// 스탬프 이미지 한 변의 픽셀 크기 (확대해도 깨지지 않도록 삽입 크기보다 크게 렌더링)
+const STAMP_RESOLUTION: usize = 128;
+
+/// 캔버스에 삽입될 때의 기본 크기
+pub const STAMP_SIZE: f32 = 64.0;
+
+/// QA 리뷰용 기본 스탬프
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stamp {
+    Check,    // 통과
+    Cross,    // 실패
+    Warning,  // 주의
+    ThumbsUp, // 좋음
+}
+
+impl Stamp {
+    pub const ALL: [Stamp; 4] = [Stamp::Check, Stamp::Cross, Stamp::Warning, Stamp::ThumbsUp];
+
+    /// 도구 모음에 표시할 아이콘
+    pub fn icon(self) -> &'static str {
+        match self {
+            Stamp::Check => "✔",
+            Stamp::Cross => "✖",
+            Stamp::Warning => "⚠",
+            Stamp::ThumbsUp => "👍",
+        }
+    }
+
+    /// 도구 모음 툴팁
+    pub fn label(self) -> &'static str {
+        match self {
+            Stamp::Check => "체크",
+            Stamp::Cross => "엑스",
+            Stamp::Warning => "경고",
+            Stamp::ThumbsUp => "좋아요",
+        }
+    }
+
+    /// 투명 배경 위에 스탬프 이미지 생성
+    pub fn render(self) -> ColorImage {
+        let size = STAMP_RESOLUTION as f32;
+        let center = Pos2::new(size / 2.0, size / 2.0);
+        let radius = size / 2.0 - 4.0;
+        let line = Stroke::new(size / 10.0, Color32::WHITE);
+        let p = |x: f32, y: f32| Pos2::new(x * size, y * size);
+
+        let fonts = render::export_fonts();
+        let shapes = match self {
+            Stamp::Check => vec![
+                Shape::circle_filled(center, radius, Color32::from_rgb(46, 160, 67)),
+                Shape::line(vec![p(0.28, 0.52), p(0.44, 0.68), p(0.74, 0.36)], line),
+            ],
+            Stamp::Cross => vec![
+                Shape::circle_filled(center, radius, Color32::from_rgb(218, 54, 51)),
+                Shape::line_segment([p(0.32, 0.32), p(0.68, 0.68)], line),
+                Shape::line_segment([p(0.68, 0.32), p(0.32, 0.68)], line),
+            ],
+            Stamp::Warning => {
+                let black = Stroke::new(size / 10.0, Color32::BLACK);
+                vec![
+                    Shape::convex_polygon(
+                        vec![p(0.5, 0.06), p(0.96, 0.9), p(0.04, 0.9)],
+                        Color32::from_rgb(255, 193, 7),
+                        Stroke::new(3.0, Color32::BLACK),
+                    ),
+                    Shape::line_segment([p(0.5, 0.35), p(0.5, 0.62)], black),
+                    Shape::circle_filled(p(0.5, 0.76), size / 18.0, Color32::BLACK),
+                ]
+            }
+            Stamp::ThumbsUp => {
+                let galley = fonts.layout_no_wrap("👍".to_string(), FontId::proportional(size * 0.55), Color32::WHITE);
+                let text_pos = center - galley.size() * 0.5;
+                vec![
+                    Shape::circle_filled(center, radius, Color32::from_rgb(9, 105, 218)),
+                    Shape::galley(text_pos, galley, Color32::WHITE),
+                ]
+            }
+        };
+
+        let mut image = ColorImage::new([STAMP_RESOLUTION, STAMP_RESOLUTION], Color32::TRANSPARENT);
+        render::rasterize_shapes(&mut image, shapes, &fonts, &[]);
+        image
+    }
+}
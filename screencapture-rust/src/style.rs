@@ -0,0 +1,351 @@
+use egui::{Color32, Pos2, Rect, Shape, Stroke, Vec2};
+use serde::{Deserialize, Serialize};
+
+/// 점선 모양
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DashPattern {
+    Solid,   // 실선
+    Dashed,  // 파선
+    Dotted,  // 점선
+    DashDot, // 일점쇄선
+}
+
+impl DashPattern {
+    pub const ALL: [DashPattern; 4] = [
+        DashPattern::Solid,
+        DashPattern::Dashed,
+        DashPattern::Dotted,
+        DashPattern::DashDot,
+    ];
+
+    /// 도구 모음에 표시할 이름
+    pub fn label(self) -> &'static str {
+        match self {
+            DashPattern::Solid => "실선",
+            DashPattern::Dashed => "파선",
+            DashPattern::Dotted => "점선",
+            DashPattern::DashDot => "일점쇄선",
+        }
+    }
+
+    /// 선 두께에 비례하는 선/간격 길이 (짝수 번째가 선, 실선은 빈 목록)
+    pub fn lengths(self, width: f32) -> Vec<f32> {
+        let w = width.max(1.0);
+        match self {
+            DashPattern::Solid => vec![],
+            DashPattern::Dashed => vec![w * 4.0, w * 2.0],
+            DashPattern::Dotted => vec![w, w * 2.0],
+            DashPattern::DashDot => vec![w * 6.0, w * 2.0, w, w * 2.0],
+        }
+    }
+}
+
+/// 열린 선의 끝 모양
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LineCap {
+    Butt,   // 끝점에서 잘림
+    Round,  // 반원
+    Square, // 두께 절반만큼 늘린 사각형
+}
+
+impl LineCap {
+    pub const ALL: [LineCap; 3] = [LineCap::Butt, LineCap::Round, LineCap::Square];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LineCap::Butt => "평평하게",
+            LineCap::Round => "둥글게",
+            LineCap::Square => "사각형",
+        }
+    }
+}
+
+/// 선이 꺾이는 점의 모양
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LineJoin {
+    Miter, // 뾰족하게
+    Round, // 둥글게
+    Bevel, // 깎아서
+}
+
+impl LineJoin {
+    pub const ALL: [LineJoin; 3] = [LineJoin::Miter, LineJoin::Round, LineJoin::Bevel];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LineJoin::Miter => "뾰족하게",
+            LineJoin::Round => "둥글게",
+            LineJoin::Bevel => "깎아서",
+        }
+    }
+}
+
+/// 도형의 선/채우기 스타일
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ShapeStyle {
+    pub stroke_color: Color32,
+    /// 0이면 외곽선을 그리지 않음
+    pub stroke_width: f32,
+    /// 채우기 색 (None이면 채우지 않음)
+    pub fill_color: Option<Color32>,
+    /// 선과 채우기 모두에 곱해지는 불투명도 (0.0 ~ 1.0)
+    pub opacity: f32,
+    pub dash: DashPattern,
+    pub line_cap: LineCap,
+    pub line_join: LineJoin,
+    /// 사각형 모서리 둥글기
+    pub corner_radius: f32,
+}
+
+impl ShapeStyle {
+    /// 외곽선만 있는 스타일
+    pub fn outline(color: Color32, width: f32) -> Self {
+        Self {
+            stroke_color: color,
+            stroke_width: width,
+            fill_color: None,
+            opacity: 1.0,
+            dash: DashPattern::Solid,
+            line_cap: LineCap::Butt,
+            line_join: LineJoin::Miter,
+            corner_radius: 0.0,
+        }
+    }
+
+    /// 불투명도가 적용된 외곽선
+    pub fn stroke(&self) -> Stroke {
+        Stroke::new(self.stroke_width, self.apply_opacity(self.stroke_color))
+    }
+
+    /// 불투명도가 적용된 채우기 색
+    pub fn fill(&self) -> Option<Color32> {
+        self.fill_color.map(|color| self.apply_opacity(color))
+    }
+
+    /// 불투명도가 적용된 이미지 색조 (이미지 레이어, 돋보기)
+    pub fn tint(&self) -> Color32 {
+        self.apply_opacity(Color32::WHITE)
+    }
+
+    /// 선 두께와 모서리 둥글기를 배율만큼 조절 (이미지 크기 조절에 맞춤)
+    pub fn scaled(self, factor: f32) -> Self {
+        Self {
+            stroke_width: self.stroke_width * factor,
+            corner_radius: self.corner_radius * factor,
+            ..self
+        }
+    }
+
+    fn apply_opacity(&self, color: Color32) -> Color32 {
+        color.gamma_multiply(self.opacity.clamp(0.0, 1.0))
+    }
+}
+
+impl Default for ShapeStyle {
+    fn default() -> Self {
+        Self::outline(Color32::RED, 2.0)
+    }
+}
+
+/// 이 값보다 완만하게 꺾이는 점(방향 벡터 내적)은 끊지 않고 이어서 그림
+///
+/// 곡선처럼 잘게 나뉜 점마다 이음새 도형을 겹쳐 그리지 않기 위함입니다.
+const SMOOTH_JOIN_COS: f32 = 0.985;
+
+/// 꺾은선을 스타일의 점선, 선 끝, 꺾임 모양에 맞춰 그리기
+pub fn stroke_path(points: &[Pos2], closed: bool, style: &ShapeStyle) -> Vec<Shape> {
+    let stroke = style.stroke();
+    if points.len() < 2 || stroke.is_empty() {
+        return vec![];
+    }
+
+    let pattern = style.dash.lengths(stroke.width);
+    if pattern.is_empty() {
+        return stroke_polyline(points, closed, stroke, style.line_cap, style.line_join);
+    }
+
+    let mut path = points.to_vec();
+    if closed {
+        path.push(points[0]);
+    }
+    dash_runs(&path, &pattern)
+        .iter()
+        .flat_map(|run| stroke_polyline(run, false, stroke, style.line_cap, style.line_join))
+        .collect()
+}
+
+/// 점선 패턴에 따라 꺾은선을 그려질 구간들로 나눔
+fn dash_runs(path: &[Pos2], pattern: &[f32]) -> Vec<Vec<Pos2>> {
+    let mut runs = Vec::new();
+    let mut current = vec![path[0]];
+    let mut index = 0;
+    let mut remaining = pattern[0];
+
+    for window in path.windows(2) {
+        let (mut a, b) = (window[0], window[1]);
+        let mut length = a.distance(b);
+        while length > remaining {
+            let p = a + (b - a) * (remaining / length);
+            if index % 2 == 0 {
+                current.push(p);
+                runs.push(std::mem::take(&mut current));
+            } else {
+                current = vec![p];
+            }
+            length -= remaining;
+            a = p;
+            index = (index + 1) % pattern.len();
+            remaining = pattern[index];
+        }
+        remaining -= length;
+        if index % 2 == 0 {
+            current.push(b);
+        }
+    }
+
+    if index % 2 == 0 && current.len() >= 2 {
+        runs.push(current);
+    }
+    runs
+}
+
+/// 실선 꺾은선 하나를 선 끝/꺾임 모양에 맞춰 그리기
+fn stroke_polyline(path: &[Pos2], closed: bool, stroke: Stroke, cap: LineCap, join: LineJoin) -> Vec<Shape> {
+    // 겹친 점은 방향을 계산할 수 없으므로 제거
+    let mut points: Vec<Pos2> = Vec::with_capacity(path.len() + 1);
+    for &p in path {
+        if points.last().is_none_or(|last| last.distance(p) > 0.01) {
+            points.push(p);
+        }
+    }
+    if points.len() < 2 {
+        return vec![];
+    }
+    if closed && join == LineJoin::Miter {
+        return vec![Shape::closed_line(points, stroke)];
+    }
+
+    let half_width = stroke.width * 0.5;
+    let mut shapes = Vec::new();
+    if closed {
+        points.push(points[0]);
+    } else if cap == LineCap::Square {
+        // 양 끝을 두께 절반만큼 늘림
+        let n = points.len();
+        let start_dir = (points[0] - points[1]).normalized();
+        let end_dir = (points[n - 1] - points[n - 2]).normalized();
+        points[0] += start_dir * half_width;
+        points[n - 1] += end_dir * half_width;
+    }
+
+    if join == LineJoin::Miter {
+        shapes.push(Shape::line(points.clone(), stroke));
+    } else {
+        // 크게 꺾이는 점에서 선을 끊고 그 자리에 이음새 도형을 채움
+        let last = points.len() - 1;
+        let mut run = vec![points[0]];
+        for i in 1..=last {
+            run.push(points[i]);
+            let corner = if i < last {
+                Some((points[i - 1], points[i], points[i + 1]))
+            } else if closed {
+                Some((points[last - 1], points[last], points[1]))
+            } else {
+                None
+            };
+            if let Some((prev, p, next)) = corner {
+                let d1 = (p - prev).normalized();
+                let d2 = (next - p).normalized();
+                if d1.dot(d2) < SMOOTH_JOIN_COS {
+                    shapes.push(Shape::line(std::mem::replace(&mut run, vec![p]), stroke));
+                    shapes.push(join_shape(p, d1, d2, half_width, stroke.color, join));
+                }
+            }
+        }
+        if run.len() >= 2 {
+            shapes.push(Shape::line(run, stroke));
+        }
+    }
+
+    if !closed && cap == LineCap::Round {
+        let n = points.len();
+        shapes.push(half_disc(points[0], (points[0] - points[1]).normalized(), half_width, stroke.color));
+        shapes.push(half_disc(points[n - 1], (points[n - 1] - points[n - 2]).normalized(), half_width, stroke.color));
+    }
+
+    shapes
+}
+
+/// 꺾이는 점 바깥쪽의 빈틈을 채우는 도형
+fn join_shape(p: Pos2, d1: Vec2, d2: Vec2, radius: f32, color: Color32, join: LineJoin) -> Shape {
+    // 바깥쪽은 꺾이는 방향의 반대편
+    let turn = d1.x * d2.y - d1.y * d2.x;
+    let side = if turn > 0.0 { -1.0 } else { 1.0 };
+    let n1 = egui::vec2(-d1.y, d1.x) * side;
+    let n2 = egui::vec2(-d2.y, d2.x) * side;
+
+    match join {
+        LineJoin::Round => {
+            let start = n1.y.atan2(n1.x);
+            let mut sweep = n2.y.atan2(n2.x) - start;
+            if sweep > std::f32::consts::PI {
+                sweep -= std::f32::consts::TAU;
+            } else if sweep < -std::f32::consts::PI {
+                sweep += std::f32::consts::TAU;
+            }
+            Shape::convex_polygon(arc_fan(p, radius, start, sweep), color, Stroke::NONE)
+        }
+        _ => Shape::convex_polygon(vec![p, p + n1 * radius, p + n2 * radius], color, Stroke::NONE),
+    }
+}
+
+/// 선 끝 바깥쪽 반원
+fn half_disc(center: Pos2, dir: Vec2, radius: f32, color: Color32) -> Shape {
+    let start = dir.y.atan2(dir.x) - std::f32::consts::FRAC_PI_2;
+    let mut points = arc_fan(center, radius, start, std::f32::consts::PI);
+    points.remove(0);
+    Shape::convex_polygon(points, color, Stroke::NONE)
+}
+
+/// 중심점과 호 위의 점들 (부채꼴)
+fn arc_fan(center: Pos2, radius: f32, start: f32, sweep: f32) -> Vec<Pos2> {
+    let steps = ((sweep.abs() * radius / 2.0).ceil() as usize).clamp(2, 32);
+    let mut points = vec![center];
+    for i in 0..=steps {
+        let angle = start + sweep * i as f32 / steps as f32;
+        points.push(center + egui::vec2(angle.cos(), angle.sin()) * radius);
+    }
+    points
+}
+
+/// 둥근 사각형 외곽선의 점들 (시계 방향)
+pub fn rounded_rect_points(rect: Rect, radius: f32) -> Vec<Pos2> {
+    let r = radius.min(rect.width() * 0.5).min(rect.height() * 0.5).max(0.0);
+    if r < 0.5 {
+        return vec![rect.left_top(), rect.right_top(), rect.right_bottom(), rect.left_bottom()];
+    }
+
+    let half_pi = std::f32::consts::FRAC_PI_2;
+    let corners = [
+        (Pos2::new(rect.max.x - r, rect.min.y + r), -half_pi),
+        (Pos2::new(rect.max.x - r, rect.max.y - r), 0.0),
+        (Pos2::new(rect.min.x + r, rect.max.y - r), half_pi),
+        (Pos2::new(rect.min.x + r, rect.min.y + r), std::f32::consts::PI),
+    ];
+    corners
+        .iter()
+        .flat_map(|&(center, start)| arc_fan(center, r, start, half_pi).into_iter().skip(1))
+        .collect()
+}
+
+/// 타원 외곽선의 점들
+pub fn ellipse_points(center: Pos2, radius: Vec2) -> Vec<Pos2> {
+    let circumference = std::f32::consts::TAU * radius.x.max(radius.y);
+    let steps = ((circumference / 4.0).ceil() as usize).clamp(16, 256);
+    (0..steps)
+        .map(|i| {
+            let angle = i as f32 / steps as f32 * std::f32::consts::TAU;
+            center + egui::vec2(angle.cos() * radius.x, angle.sin() * radius.y)
+        })
+        .collect()
+}
@@ -0,0 +1,477 @@
+use crate::base64;
+use crate::clipboard::encode_png;
+use crate::effects;
+use crate::shapes::{self, ArrowStyle, DrawShape, CALLOUT_PADDING, CALLOUT_ROUNDING};
+use crate::style::{DashPattern, LineCap, LineJoin, ShapeStyle};
+use egui::epaint::text::{Fonts, Galley};
+use egui::{Color32, ColorImage, FontId, Pos2, Rect, Vec2};
+
+/// 캡처 이미지를 가리키는 SVG 요소 ID (돋보기가 같은 이미지를 확대해 씀)
+const CAPTURE_ID: &str = "capture";
+
+/// 주석이 그려진 캡처를 SVG 문서로 변환
+///
+/// 캡처는 base64 PNG `<image>`로 넣고 도형은 벡터 편집기에서 고칠 수 있도록 SVG 요소로 씁니다.
+/// 스포트라이트처럼 픽셀을 바꾸는 배경 효과는 캡처 이미지에 미리 적용합니다.
+pub fn export_svg(background: &ColorImage, shapes: &[&DrawShape], fonts: &Fonts) -> Result<String, String> {
+    let mut capture = background.clone();
+    effects::apply_effects(&mut capture, shapes.iter().copied());
+    let [width, height] = capture.size;
+
+    let mut svg = SvgWriter { out: String::new(), fonts, next_id: 0 };
+    svg.line(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    svg.line(&format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+        width, height
+    ));
+    svg.line(&format!(
+        r#"<defs><image id="{}" width="{}" height="{}" xlink:href="{}"/></defs>"#,
+        CAPTURE_ID,
+        width,
+        height,
+        png_data_url(&capture)?
+    ));
+    svg.line(&format!(r##"<use xlink:href="#{}"/>"##, CAPTURE_ID));
+    for shape in shapes {
+        svg.shape(shape)?;
+    }
+    svg.line("</svg>");
+    Ok(svg.out)
+}
+
+/// SVG 문서 작성 상태
+struct SvgWriter<'a> {
+    out: String,
+    fonts: &'a Fonts,
+    /// 화살표 머리, 잘라내기 영역 등에 붙이는 고유 ID
+    next_id: usize,
+}
+
+impl SvgWriter<'_> {
+    fn line(&mut self, text: &str) {
+        self.out.push_str(text);
+        self.out.push('\n');
+    }
+
+    fn id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}-{}", prefix, self.next_id)
+    }
+
+    fn shape(&mut self, shape: &DrawShape) -> Result<(), String> {
+        match shape {
+            DrawShape::Rectangle { rect, style } => {
+                let element = rect_element(*rect, style.corner_radius, &fill_attrs(style.fill()), &stroke_attrs(style));
+                self.line(&element);
+            }
+            DrawShape::Ellipse { center, radius_x, radius_y, style } => {
+                self.line(&format!(
+                    r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}" {} {}/>"#,
+                    num(center.x),
+                    num(center.y),
+                    num(*radius_x),
+                    num(*radius_y),
+                    fill_attrs(style.fill()),
+                    stroke_attrs(style)
+                ));
+            }
+            DrawShape::Arrow { start, end, control, kind, style } => self.arrow(*start, *end, *control, *kind, style),
+            DrawShape::Line { points, style } => {
+                if points.len() >= 2 {
+                    self.line(&format!(
+                        r#"<polyline points="{}" fill="none" {}/>"#,
+                        points_attr(points),
+                        stroke_attrs(style)
+                    ));
+                }
+            }
+            DrawShape::Curve { points, smoothing, style } => {
+                if points.len() >= 2 {
+                    self.line(&format!(
+                        r#"<path d="{}" fill="none" {}/>"#,
+                        curve_path(points, *smoothing),
+                        stroke_attrs(style)
+                    ));
+                }
+            }
+            DrawShape::Text { pos, text, font_size, style } => {
+                let color = style.stroke().color;
+                let galley = self.fonts.layout_no_wrap(text.clone(), FontId::proportional(*font_size), color);
+                self.line("<g>");
+                if let Some(fill) = style.fill() {
+                    let rect = Rect::from_min_size(*pos, galley.size());
+                    self.line(&rect_element(rect, style.corner_radius, &fill_attrs(Some(fill)), r#"stroke="none""#));
+                }
+                self.text(*pos, &galley, *font_size, color);
+                self.line("</g>");
+            }
+            DrawShape::Number { center, number, radius, format, style } => {
+                let badge_color = style.fill_color.unwrap_or(style.stroke_color);
+                let text_color = shapes::contrasting_text_color(badge_color).gamma_multiply(style.opacity.clamp(0.0, 1.0));
+                let label = format.format(*number);
+                let font_size = shapes::badge_font_size(self.fonts, &label, *radius);
+                let outline = if style.fill_color.is_some() { stroke_attrs(style) } else { r#"stroke="none""#.to_string() };
+
+                self.line("<g>");
+                self.line(&format!(
+                    r#"<circle cx="{}" cy="{}" r="{}" {} {}/>"#,
+                    num(center.x),
+                    num(center.y),
+                    num(*radius),
+                    fill_attrs(Some(style.fill().unwrap_or(style.stroke().color))),
+                    outline
+                ));
+                self.line(&format!(
+                    r#"<text x="{}" y="{}" text-anchor="middle" dominant-baseline="central" font-family="sans-serif" font-size="{}" {}>{}</text>"#,
+                    num(center.x),
+                    num(center.y),
+                    num(font_size),
+                    fill_attrs(Some(text_color)),
+                    escape(&label)
+                ));
+                self.line("</g>");
+            }
+            DrawShape::Callout { rect, target, text, font_size, style } => {
+                self.line("<g>");
+                let fill = style.fill();
+                self.line(&rect_element(*rect, CALLOUT_ROUNDING, &fill_attrs(fill), &stroke_attrs(style)));
+
+                // 꼬리 밑변을 지나는 말풍선 외곽선은 꼬리 채우기로 덮음
+                if let Some(tail) = shapes::callout_tail(*rect, *target, *font_size) {
+                    if fill.is_some() {
+                        self.line(&format!(
+                            r#"<polygon points="{}" {} stroke="none"/>"#,
+                            points_attr(&tail),
+                            fill_attrs(fill)
+                        ));
+                    }
+                    let exits = [shapes::rect_exit(*rect, tail[0], tail[1]), shapes::rect_exit(*rect, tail[2], tail[1])];
+                    self.line(&format!(
+                        r#"<polyline points="{}" fill="none" {}/>"#,
+                        points_attr(&[exits[0], tail[1], exits[1]]),
+                        stroke_attrs(style)
+                    ));
+                }
+
+                let text_color = match style.fill_color {
+                    Some(color) => shapes::contrasting_text_color(color),
+                    None => style.stroke_color,
+                }
+                .gamma_multiply(style.opacity.clamp(0.0, 1.0));
+                let wrap_width = (rect.width() - CALLOUT_PADDING * 2.0).max(0.0);
+                let galley = self.fonts.layout(text.clone(), FontId::proportional(*font_size), text_color, wrap_width);
+                self.text(rect.min + Vec2::splat(CALLOUT_PADDING), &galley, *font_size, text_color);
+                self.line("</g>");
+            }
+            // 어둡게 하는 효과는 캡처 이미지에 이미 적용되어 있으므로 구멍 테두리만 씀
+            DrawShape::Spotlight { holes, style, .. } => {
+                self.line("<g>");
+                for hole in holes {
+                    let outline = if hole.ellipse {
+                        ellipse_element(hole.rect, r#"fill="none""#, &stroke_attrs(style))
+                    } else {
+                        rect_element(hole.rect, style.corner_radius, r#"fill="none""#, &stroke_attrs(style))
+                    };
+                    self.line(&outline);
+                }
+                self.line("</g>");
+            }
+            DrawShape::Magnifier { source, inset, circular, style } => self.magnifier(*source, *inset, *circular, style),
+            DrawShape::Image { rect, image, style } => {
+                let clip = self.clip_path(*rect, false, style.corner_radius);
+                self.line("<g>");
+                self.line(&format!(
+                    r#"<image x="{}" y="{}" width="{}" height="{}" preserveAspectRatio="none" opacity="{}"{} xlink:href="{}"/>"#,
+                    num(rect.min.x),
+                    num(rect.min.y),
+                    num(rect.width()),
+                    num(rect.height()),
+                    num(style.opacity.clamp(0.0, 1.0)),
+                    clip,
+                    png_data_url(&image.pixels)?
+                ));
+                self.line(&rect_element(*rect, style.corner_radius, r#"fill="none""#, &stroke_attrs(style)));
+                self.line("</g>");
+            }
+        }
+        Ok(())
+    }
+
+    /// 화살표 (머리는 `<marker>`, 포인터 화살표는 몸통과 머리를 채운 도형으로)
+    fn arrow(&mut self, start: Pos2, end: Pos2, control: Option<Pos2>, kind: ArrowStyle, style: &ShapeStyle) {
+        let length = start.distance(end);
+        if length < 1.0 {
+            return;
+        }
+        let head = shapes::arrow_head_size(style.stroke_width).min(length * 0.5);
+        let dir = (end - start) / length;
+        let color = style.stroke().color;
+
+        if kind == ArrowStyle::Pointer {
+            let perp = Vec2::new(-dir.y, dir.x);
+            let base = end - dir * head * 0.8;
+            let half_width = style.stroke_width.max(1.0);
+            let head_base = end - dir * head;
+            self.line("<g>");
+            self.line(&format!(
+                r#"<polygon points="{}" {} stroke="none"/>"#,
+                points_attr(&[start, base + perp * half_width, base - perp * half_width]),
+                fill_attrs(Some(color))
+            ));
+            self.line(&format!(
+                r#"<polygon points="{}" {} stroke="none"/>"#,
+                points_attr(&[end, head_base + perp * head * 0.5, head_base - perp * head * 0.5]),
+                fill_attrs(Some(color))
+            ));
+            self.line("</g>");
+            return;
+        }
+
+        // 머리 끝이 화살표 끝점에 오도록 선은 머리 길이의 80%만큼 짧게 그림
+        let marker = self.id("arrow");
+        self.line(&format!(
+            r#"<defs><marker id="{}" markerUnits="userSpaceOnUse" markerWidth="{}" markerHeight="{}" viewBox="0 0 10 10" refX="2" refY="5" orient="auto-start-reverse"><path d="M0,0 L10,5 L0,10 Z" {}/></marker></defs>"#,
+            marker,
+            num(head),
+            num(head),
+            fill_attrs(Some(color))
+        ));
+
+        let (path, both_ends) = match kind {
+            ArrowStyle::Double => {
+                let (a, b) = (start + dir * head * 0.8, end - dir * head * 0.8);
+                (format!("M{} L{}", point(a), point(b)), true)
+            }
+            ArrowStyle::Curved => {
+                let control = control.unwrap_or_else(|| shapes::default_arrow_control(start, end));
+                let tip_dir = if control.distance(end) > f32::EPSILON { (end - control).normalized() } else { dir };
+                let tip = end - tip_dir * head * 0.8;
+                (format!("M{} Q{} {}", point(start), point(control), point(tip)), false)
+            }
+            _ => (format!("M{} L{}", point(start), point(end - dir * head * 0.8)), false),
+        };
+        let start_marker = if both_ends { format!(r#" marker-start="url(#{})""#, marker) } else { String::new() };
+        self.line(&format!(
+            r#"<path d="{}" fill="none" {}{} marker-end="url(#{})"/>"#,
+            path,
+            stroke_attrs(style),
+            start_marker,
+            marker
+        ));
+    }
+
+    /// 돋보기 (캡처 이미지를 잘라내기 영역 안에서 확대)
+    fn magnifier(&mut self, source: Rect, inset: Rect, circular: bool, style: &ShapeStyle) {
+        if source.width() < 1.0 || source.height() < 1.0 || inset.width() < 1.0 || inset.height() < 1.0 {
+            return;
+        }
+        let outline = |rect: Rect| {
+            if circular {
+                ellipse_element(rect, r#"fill="none""#, &stroke_attrs(style))
+            } else {
+                rect_element(rect, style.corner_radius, r#"fill="none""#, &stroke_attrs(style))
+            }
+        };
+
+        self.line("<g>");
+        if !source.intersects(inset) {
+            let dir = (inset.center() - source.center()).normalized();
+            let from = shapes::boundary_point(source, circular, dir);
+            let to = shapes::boundary_point(inset, circular, -dir);
+            self.line(&format!(r#"<polyline points="{}" fill="none" {}/>"#, points_attr(&[from, to]), stroke_attrs(style)));
+        }
+
+        // 원본 영역이 확대 영역에 맞도록 캡처 이미지를 옮기고 늘림
+        let scale = inset.size() / source.size();
+        let offset = inset.min.to_vec2() - source.min.to_vec2() * scale;
+        let clip = self.clip_path(inset, circular, style.corner_radius);
+        self.line(&format!(r#"<g{} opacity="{}">"#, clip, num(style.opacity.clamp(0.0, 1.0))));
+        self.line(&format!(
+            r##"<use xlink:href="#{}" transform="matrix({} 0 0 {} {} {})"/>"##,
+            CAPTURE_ID,
+            num(scale.x),
+            num(scale.y),
+            num(offset.x),
+            num(offset.y)
+        ));
+        self.line("</g>");
+        self.line(&outline(source));
+        self.line(&outline(inset));
+        self.line("</g>");
+    }
+
+    /// 영역 밖을 잘라내는 `<clipPath>`를 정의하고 참조 속성 반환 (잘라낼 필요가 없으면 빈 문자열)
+    fn clip_path(&mut self, rect: Rect, ellipse: bool, radius: f32) -> String {
+        if !ellipse && radius <= 0.0 {
+            return String::new();
+        }
+        let id = self.id("clip");
+        let shape = if ellipse {
+            ellipse_element(rect, "", "")
+        } else {
+            rect_element(rect, radius, "", "")
+        };
+        self.line(&format!(r#"<defs><clipPath id="{}">{}</clipPath></defs>"#, id, shape));
+        format!(r#" clip-path="url(#{})""#, id)
+    }
+
+    /// 레이아웃된 텍스트를 줄마다 `<tspan>`으로 (`pos`는 텍스트 왼쪽 위)
+    fn text(&mut self, pos: Pos2, galley: &Galley, font_size: f32, color: Color32) {
+        self.line(&format!(
+            r#"<text font-family="sans-serif" font-size="{}" {} xml:space="preserve">"#,
+            num(font_size),
+            fill_attrs(Some(color))
+        ));
+        for row in &galley.rows {
+            let Some(first) = row.glyphs.first() else {
+                continue;
+            };
+            // 글리프 위치는 기준선 기준
+            self.line(&format!(
+                r#"<tspan x="{}" y="{}">{}</tspan>"#,
+                num(pos.x + first.pos.x),
+                num(pos.y + first.pos.y),
+                escape(&row.text())
+            ));
+        }
+        self.line("</text>");
+    }
+}
+
+/// 이미지를 PNG data URL로 변환
+fn png_data_url(image: &ColorImage) -> Result<String, String> {
+    Ok(format!("data:image/png;base64,{}", base64::encode(&encode_png(image)?)))
+}
+
+fn rect_element(rect: Rect, radius: f32, fill: &str, stroke: &str) -> String {
+    let radius = radius.min(rect.width() * 0.5).min(rect.height() * 0.5).max(0.0);
+    let rounding = if radius > 0.0 { format!(r#" rx="{}""#, num(radius)) } else { String::new() };
+    format!(
+        r#"<rect x="{}" y="{}" width="{}" height="{}"{}{}/>"#,
+        num(rect.min.x),
+        num(rect.min.y),
+        num(rect.width()),
+        num(rect.height()),
+        rounding,
+        paint_attrs(fill, stroke)
+    )
+}
+
+fn ellipse_element(rect: Rect, fill: &str, stroke: &str) -> String {
+    format!(
+        r#"<ellipse cx="{}" cy="{}" rx="{}" ry="{}"{}/>"#,
+        num(rect.center().x),
+        num(rect.center().y),
+        num(rect.width() * 0.5),
+        num(rect.height() * 0.5),
+        paint_attrs(fill, stroke)
+    )
+}
+
+/// 채우기/선 속성을 앞에 공백을 붙여 연결 (잘라내기 영역처럼 속성이 없으면 빈 문자열)
+fn paint_attrs(fill: &str, stroke: &str) -> String {
+    [fill, stroke].iter().filter(|attrs| !attrs.is_empty()).map(|attrs| format!(" {}", attrs)).collect()
+}
+
+/// 채우기 속성 (불투명도가 적용된 색)
+fn fill_attrs(color: Option<Color32>) -> String {
+    match color {
+        Some(color) => {
+            let (hex, alpha) = svg_color(color);
+            if alpha < 1.0 {
+                format!(r#"fill="{}" fill-opacity="{}""#, hex, num(alpha))
+            } else {
+                format!(r#"fill="{}""#, hex)
+            }
+        }
+        None => r#"fill="none""#.to_string(),
+    }
+}
+
+/// 선 색, 두께, 점선, 선 끝, 꺾임 속성
+fn stroke_attrs(style: &ShapeStyle) -> String {
+    let stroke = style.stroke();
+    if stroke.is_empty() {
+        return r#"stroke="none""#.to_string();
+    }
+    let (hex, alpha) = svg_color(stroke.color);
+    let mut attrs = format!(r#"stroke="{}" stroke-width="{}""#, hex, num(stroke.width));
+    if alpha < 1.0 {
+        attrs.push_str(&format!(r#" stroke-opacity="{}""#, num(alpha)));
+    }
+    let cap = match style.line_cap {
+        LineCap::Butt => "butt",
+        LineCap::Round => "round",
+        LineCap::Square => "square",
+    };
+    let join = match style.line_join {
+        LineJoin::Miter => "miter",
+        LineJoin::Round => "round",
+        LineJoin::Bevel => "bevel",
+    };
+    attrs.push_str(&format!(r#" stroke-linecap="{}" stroke-linejoin="{}""#, cap, join));
+    if style.dash != DashPattern::Solid {
+        let lengths: Vec<String> = style.dash.lengths(stroke.width).into_iter().map(num).collect();
+        attrs.push_str(&format!(r#" stroke-dasharray="{}""#, lengths.join(" ")));
+    }
+    attrs
+}
+
+/// "#RRGGBB"와 불투명도 (0~1)
+fn svg_color(color: Color32) -> (String, f32) {
+    let [r, g, b, a] = color.to_srgba_unmultiplied();
+    (format!("#{:02X}{:02X}{:02X}", r, g, b), a as f32 / 255.0)
+}
+
+/// 곡선 도형과 같은 Catmull-Rom 스플라인을 3차 베지어 경로로 변환
+fn curve_path(points: &[Pos2], smoothing: f32) -> String {
+    let mut path = format!("M{}", point(points[0]));
+    match shapes::curve_bezier_segments(points, smoothing) {
+        Some(segments) => {
+            for [c1, c2, end] in segments {
+                path.push_str(&format!(" C{} {} {}", point(c1), point(c2), point(end)));
+            }
+        }
+        None => {
+            for p in &points[1..] {
+                path.push_str(&format!(" L{}", point(*p)));
+            }
+        }
+    }
+    path
+}
+
+fn points_attr(points: &[Pos2]) -> String {
+    points.iter().map(|p| point(*p)).collect::<Vec<_>>().join(" ")
+}
+
+fn point(p: Pos2) -> String {
+    format!("{},{}", num(p.x), num(p.y))
+}
+
+/// 소수점 둘째 자리까지, 불필요한 0 제거
+fn num(value: f32) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" {
+        "0".to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// XML 텍스트/속성 이스케이프
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
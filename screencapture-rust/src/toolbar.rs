@@ -1,11 +1,63 @@
-use crate::shapes::{ToolType, COLORS, STROKE_WIDTHS};
-use egui::{Color32, Response, Ui};
+use crate::color_picker::{color_swatch, ColorPicker};
+use crate::color_format::{self, expand_template, ColorFormat, TEMPLATE_HELP};
+use crate::config::{ColorTemplate, Config};
+use crate::file_naming::{self, CaptureInfo, CollisionPolicy, DEFAULT_TEMPLATE, FILE_NAME_HELP};
+use crate::image_format::{Compression, ImageFormat};
+use crate::pdf::{PageOrientation, PageSize};
+use crate::shapes::{
+    ArrowStyle, NumberStyle, ToolType, COLORS, DEFAULT_BADGE_RADIUS, DEFAULT_MAGNIFICATION, DEFAULT_SMOOTHING,
+    DEFAULT_SPOTLIGHT_OPACITY, SAMPLE_SIZES, STROKE_WIDTHS,
+};
+use crate::stamps::Stamp;
+use crate::style::{DashPattern, LineCap, LineJoin};
+use crate::transform::ImageOp;
+use egui::{Align2, Color32, Pos2, Rect, Response, Ui};
 
 /// 도구 모음 UI
 pub struct Toolbar {
     pub current_tool: ToolType,
     pub current_color: Color32,
     pub current_stroke_width: f32,
+    /// 채우기 색 (None이면 채우지 않음)과 채우기 불투명도
+    pub current_fill: Option<Color32>,
+    pub fill_alpha: f32,
+    pub current_opacity: f32,
+    pub current_dash: DashPattern,
+    pub current_line_cap: LineCap,
+    pub current_line_join: LineJoin,
+    pub current_corner_radius: f32,
+    pub current_smoothing: f32,
+    pub current_arrow_style: ArrowStyle,
+    pub number_style: NumberStyle,
+    pub number_start: u32,
+    pub number_radius: f32,
+    pub spotlight_ellipse: bool,
+    pub spotlight_opacity: f32,
+    pub spotlight_desaturate: bool,
+    pub magnifier_zoom: f32,
+    pub magnifier_circular: bool,
+    pub current_stamp: Stamp,
+    /// 스포이트 평균 영역 크기와 마지막으로 추출한 색 (캔버스에서 전달됨)
+    pub sample_size: usize,
+    pub sampled_color: Option<Color32>,
+    /// 커서 옆 픽셀 정보 돋보기 표시 여부
+    pub show_pixel_info: bool,
+    /// 레이어 패널 표시 여부
+    pub show_layers: bool,
+    /// 자르기 영역 (캔버스와 양방향 동기화, 이미지 좌표)
+    pub crop_rect: Rect,
+    /// 현재 이미지 크기 (캔버스에서 전달됨, 이미지가 없으면 None)
+    pub image_size: Option<[usize; 2]>,
+    /// 색상 복사 템플릿 편집 창 표시 여부
+    show_template_editor: bool,
+    /// 크기 조절/캔버스 크기 창
+    resize_dialog: Option<ResizeDialog>,
+    /// 명령줄 `--comp` (저장 옵션 메뉴에 표시)
+    pub compression: Option<Compression>,
+
+    /// 선 색/채우기 색 선택 팝업
+    color_picker: ColorPicker,
+    fill_picker: ColorPicker,
 }
 
 impl Toolbar {
@@ -14,11 +66,40 @@ impl Toolbar {
             current_tool: ToolType::Rectangle,
             current_color: COLORS[0],
             current_stroke_width: STROKE_WIDTHS[1],
+            current_fill: None,
+            fill_alpha: DEFAULT_FILL_ALPHA,
+            current_opacity: 1.0,
+            current_dash: DashPattern::Solid,
+            current_line_cap: LineCap::Butt,
+            current_line_join: LineJoin::Miter,
+            current_corner_radius: 0.0,
+            current_smoothing: DEFAULT_SMOOTHING,
+            current_arrow_style: ArrowStyle::Filled,
+            number_style: NumberStyle::Numeric,
+            number_start: 1,
+            number_radius: DEFAULT_BADGE_RADIUS,
+            spotlight_ellipse: false,
+            spotlight_opacity: DEFAULT_SPOTLIGHT_OPACITY,
+            spotlight_desaturate: false,
+            magnifier_zoom: DEFAULT_MAGNIFICATION,
+            magnifier_circular: false,
+            current_stamp: Stamp::Check,
+            sample_size: SAMPLE_SIZES[0],
+            sampled_color: None,
+            show_pixel_info: false,
+            show_layers: false,
+            crop_rect: Rect::NOTHING,
+            image_size: None,
+            show_template_editor: false,
+            resize_dialog: None,
+            compression: None,
+            color_picker: ColorPicker::new("stroke"),
+            fill_picker: ColorPicker::new("fill"),
         }
     }
 
     /// 도구 모음 UI 렌더링
-    pub fn ui(&mut self, ui: &mut Ui) -> ToolbarAction {
+    pub fn ui(&mut self, ui: &mut Ui, config: &mut Config) -> ToolbarAction {
         let mut action = ToolbarAction::None;
 
         ui.horizontal(|ui| {
@@ -64,9 +145,27 @@ impl Toolbar {
             if self.tool_button(ui, "T", ToolType::Text, "텍스트").clicked() {
                 self.current_tool = ToolType::Text;
             }
+            if self.tool_button(ui, "💬", ToolType::Callout, "말풍선").clicked() {
+                self.current_tool = ToolType::Callout;
+            }
             if self.tool_button(ui, "#", ToolType::Number, "번호").clicked() {
                 self.current_tool = ToolType::Number;
             }
+            if self.tool_button(ui, "🔦", ToolType::Spotlight, "스포트라이트").clicked() {
+                self.current_tool = ToolType::Spotlight;
+            }
+            if self.tool_button(ui, "🔍", ToolType::Magnifier, "돋보기").clicked() {
+                self.current_tool = ToolType::Magnifier;
+            }
+            if self.tool_button(ui, "✔", ToolType::Stamp, "스탬프").clicked() {
+                self.current_tool = ToolType::Stamp;
+            }
+            if self.tool_button(ui, "💧", ToolType::Eyedropper, "스포이트").clicked() {
+                self.current_tool = ToolType::Eyedropper;
+            }
+            if self.tool_button(ui, "✂", ToolType::Crop, "자르기").clicked() {
+                self.current_tool = ToolType::Crop;
+            }
             if self.tool_button(ui, "⌧", ToolType::Mosaic, "모자이크").clicked() {
                 self.current_tool = ToolType::Mosaic;
             }
@@ -82,27 +181,14 @@ impl Toolbar {
 
             // 색상 선택
             for color in &COLORS {
-                let size = egui::vec2(24.0, 24.0);
-                let (rect, response) = ui.allocate_exact_size(size, egui::Sense::click());
-
-                if ui.is_rect_visible(rect) {
-                    let visuals = ui.style().interact(&response);
-                    let stroke = if self.current_color == *color {
-                        egui::Stroke::new(2.0, Color32::WHITE)
-                    } else {
-                        visuals.bg_stroke
-                    };
-
-                    ui.painter().rect_filled(rect, 2.0, *color);
-                    ui.painter().rect_stroke(rect, 2.0, stroke);
-                }
-
+                let response = color_swatch(ui, *color, self.current_color == *color, 24.0);
                 if response.clicked() {
                     self.current_color = *color;
                 }
 
                 response.on_hover_text(format!("RGB: {:?}", color));
             }
+            self.color_picker.ui(ui, &mut self.current_color, config);
 
             ui.separator();
 
@@ -115,6 +201,180 @@ impl Toolbar {
                     self.current_stroke_width = width;
                 }
             }
+
+            // 곡선 부드럽게 하기 강도
+            if self.current_tool == ToolType::Curve {
+                ui.separator();
+                ui.label("부드럽게:");
+                ui.add(egui::Slider::new(&mut self.current_smoothing, 0.0..=1.0).fixed_decimals(1))
+                    .on_hover_text("0: 원본 그대로, 1: 최대로 부드럽게");
+            }
+
+            // 화살표 모양
+            if self.current_tool == ToolType::Arrow {
+                ui.separator();
+                ui.label("화살표:");
+                for style in ArrowStyle::ALL {
+                    if ui.selectable_label(self.current_arrow_style == style, style.label()).clicked() {
+                        self.current_arrow_style = style;
+                    }
+                }
+            }
+
+            // 번호 배지 설정
+            if self.current_tool == ToolType::Number {
+                ui.separator();
+                ui.label("번호:");
+                for style in NumberStyle::ALL {
+                    if ui.selectable_label(self.number_style == style, style.label()).clicked() {
+                        self.number_style = style;
+                    }
+                }
+                ui.label("시작:");
+                ui.add(egui::DragValue::new(&mut self.number_start).range(0..=9999));
+                ui.label("크기:");
+                ui.add(egui::Slider::new(&mut self.number_radius, 8.0..=40.0).fixed_decimals(0));
+                if ui.button("번호 다시 매기기").clicked() {
+                    action = ToolbarAction::RenumberBadges;
+                }
+            }
+
+            // 스포트라이트 설정
+            if self.current_tool == ToolType::Spotlight {
+                ui.separator();
+                ui.label("구멍:");
+                if ui.selectable_label(!self.spotlight_ellipse, "□").clicked() {
+                    self.spotlight_ellipse = false;
+                }
+                if ui.selectable_label(self.spotlight_ellipse, "○").clicked() {
+                    self.spotlight_ellipse = true;
+                }
+                ui.label("어둡게:");
+                ui.add(egui::Slider::new(&mut self.spotlight_opacity, 0.0..=1.0).fixed_decimals(2));
+                ui.checkbox(&mut self.spotlight_desaturate, "흑백");
+            }
+
+            // 돋보기 설정
+            if self.current_tool == ToolType::Magnifier {
+                ui.separator();
+                ui.label("배율:");
+                for zoom in [2.0, 3.0, 4.0] {
+                    let selected = (self.magnifier_zoom - zoom).abs() < 0.1;
+                    if ui.selectable_label(selected, format!("{:.0}x", zoom)).clicked() {
+                        self.magnifier_zoom = zoom;
+                    }
+                }
+                ui.checkbox(&mut self.magnifier_circular, "원형");
+            }
+
+            // 스탬프 선택
+            if self.current_tool == ToolType::Stamp {
+                ui.separator();
+                ui.label("스탬프:");
+                for stamp in Stamp::ALL {
+                    if ui
+                        .selectable_label(self.current_stamp == stamp, stamp.icon())
+                        .on_hover_text(stamp.label())
+                        .clicked()
+                    {
+                        self.current_stamp = stamp;
+                    }
+                }
+            }
+
+            // 스포이트 설정 및 추출한 색
+            if self.current_tool == ToolType::Eyedropper {
+                ui.separator();
+                ui.label("평균:");
+                for size in SAMPLE_SIZES {
+                    if ui.selectable_label(self.sample_size == size, format!("{0}×{0}", size)).clicked() {
+                        self.sample_size = size;
+                    }
+                }
+                if let Some(color) = self.sampled_color {
+                    color_swatch(ui, color, false, 24.0)
+                        .on_hover_text(format!("RGB: {}, {}, {}", color.r(), color.g(), color.b()));
+                    if ui.button("CMYK 복사").clicked() {
+                        action = ToolbarAction::CopyColorFormat(ColorFormat::Cmyk);
+                    }
+                    if ui.button("펜 색으로 사용").clicked() {
+                        action = ToolbarAction::UseSampledColor;
+                    }
+                } else {
+                    ui.label("캡처 화면을 클릭해 색을 추출하세요");
+                }
+            }
+
+            // 자르기 영역 직접 입력
+            if self.current_tool == ToolType::Crop {
+                ui.separator();
+                if let Some(crop) = self.crop_ui(ui) {
+                    action = crop;
+                }
+            }
+        });
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.label("채우기:");
+
+            // 채우기 색 선택 (선 색과 별도)
+            if ui.selectable_label(self.current_fill.is_none(), "없음").clicked() {
+                self.current_fill = None;
+            }
+            for color in &COLORS {
+                if color_swatch(ui, *color, self.current_fill == Some(*color), 18.0).clicked() {
+                    self.current_fill = Some(*color);
+                }
+            }
+            // 선택기의 투명도는 채우기 불투명도 슬라이더와 같은 값 하나로 관리
+            let mut fill = self.fill_color().unwrap_or(self.current_color);
+            if self.fill_picker.ui(ui, &mut fill, config) {
+                let [r, g, b, a] = fill.to_srgba_unmultiplied();
+                self.current_fill = Some(Color32::from_rgb(r, g, b));
+                self.fill_alpha = a as f32 / 255.0;
+            }
+            ui.add_enabled(
+                self.current_fill.is_some(),
+                egui::Slider::new(&mut self.fill_alpha, 0.0..=1.0).fixed_decimals(2),
+            )
+            .on_hover_text("채우기 불투명도");
+
+            ui.separator();
+
+            ui.label("불투명도:");
+            ui.add(egui::Slider::new(&mut self.current_opacity, 0.1..=1.0).fixed_decimals(2));
+
+            ui.separator();
+
+            ui.label("선:");
+            egui::ComboBox::from_id_salt("dash")
+                .selected_text(self.current_dash.label())
+                .show_ui(ui, |ui| {
+                    for dash in DashPattern::ALL {
+                        ui.selectable_value(&mut self.current_dash, dash, dash.label());
+                    }
+                });
+            egui::ComboBox::from_id_salt("line_cap")
+                .selected_text(format!("끝: {}", self.current_line_cap.label()))
+                .show_ui(ui, |ui| {
+                    for cap in LineCap::ALL {
+                        ui.selectable_value(&mut self.current_line_cap, cap, cap.label());
+                    }
+                });
+            egui::ComboBox::from_id_salt("line_join")
+                .selected_text(format!("꺾임: {}", self.current_line_join.label()))
+                .show_ui(ui, |ui| {
+                    for join in LineJoin::ALL {
+                        ui.selectable_value(&mut self.current_line_join, join, join.label());
+                    }
+                });
+
+            ui.separator();
+
+            ui.label("모서리:");
+            ui.add(egui::Slider::new(&mut self.current_corner_radius, 0.0..=30.0).fixed_decimals(0));
         });
 
         ui.separator();
@@ -134,9 +394,48 @@ impl Toolbar {
             if ui.button("💾 저장").clicked() {
                 action = ToolbarAction::Save;
             }
+            ui.menu_button("⚙ 저장 옵션 ▾", |ui| {
+                if let Some(selected) = self.save_options_menu(ui, config) {
+                    action = selected;
+                    ui.close_menu();
+                }
+            });
             if ui.button("📋 클립보드에 복사").clicked() {
                 action = ToolbarAction::CopyToClipboard;
             }
+            ui.menu_button("🗁 열기 ▾", |ui| {
+                if let Some(selected) = Self::open_menu(ui, config) {
+                    action = selected;
+                    ui.close_menu();
+                }
+            });
+            if ui.button("🖫 세션 저장").on_hover_text("원본 캡처와 도형을 편집 가능한 .scap 파일로 저장 (Ctrl+Shift+S)").clicked() {
+                action = ToolbarAction::SaveSession;
+            }
+            ui.menu_button("📤 내보내기 ▾", |ui| {
+                if let Some(selected) = self.export_menu(ui, config) {
+                    action = selected;
+                    ui.close_menu();
+                }
+            });
+
+            ui.separator();
+
+            // 이미지 레이어 삽입
+            if ui.button("🖼 이미지 붙여넣기").clicked() {
+                action = ToolbarAction::PasteImageLayer;
+            }
+            if ui.button("📂 이미지 삽입").clicked() {
+                action = ToolbarAction::InsertImageFile;
+            }
+            ui.add_enabled_ui(self.image_size.is_some(), |ui| {
+                ui.menu_button("📐 이미지 ▾", |ui| {
+                    if let Some(selected) = self.image_menu(ui) {
+                        action = selected;
+                        ui.close_menu();
+                    }
+                });
+            });
 
             ui.separator();
 
@@ -147,6 +446,19 @@ impl Toolbar {
             if ui.button("HEX 복사 (Ctrl+H)").clicked() {
                 action = ToolbarAction::CopyColorHEX;
             }
+            ui.menu_button("다른 형식 ▾", |ui| {
+                if let Some(selected) = self.color_format_menu(ui, config) {
+                    action = selected;
+                    ui.close_menu();
+                }
+            });
+            ui.toggle_value(&mut self.show_pixel_info, "🔎 픽셀 정보")
+                .on_hover_text("커서 위치의 확대 화면, 좌표, 색상 표시 (스포이트 사용 중에는 항상 표시)");
+            ui.toggle_value(&mut self.show_layers, "🗂 레이어")
+                .on_hover_text("레이어 목록 (표시/숨기기, 잠금, 순서 변경, 이름 바꾸기)");
+            if ui.button("🕘 기록").on_hover_text("지난 캡처를 다시 열기, 태그, 검색, 삭제").clicked() {
+                action = ToolbarAction::ShowHistory;
+            }
 
             ui.separator();
 
@@ -156,9 +468,436 @@ impl Toolbar {
             }
         });
 
+        self.template_editor(ui.ctx(), config);
+        if let Some(resize) = self.resize_window(ui.ctx()) {
+            action = resize;
+        }
+
+        action
+    }
+
+    /// 자르기 영역 X/Y/W/H 입력과 적용/취소 버튼
+    fn crop_ui(&mut self, ui: &mut Ui) -> Option<ToolbarAction> {
+        let [width, height] = self.image_size?.map(|v| v as f32);
+        let mut min = self.crop_rect.min;
+        let mut size = self.crop_rect.size();
+
+        ui.label("영역:");
+        ui.add(egui::DragValue::new(&mut min.x).range(0.0..=width - 1.0).prefix("X: "));
+        ui.add(egui::DragValue::new(&mut min.y).range(0.0..=height - 1.0).prefix("Y: "));
+        ui.add(egui::DragValue::new(&mut size.x).range(1.0..=width - min.x).prefix("W: "));
+        ui.add(egui::DragValue::new(&mut size.y).range(1.0..=height - min.y).prefix("H: "));
+        // 위치를 옮기면 이미지 밖으로 나가지 않도록 크기를 줄임
+        let max = (min + size).min(Pos2::new(width, height));
+        self.crop_rect = Rect::from_min_max(min.round(), max.round());
+
+        if ui.button("✔ 자르기 적용 (Enter)").clicked() {
+            return Some(ToolbarAction::TransformImage(ImageOp::Crop(self.crop_rect)));
+        }
+        if ui.button("취소").clicked() {
+            self.current_tool = ToolType::Select;
+        }
+        None
+    }
+
+    /// 이미지 회전/반전/크기 메뉴
+    fn image_menu(&mut self, ui: &mut Ui) -> Option<ToolbarAction> {
+        let mut action = None;
+        for (icon, op) in [
+            ("↻", ImageOp::RotateCw),
+            ("↺", ImageOp::RotateCcw),
+            ("⇔", ImageOp::FlipHorizontal),
+            ("⇕", ImageOp::FlipVertical),
+        ] {
+            if ui.button(format!("{} {}", icon, op.label())).clicked() {
+                action = Some(ToolbarAction::TransformImage(op));
+            }
+        }
+
+        ui.separator();
+        if ui.button("✂ 자르기").clicked() {
+            self.current_tool = ToolType::Crop;
+            ui.close_menu();
+        }
+        let size = self.image_size.unwrap_or([1, 1]);
+        if ui.button("크기 조절…").on_hover_text("이미지와 도형을 함께 확대/축소").clicked() {
+            self.resize_dialog = Some(ResizeDialog::new(size, false));
+            ui.close_menu();
+        }
+        if ui.button("캔버스 크기…").on_hover_text("이미지는 그대로 두고 여백을 추가하거나 잘라냄").clicked() {
+            self.resize_dialog = Some(ResizeDialog::new(size, true));
+            ui.close_menu();
+        }
+        action
+    }
+
+    /// 크기 조절/캔버스 크기 창
+    fn resize_window(&mut self, ctx: &egui::Context) -> Option<ToolbarAction> {
+        let dialog = self.resize_dialog.as_mut()?;
+        let mut open = true;
+        let mut action = None;
+        let mut close = false;
+
+        let title = if dialog.canvas_only { "캔버스 크기" } else { "이미지 크기 조절" };
+        egui::Window::new(title)
+            .open(&mut open)
+            .resizable(false)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                let [original_w, original_h] = dialog.original.map(|v| v as f32);
+                ui.label(format!("현재 크기: {} × {}", dialog.original[0], dialog.original[1]));
+
+                egui::Grid::new("resize_dialog").num_columns(2).show(ui, |ui| {
+                    ui.label("너비");
+                    if ui.add(egui::DragValue::new(&mut dialog.size[0]).range(1..=MAX_IMAGE_SIZE).suffix(" px")).changed()
+                        && dialog.keep_aspect
+                    {
+                        dialog.size[1] = ((dialog.size[0] as f32 * original_h / original_w).round() as usize).max(1);
+                    }
+                    ui.end_row();
+
+                    ui.label("높이");
+                    if ui.add(egui::DragValue::new(&mut dialog.size[1]).range(1..=MAX_IMAGE_SIZE).suffix(" px")).changed()
+                        && dialog.keep_aspect
+                    {
+                        dialog.size[0] = ((dialog.size[1] as f32 * original_w / original_h).round() as usize).max(1);
+                    }
+                    ui.end_row();
+
+                    ui.label("");
+                    ui.checkbox(&mut dialog.keep_aspect, "비율 유지");
+                    ui.end_row();
+
+                    if dialog.canvas_only {
+                        ui.label("기준 위치");
+                        egui::Grid::new("resize_anchor").spacing([2.0, 2.0]).show(ui, |ui| {
+                            for row in ANCHORS {
+                                for anchor in row {
+                                    let icon = if dialog.anchor == anchor { "●" } else { "○" };
+                                    if ui.selectable_label(dialog.anchor == anchor, icon).clicked() {
+                                        dialog.anchor = anchor;
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+                        ui.end_row();
+                    }
+                });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("적용").clicked() {
+                        action = Some(ToolbarAction::TransformImage(dialog.op()));
+                        close = true;
+                    }
+                    if ui.button("취소").clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if !open || close {
+            self.resize_dialog = None;
+        }
+        action
+    }
+
+    /// 복사할 색
+    fn color_to_copy(&self) -> Color32 {
+        color_format::color_to_copy(self.sampled_color, self.current_color)
+    }
+
+    /// 이미지 저장 형식과 품질, 크기, 저장 위치와 파일 이름 설정
+    fn save_options_menu(&mut self, ui: &mut Ui, config: &mut Config) -> Option<ToolbarAction> {
+        let options = &mut config.export;
+        let before = *options;
+        let save = &mut config.save;
+        let save_before = save.clone();
+        let mut action = None;
+
+        ui.label("기본 형식");
+        ui.horizontal(|ui| {
+            for format in ImageFormat::ALL {
+                ui.selectable_value(&mut options.format, format, format.label());
+            }
+        });
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.label("JPEG/WebP 품질");
+            ui.add(egui::Slider::new(&mut options.quality, 1..=100));
+        })
+        .response
+        .on_hover_text("WebP는 100이면 무손실로 저장됩니다");
+        ui.horizontal(|ui| {
+            ui.label("PNG 압축");
+            let mut custom = options.png_level.is_some();
+            if ui.checkbox(&mut custom, "직접 지정").changed() {
+                options.png_level = custom.then_some(6);
+            }
+            if let Some(level) = &mut options.png_level {
+                ui.add(egui::Slider::new(level, 0..=9))
+                    .on_hover_text("0 = 압축 안 함, 9 = 최대 압축 (느림)");
+            }
+        });
+        let optimize = &mut options.png_optimize;
+        ui.checkbox(&mut optimize.enabled, "PNG 최적화")
+            .on_hover_text("색 수와 비트 깊이를 손실 없이 줄이고 여러 필터 중 가장 작은 결과로 저장 (느림)");
+        ui.add_enabled_ui(optimize.enabled, |ui| {
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut optimize.quantize, "256색으로 줄이기")
+                    .on_hover_text("색이 256개보다 많으면 팔레트로 줄임 (손실)");
+                ui.add_enabled(optimize.quantize, egui::Checkbox::new(&mut optimize.dither, "디더링"));
+            });
+        });
+        ui.horizontal(|ui| {
+            ui.label("크기");
+            ui.add(egui::Slider::new(&mut options.scale_percent, 1..=100).suffix("%"))
+                .on_hover_text("저장과 클립보드 복사에 적용");
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut options.flatten_alpha, "투명 부분 채우기")
+                .on_hover_text("투명한 부분을 배경색으로 채움 (JPEG은 항상 채움)");
+            // 메뉴 밖에 뜨는 색 선택 팝업을 누르면 메뉴가 닫히므로 견본으로 선택
+            for color in FLATTEN_BACKGROUNDS {
+                if color_swatch(ui, color, options.background == color, 16.0).clicked() {
+                    options.background = color;
+                }
+            }
+        });
+
+        ui.separator();
+        ui.checkbox(&mut save.show_dialog, "저장할 때 파일 대화상자 표시")
+            .on_hover_text("끄면 저장 폴더에 파일 이름 템플릿으로 바로 저장");
+        ui.horizontal(|ui| {
+            ui.label("저장 폴더");
+            let directory = save.directory.clone().unwrap_or_else(file_naming::pictures_dir);
+            ui.weak(directory.display().to_string());
+            if ui.button("변경…").clicked() {
+                action = Some(ToolbarAction::ChooseSaveFolder);
+            }
+            if save.directory.is_some() && ui.button("기본값").on_hover_text("사진 폴더로 되돌리기").clicked() {
+                save.directory = None;
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("파일 이름");
+            ui.add(egui::TextEdit::singleline(&mut save.template).hint_text(DEFAULT_TEMPLATE).desired_width(220.0))
+                .on_hover_text(FILE_NAME_HELP);
+        });
+        ui.weak(format!("예: {}", save.file_name(&CaptureInfo::default(), options.format.extension())));
+        ui.horizontal(|ui| {
+            ui.label("같은 이름");
+            for policy in CollisionPolicy::ALL {
+                ui.selectable_value(&mut save.collision, policy, policy.label());
+            }
+        })
+        .response
+        .on_hover_text("저장 폴더에 같은 이름의 파일이 있을 때");
+
+        if let Some(compression) = self.compression {
+            ui.separator();
+            let level = compression.level.map_or("-1".to_string(), |level| level.to_string());
+            ui.label(format!(
+                "명령줄 --comp:{},{} 옵션이 우선 적용됩니다",
+                level, compression.scale_percent
+            ));
+        }
+
+        if *options != before || *save != save_before {
+            config.mark_changed();
+        }
         action
     }
 
+    /// 파일/클립보드 이미지 열기와 최근 파일 목록
+    fn open_menu(ui: &mut Ui, config: &Config) -> Option<ToolbarAction> {
+        let mut action = None;
+        if ui
+            .add(egui::Button::new("파일 열기…").shortcut_text("Ctrl+O"))
+            .on_hover_text("이미지, .scap 세션, 이 앱으로 저장한 PNG를 열어 편집 (창에 파일을 끌어다 놓아도 됨)")
+            .clicked()
+        {
+            action = Some(ToolbarAction::OpenFile);
+        }
+        if ui
+            .button("클립보드 이미지 열기")
+            .on_hover_text("클립보드의 이미지를 새 캡처처럼 열어 편집")
+            .clicked()
+        {
+            action = Some(ToolbarAction::OpenClipboardImage);
+        }
+
+        ui.separator();
+        ui.label("최근 파일");
+        if config.recent_files.is_empty() {
+            ui.weak("없음");
+            return action;
+        }
+        for (index, path) in config.recent_files.iter().enumerate() {
+            let name = path.file_name().unwrap_or(path.as_os_str()).to_string_lossy();
+            let response = ui
+                .add_enabled(path.exists(), egui::Button::new(name))
+                .on_hover_text(path.display().to_string())
+                .on_disabled_hover_text(format!("{}\n파일이 없습니다", path.display()));
+            if response.clicked() {
+                action = Some(ToolbarAction::OpenRecent(index));
+            }
+        }
+        if ui.button("목록 지우기").clicked() {
+            action = Some(ToolbarAction::ClearRecentFiles);
+        }
+        action
+    }
+
+    /// 내보내기 메뉴 (SVG, PDF와 PDF 용지 설정)
+    fn export_menu(&mut self, ui: &mut Ui, config: &mut Config) -> Option<ToolbarAction> {
+        let mut action = None;
+        let has_image = self.image_size.is_some();
+        if ui
+            .add_enabled(has_image, egui::Button::new("SVG (벡터)"))
+            .on_hover_text("캡처는 이미지로, 도형은 벡터 편집기에서 고칠 수 있는 SVG 요소로 내보내기")
+            .clicked()
+        {
+            action = Some(ToolbarAction::ExportSvg);
+        }
+        if ui
+            .add_enabled(has_image, egui::Button::new("PDF"))
+            .on_hover_text("도형은 벡터로, 텍스트는 선택할 수 있는 글자로 PDF 한 페이지에 내보내기")
+            .clicked()
+        {
+            action = Some(ToolbarAction::ExportPdf);
+        }
+        if ui
+            .button("PDF (여러 파일 합치기)…")
+            .on_hover_text("선택한 세션/이미지 파일을 한 페이지씩 하나의 PDF로 내보내기")
+            .clicked()
+        {
+            action = Some(ToolbarAction::ExportPdfFiles);
+        }
+
+        ui.separator();
+        ui.label("PDF 용지");
+        let options = &mut config.pdf;
+        let before = *options;
+        // 메뉴 안에서 콤보 상자를 열면 메뉴가 닫히므로 선택 버튼으로 표시
+        ui.horizontal(|ui| {
+            for size in PageSize::ALL {
+                ui.selectable_value(&mut options.page_size, size, size.label());
+            }
+        });
+        ui.add_enabled_ui(options.page_size != PageSize::FitImage, |ui| {
+            ui.horizontal(|ui| {
+                for orientation in PageOrientation::ALL {
+                    ui.selectable_value(&mut options.orientation, orientation, orientation.label());
+                }
+            });
+        });
+        ui.horizontal(|ui| {
+            ui.label("여백");
+            ui.add(egui::DragValue::new(&mut options.margin_mm).range(0.0..=50.0).speed(0.5).suffix(" mm"));
+        });
+        if *options != before {
+            config.mark_changed();
+        }
+        action
+    }
+
+    /// 색상 복사 형식 메뉴 (기본 형식, 사용자 템플릿, 템플릿 편집)
+    fn color_format_menu(&mut self, ui: &mut Ui, config: &Config) -> Option<ToolbarAction> {
+        let color = self.color_to_copy();
+        let mut action = None;
+
+        for format in ColorFormat::ALL {
+            let button = egui::Button::new(format.label()).shortcut_text(format.shortcut().unwrap_or_default());
+            if ui.add(button).on_hover_text(format.format(color)).clicked() {
+                action = Some(ToolbarAction::CopyColorFormat(format));
+            }
+        }
+
+        if !config.color_templates.is_empty() {
+            ui.separator();
+            for (i, template) in config.color_templates.iter().enumerate() {
+                let button = egui::Button::new(&template.name).shortcut_text(&template.shortcut);
+                if ui.add(button).on_hover_text(expand_template(&template.template, color)).clicked() {
+                    action = Some(ToolbarAction::CopyColorTemplate(i));
+                }
+            }
+        }
+
+        ui.separator();
+        if ui.button("템플릿 편집…").clicked() {
+            self.show_template_editor = true;
+            ui.close_menu();
+        }
+
+        action
+    }
+
+    /// 사용자 정의 색상 복사 템플릿 편집 창
+    fn template_editor(&mut self, ctx: &egui::Context, config: &mut Config) {
+        let color = self.color_to_copy();
+        let mut changed = false;
+        let mut remove = None;
+
+        egui::Window::new("색상 복사 템플릿")
+            .open(&mut self.show_template_editor)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(TEMPLATE_HELP);
+                ui.separator();
+
+                egui::Grid::new("color_templates").num_columns(5).striped(true).show(ui, |ui| {
+                    ui.label("이름");
+                    ui.label("템플릿");
+                    ui.label("단축키");
+                    ui.label("미리 보기");
+                    ui.end_row();
+
+                    for (i, template) in config.color_templates.iter_mut().enumerate() {
+                        changed |= ui.add(egui::TextEdit::singleline(&mut template.name).desired_width(100.0)).changed();
+                        changed |= ui.add(egui::TextEdit::singleline(&mut template.template).desired_width(200.0)).changed();
+                        ui.horizontal(|ui| {
+                            changed |= ui
+                                .add(egui::TextEdit::singleline(&mut template.shortcut).hint_text("Ctrl+Shift+1").desired_width(100.0))
+                                .changed();
+                            if !template.shortcut.trim().is_empty() && template.keyboard_shortcut().is_none() {
+                                ui.colored_label(Color32::RED, "⚠").on_hover_text("예: Ctrl+Shift+1, Alt+L");
+                            }
+                        });
+                        ui.monospace(expand_template(&template.template, color));
+                        if ui.small_button("🗑").clicked() {
+                            remove = Some(i);
+                        }
+                        ui.end_row();
+                    }
+                });
+
+                if ui.button("+ 템플릿 추가").clicked() {
+                    config.color_templates.push(ColorTemplate {
+                        name: format!("템플릿 {}", config.color_templates.len() + 1),
+                        template: "rgba({r}, {g}, {b}, {a})".to_string(),
+                        shortcut: String::new(),
+                    });
+                    changed = true;
+                }
+            });
+
+        if let Some(i) = remove {
+            config.color_templates.remove(i);
+            changed = true;
+        }
+        if changed {
+            config.mark_changed();
+        }
+    }
+
+    /// 채우기 불투명도가 적용된 채우기 색
+    pub fn fill_color(&self) -> Option<Color32> {
+        self.current_fill.map(|color| color.gamma_multiply(self.fill_alpha))
+    }
+
     /// 도구 버튼 생성
     fn tool_button(
         &self,
@@ -173,12 +912,64 @@ impl Toolbar {
     }
 }
 
+/// 채우기 기본 불투명도 (외곽선이 보이도록 반투명)
+const DEFAULT_FILL_ALPHA: f32 = 0.3;
+
+/// 크기 조절 창에서 입력할 수 있는 최대 크기 (px)
+const MAX_IMAGE_SIZE: usize = 16384;
+
+/// 캔버스 크기 변경 기준 위치 (3×3 격자)
+const ANCHORS: [[Align2; 3]; 3] = [
+    [Align2::LEFT_TOP, Align2::CENTER_TOP, Align2::RIGHT_TOP],
+    [Align2::LEFT_CENTER, Align2::CENTER_CENTER, Align2::RIGHT_CENTER],
+    [Align2::LEFT_BOTTOM, Align2::CENTER_BOTTOM, Align2::RIGHT_BOTTOM],
+];
+
+/// 크기 조절/캔버스 크기 창의 입력 상태
+struct ResizeDialog {
+    /// true면 캔버스 크기 변경, false면 이미지 크기 조절
+    canvas_only: bool,
+    original: [usize; 2],
+    size: [usize; 2],
+    keep_aspect: bool,
+    anchor: Align2,
+}
+
+impl ResizeDialog {
+    fn new(size: [usize; 2], canvas_only: bool) -> Self {
+        Self {
+            canvas_only,
+            original: size,
+            size,
+            keep_aspect: !canvas_only,
+            anchor: Align2::CENTER_CENTER,
+        }
+    }
+
+    fn op(&self) -> ImageOp {
+        if self.canvas_only {
+            ImageOp::ResizeCanvas { size: self.size, anchor: self.anchor }
+        } else {
+            ImageOp::Scale(self.size)
+        }
+    }
+}
+
 impl Default for Toolbar {
     fn default() -> Self {
         Self::new()
     }
 }
 
+/// 투명 부분을 채울 배경색 견본
+const FLATTEN_BACKGROUNDS: [Color32; 5] = [
+    Color32::WHITE,
+    Color32::from_gray(240),
+    Color32::from_gray(128),
+    Color32::from_gray(32),
+    Color32::BLACK,
+];
+
 /// 도구 모음에서 발생한 액션
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ToolbarAction {
@@ -187,7 +978,35 @@ pub enum ToolbarAction {
     Redo,
     Save,
     CopyToClipboard,
+    /// 편집 가능한 세션 파일 (.scap)
+    SaveSession,
+    /// 이미지나 세션 파일을 선택해 열기
+    OpenFile,
+    /// 클립보드의 이미지를 새 캡처로 열기
+    OpenClipboardImage,
+    /// 최근 파일 (`Config::recent_files` 인덱스)
+    OpenRecent(usize),
+    ClearRecentFiles,
+    /// 도형을 벡터로 유지한 SVG 문서
+    ExportSvg,
+    /// 현재 캡처를 PDF 한 페이지로
+    ExportPdf,
+    /// 선택한 여러 파일을 한 페이지씩 PDF로
+    ExportPdfFiles,
+    /// 저장 옵션의 기본 저장 폴더 선택
+    ChooseSaveFolder,
+    /// 캡처 기록 갤러리 열기
+    ShowHistory,
+    PasteImageLayer,
+    InsertImageFile,
     CopyColorRGB,
     CopyColorHEX,
+    CopyColorFormat(ColorFormat),
+    /// 설정의 사용자 템플릿 (`Config::color_templates` 인덱스)
+    CopyColorTemplate(usize),
+    UseSampledColor,
+    RenumberBadges,
+    /// 자르기, 크기 조절, 회전, 반전
+    TransformImage(ImageOp),
     Close,
 }
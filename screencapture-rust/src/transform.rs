@@ -0,0 +1,266 @@
+use crate::clipboard::{color_image_to_image_buffer, image_buffer_to_color_image};
+use egui::{Align2, Color32, ColorImage, Pos2, Rect, Vec2};
+use image::imageops::{self, FilterType};
+
+/// 배경 이미지 변환 (자르기, 크기 조절, 회전, 반전)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageOp {
+    /// 이미지 좌표의 영역만 남김
+    Crop(Rect),
+    /// 새 크기로 다시 샘플링 (도형도 같은 비율로 확대/축소)
+    Scale([usize; 2]),
+    /// 이미지는 그대로 두고 캔버스 크기만 변경 (늘어난 부분은 투명)
+    ResizeCanvas { size: [usize; 2], anchor: Align2 },
+    RotateCw,
+    RotateCcw,
+    FlipHorizontal,
+    FlipVertical,
+}
+
+impl ImageOp {
+    /// 상태 표시줄에 표시할 이름
+    pub fn label(self) -> &'static str {
+        match self {
+            ImageOp::Crop(_) => "자르기",
+            ImageOp::Scale(_) => "크기 조절",
+            ImageOp::ResizeCanvas { .. } => "캔버스 크기 변경",
+            ImageOp::RotateCw => "오른쪽으로 90° 회전",
+            ImageOp::RotateCcw => "왼쪽으로 90° 회전",
+            ImageOp::FlipHorizontal => "좌우 반전",
+            ImageOp::FlipVertical => "상하 반전",
+        }
+    }
+
+    /// 이미지에 적용한 결과와 도형 좌표에 적용할 변환 (결과가 비면 None)
+    pub fn apply(self, image: &ColorImage) -> Option<(ColorImage, GeometryTransform)> {
+        let [width, height] = image.size.map(|v| v as f32);
+        let (result, transform) = match self {
+            ImageOp::Crop(rect) => {
+                let bounds = Rect::from_min_size(Pos2::ZERO, egui::vec2(width, height));
+                let rect = pixel_rect(rect.intersect(bounds));
+                (crop(image, rect, Color32::TRANSPARENT), GeometryTransform::translate(-rect.min.to_vec2()))
+            }
+            ImageOp::Scale(size) => {
+                if size.contains(&0) {
+                    return None;
+                }
+                let resized = imageops::resize(
+                    &color_image_to_image_buffer(image),
+                    size[0] as u32,
+                    size[1] as u32,
+                    FilterType::Lanczos3,
+                );
+                let scale = egui::vec2(size[0] as f32 / width, size[1] as f32 / height);
+                (image_buffer_to_color_image(&resized), GeometryTransform::scale(scale))
+            }
+            ImageOp::ResizeCanvas { size, anchor } => {
+                // 기준 위치(anchor)에 맞춰 원본을 놓고 나머지는 투명하게 채움
+                let new_size = egui::vec2(size[0] as f32, size[1] as f32);
+                let offset = ((new_size - egui::vec2(width, height)) * anchor_factor(anchor)).round();
+                let rect = Rect::from_min_size((-offset).to_pos2(), new_size);
+                (crop(image, rect, Color32::TRANSPARENT), GeometryTransform::translate(offset))
+            }
+            ImageOp::RotateCw => {
+                // (x, y) → (H - y, x)
+                let transform = GeometryTransform::oriented(egui::vec2(0.0, 1.0), egui::vec2(-1.0, 0.0), egui::vec2(height, 0.0));
+                (reorient(image, &transform), transform)
+            }
+            ImageOp::RotateCcw => {
+                // (x, y) → (y, W - x)
+                let transform = GeometryTransform::oriented(egui::vec2(0.0, -1.0), egui::vec2(1.0, 0.0), egui::vec2(0.0, width));
+                (reorient(image, &transform), transform)
+            }
+            ImageOp::FlipHorizontal => {
+                let transform = GeometryTransform::oriented(egui::vec2(-1.0, 0.0), egui::vec2(0.0, 1.0), egui::vec2(width, 0.0));
+                (reorient(image, &transform), transform)
+            }
+            ImageOp::FlipVertical => {
+                let transform = GeometryTransform::oriented(egui::vec2(1.0, 0.0), egui::vec2(0.0, -1.0), egui::vec2(0.0, height));
+                (reorient(image, &transform), transform)
+            }
+        };
+        (!result.size.contains(&0)).then_some((result, transform))
+    }
+}
+
+/// 이미지 좌표 변환 (90° 단위 회전/반전, 축별 배율, 이동)
+///
+/// 점 p는 `x_axis * p.x + y_axis * p.y + offset`으로 옮겨집니다.
+#[derive(Debug, Clone, Copy)]
+pub struct GeometryTransform {
+    x_axis: Vec2,
+    y_axis: Vec2,
+    offset: Vec2,
+}
+
+impl GeometryTransform {
+    fn oriented(x_axis: Vec2, y_axis: Vec2, offset: Vec2) -> Self {
+        Self { x_axis, y_axis, offset }
+    }
+
+    fn translate(offset: Vec2) -> Self {
+        Self::oriented(egui::vec2(1.0, 0.0), egui::vec2(0.0, 1.0), offset)
+    }
+
+    fn scale(scale: Vec2) -> Self {
+        Self::oriented(egui::vec2(scale.x, 0.0), egui::vec2(0.0, scale.y), Vec2::ZERO)
+    }
+
+    /// 점 변환
+    pub fn apply(&self, p: Pos2) -> Pos2 {
+        (self.x_axis * p.x + self.y_axis * p.y + self.offset).to_pos2()
+    }
+
+    /// 사각형 변환 (90° 단위 변환이므로 결과도 축 정렬 사각형)
+    pub fn apply_rect(&self, rect: Rect) -> Rect {
+        Rect::from_two_pos(self.apply(rect.min), self.apply(rect.max))
+    }
+
+    /// 크기 변환 (회전하면 가로/세로가 바뀜)
+    pub fn apply_size(&self, size: Vec2) -> Vec2 {
+        (self.x_axis * size.x + self.y_axis * size.y).abs()
+    }
+
+    /// 선 두께, 글자 크기 등 방향이 없는 길이에 적용할 배율
+    pub fn length_scale(&self) -> f32 {
+        (self.x_axis.x * self.y_axis.y - self.x_axis.y * self.y_axis.x).abs().sqrt()
+    }
+
+    /// 회전이나 반전이 포함되어 있는지 여부
+    pub fn changes_orientation(&self) -> bool {
+        self.x_axis.x <= 0.0 || self.y_axis.y <= 0.0
+    }
+}
+
+/// 이미지 픽셀을 변환의 방향(회전/반전)에 맞게 재배치 (배율과 이동은 무시)
+pub fn reorient(image: &ColorImage, transform: &GeometryTransform) -> ColorImage {
+    let [width, height] = image.size;
+    let x_axis = transform.x_axis.normalized().round();
+    let y_axis = transform.y_axis.normalized().round();
+    let swapped = x_axis.x == 0.0;
+    let size = if swapped { [height, width] } else { [width, height] };
+
+    // 원본 (0, 0) 모서리가 옮겨질 위치 (결과가 0부터 시작하도록 이동)
+    let map = |x: f32, y: f32| x_axis * x + y_axis * y;
+    let min = [map(0.0, 0.0), map(width as f32, 0.0), map(0.0, height as f32), map(width as f32, height as f32)]
+        .into_iter()
+        .fold(egui::vec2(f32::MAX, f32::MAX), |acc, v| acc.min(v));
+
+    let mut pixels = vec![Color32::TRANSPARENT; width * height];
+    for (i, color) in image.pixels.iter().enumerate() {
+        let p = map((i % width) as f32 + 0.5, (i / width) as f32 + 0.5) - min;
+        let (x, y) = (p.x as usize, p.y as usize);
+        pixels[y * size[0] + x] = *color;
+    }
+    ColorImage { size, pixels }
+}
+
+/// 이미지의 `rect` 영역을 잘라냄 (원본 밖은 `fill`로 채움)
+fn crop(image: &ColorImage, rect: Rect, fill: Color32) -> ColorImage {
+    let [width, height] = image.size;
+    let (x0, y0) = (rect.min.x as isize, rect.min.y as isize);
+    let size = [rect.width().max(0.0) as usize, rect.height().max(0.0) as usize];
+
+    let mut pixels = Vec::with_capacity(size[0] * size[1]);
+    for y in 0..size[1] as isize {
+        for x in 0..size[0] as isize {
+            let (sx, sy) = (x0 + x, y0 + y);
+            let inside = sx >= 0 && sy >= 0 && (sx as usize) < width && (sy as usize) < height;
+            pixels.push(if inside { image.pixels[sy as usize * width + sx as usize] } else { fill });
+        }
+    }
+    ColorImage { size, pixels }
+}
+
+/// 픽셀 경계에 맞춘 사각형
+pub fn pixel_rect(rect: Rect) -> Rect {
+    Rect::from_min_max(rect.min.round(), rect.max.round())
+}
+
+/// 기준 위치별로 늘어난 크기 중 왼쪽/위쪽에 들어가는 비율
+fn anchor_factor(anchor: Align2) -> Vec2 {
+    let factor = |align: egui::Align| match align {
+        egui::Align::Min => 0.0,
+        egui::Align::Center => 0.5,
+        egui::Align::Max => 1.0,
+    };
+    egui::vec2(factor(anchor.x()), factor(anchor.y()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 픽셀마다 다른 색을 가진 3x2 이미지
+    fn test_image() -> ColorImage {
+        ColorImage {
+            size: [3, 2],
+            pixels: (0..6).map(|i| Color32::from_rgb(i * 40, 255 - i * 40, i)).collect(),
+        }
+    }
+
+    fn pixel(image: &ColorImage, x: usize, y: usize) -> Color32 {
+        image.pixels[y * image.size[0] + x]
+    }
+
+    #[test]
+    fn rotate_cw_then_ccw_restores_image() {
+        let image = test_image();
+        let (rotated, _) = ImageOp::RotateCw.apply(&image).unwrap();
+        assert_eq!(rotated.size, [2, 3]);
+        let (restored, _) = ImageOp::RotateCcw.apply(&rotated).unwrap();
+        assert_eq!(restored.size, image.size);
+        assert_eq!(restored.pixels, image.pixels);
+    }
+
+    #[test]
+    fn flip_twice_restores_image() {
+        let image = test_image();
+        for op in [ImageOp::FlipHorizontal, ImageOp::FlipVertical] {
+            let (flipped, _) = op.apply(&image).unwrap();
+            assert_ne!(flipped.pixels, image.pixels);
+            let (restored, _) = op.apply(&flipped).unwrap();
+            assert_eq!(restored.pixels, image.pixels);
+        }
+    }
+
+    #[test]
+    fn geometry_follows_pixels() {
+        let image = test_image();
+        // 원본 왼쪽 위 픽셀이 옮겨질 위치
+        let cases = [
+            (ImageOp::RotateCw, [2, 3], (1, 0)),
+            (ImageOp::RotateCcw, [2, 3], (0, 2)),
+            (ImageOp::FlipHorizontal, [3, 2], (2, 0)),
+            (ImageOp::FlipVertical, [3, 2], (0, 1)),
+            (ImageOp::Crop(Rect::from_min_max(Pos2::new(-1.0, 0.0), Pos2::new(2.0, 2.0))), [2, 2], (0, 0)),
+            (ImageOp::ResizeCanvas { size: [5, 4], anchor: Align2::CENTER_CENTER }, [5, 4], (1, 1)),
+        ];
+        for (op, size, (cx, cy)) in cases {
+            let (result, transform) = op.apply(&image).unwrap();
+            assert_eq!(result.size, size, "{:?}", op);
+
+            let corner = transform.apply(Pos2::new(0.5, 0.5));
+            assert_eq!((corner.x as usize, corner.y as usize), (cx, cy), "{:?}", op);
+            assert_eq!(pixel(&result, cx, cy), pixel(&image, 0, 0), "{:?}", op);
+
+            // 결과에 남은 모든 픽셀은 변환한 좌표의 원본 픽셀과 같아야 함
+            for y in 0..2 {
+                for x in 0..3 {
+                    let p = transform.apply(Pos2::new(x as f32 + 0.5, y as f32 + 0.5));
+                    if p.x >= 0.0 && p.y >= 0.0 && (p.x as usize) < size[0] && (p.y as usize) < size[1] {
+                        assert_eq!(pixel(&result, p.x as usize, p.y as usize), pixel(&image, x, y), "{:?}", op);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn crop_outside_image_is_none() {
+        let image = test_image();
+        let outside = Rect::from_min_max(Pos2::new(5.0, 5.0), Pos2::new(8.0, 8.0));
+        assert!(ImageOp::Crop(outside).apply(&image).is_none());
+        assert!(ImageOp::Scale([0, 2]).apply(&image).is_none());
+    }
+}